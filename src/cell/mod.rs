@@ -5,4 +5,4 @@ mod cell;
 mod unsafe_cell;
 
 pub use self::cell::Cell;
-pub use self::unsafe_cell::{ConstPtr, MutPtr, UnsafeCell};
+pub use self::unsafe_cell::{ConstGuard, ConstPtr, MutGuard, MutPtr, Optimistic, UnsafeCell};
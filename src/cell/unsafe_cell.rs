@@ -1,5 +1,8 @@
 use crate::rt;
 
+use std::marker::PhantomData;
+use std::ops;
+
 /// A checked version of `std::cell::UnsafeCell`.
 ///
 /// Instead of providing a `get()` API, this version of `UnsafeCell` provides
@@ -101,6 +104,49 @@ pub struct MutPtr<T: ?Sized> {
     ptr: *mut T,
 }
 
+/// A [`ConstPtr`] whose lifetime is bound to the [`UnsafeCell`] it was
+/// produced from.
+///
+/// [`ConstGuard`]s are produced by the [`UnsafeCell::borrow`] method and
+/// dereference to a [`ConstPtr`]. Unlike a [`ConstPtr`], the borrow checker
+/// prevents a `ConstGuard` from outliving its cell.
+#[derive(Debug)]
+pub struct ConstGuard<'a, T: ?Sized> {
+    ptr: ConstPtr<T>,
+    _cell: PhantomData<&'a UnsafeCell<T>>,
+}
+
+/// A [`MutPtr`] whose lifetime is bound to the [`UnsafeCell`] it was produced
+/// from.
+///
+/// [`MutGuard`]s are produced by the [`UnsafeCell::borrow_mut`] method and
+/// dereference to a [`MutPtr`]. Unlike a [`MutPtr`], the borrow checker
+/// prevents a `MutGuard` from outliving its cell.
+#[derive(Debug)]
+pub struct MutGuard<'a, T: ?Sized> {
+    ptr: MutPtr<T>,
+    _cell: PhantomData<&'a UnsafeCell<T>>,
+}
+
+/// The result of an optimistic read of an [`UnsafeCell`], which may have
+/// raced with a write.
+///
+/// [`Optimistic`] values are produced by the [`UnsafeCell::with_optimistic`]
+/// method. The result can only be obtained with [`Optimistic::validate`],
+/// which checks that the read did not race with a write. Dropping it discards
+/// the result without any check.
+///
+/// Several optimistic reads, e.g. of the cells protected by the same version
+/// counter, can be [joined] to be validated at once.
+///
+/// [joined]: Optimistic::join
+#[derive(Debug)]
+#[must_use = "the result of an optimistic read must be validated to be used"]
+pub struct Optimistic<R> {
+    reads: Vec<rt::cell::OptimisticRead>,
+    value: R,
+}
+
 impl<T> UnsafeCell<T> {
     /// Constructs a new instance of `UnsafeCell` which will wrap the specified value.
     #[track_caller]
@@ -200,6 +246,183 @@ impl<T: ?Sized> UnsafeCell<T> {
             ptr: self.data.get(),
         }
     }
+
+    /// Read the wrapped value optimistically, deferring the check for
+    /// concurrent mutable accesses.
+    ///
+    /// This is the read side of a seqlock: the cell is read without
+    /// excluding writers, and the result is only used once a version counter
+    /// confirms that no write happened concurrently. The read is not tracked
+    /// as an immutable access, so writes racing with it are not reported.
+    /// Instead, the returned [`Optimistic`] must be [validated] after the
+    /// version counter is re-checked. Loom considers the read to last until
+    /// then, and panics if a write to the cell up to that point does not
+    /// happen-before the current thread.
+    ///
+    /// The closure should only copy the data out of the cell, as the read may
+    /// race with a write.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use loom::cell::UnsafeCell;
+    /// use loom::sync::atomic::AtomicUsize;
+    /// use std::sync::atomic::Ordering::SeqCst;
+    ///
+    /// loom::model(|| {
+    ///     let seq = AtomicUsize::new(0);
+    ///     let cell = UnsafeCell::new(1);
+    ///
+    ///     let version = seq.load(SeqCst);
+    ///     let read = cell.with_optimistic(|ptr| unsafe { *ptr });
+    ///
+    ///     if version % 2 == 0 && seq.load(SeqCst) == version {
+    ///         assert_eq!(1, read.validate());
+    ///     }
+    /// });
+    /// ```
+    ///
+    /// [validated]: Optimistic::validate
+    #[track_caller]
+    pub fn with_optimistic<F, R>(&self, f: F) -> Optimistic<R>
+    where
+        F: FnOnce(*const T) -> R,
+    {
+        Optimistic {
+            reads: vec![self.state.optimistic_read(location!())],
+            value: f(self.data.get() as *const T),
+        }
+    }
+
+    /// Get an immutable pointer to the wrapped value, bound to the lifetime of
+    /// the cell.
+    ///
+    /// This behaves like [`get`], but the returned [`ConstGuard`] borrows the
+    /// cell, so it cannot dangle:
+    ///
+    /// ```compile_fail,E0597
+    /// # loom::model(|| {
+    /// use loom::cell::UnsafeCell;
+    ///
+    /// let guard = {
+    ///     let cell = UnsafeCell::new(1);
+    ///     cell.borrow()
+    /// };
+    /// # drop(guard);
+    /// # })
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the access is not valid under the Rust memory
+    /// model.
+    ///
+    /// [`get`]: UnsafeCell::get
+    #[track_caller]
+    pub fn borrow(&self) -> ConstGuard<'_, T> {
+        ConstGuard {
+            ptr: self.get(),
+            _cell: PhantomData,
+        }
+    }
+
+    /// Get a mutable pointer to the wrapped value, bound to the lifetime of the
+    /// cell.
+    ///
+    /// This behaves like [`get_mut`], but the returned [`MutGuard`] borrows the
+    /// cell, so it cannot dangle:
+    ///
+    /// ```compile_fail,E0597
+    /// # loom::model(|| {
+    /// use loom::cell::UnsafeCell;
+    ///
+    /// let guard = {
+    ///     let cell = UnsafeCell::new(1);
+    ///     cell.borrow_mut()
+    /// };
+    /// # drop(guard);
+    /// # })
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the access is not valid under the Rust memory
+    /// model.
+    ///
+    /// [`get_mut`]: UnsafeCell::get_mut
+    #[track_caller]
+    pub fn borrow_mut(&self) -> MutGuard<'_, T> {
+        MutGuard {
+            ptr: self.get_mut(),
+            _cell: PhantomData,
+        }
+    }
+}
+
+impl<T: ?Sized> ops::Deref for ConstGuard<'_, T> {
+    type Target = ConstPtr<T>;
+
+    fn deref(&self) -> &ConstPtr<T> {
+        &self.ptr
+    }
+}
+
+impl<T: ?Sized> ops::Deref for MutGuard<'_, T> {
+    type Target = MutPtr<T>;
+
+    fn deref(&self) -> &MutPtr<T> {
+        &self.ptr
+    }
+}
+
+impl<R> Optimistic<R> {
+    /// Returns the result of the optimistic read.
+    ///
+    /// This must be called once the read has been re-validated, e.g. by
+    /// checking that a version counter did not change since before the read.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the read raced with a write to the cell,
+    /// that is if a write to the cell since it was created does not
+    /// happen-before the current thread.
+    #[track_caller]
+    pub fn validate(self) -> R {
+        let location = location!();
+
+        for read in self.reads {
+            read.validate(location);
+        }
+
+        self.value
+    }
+
+    /// Combines two optimistic reads, so that they are validated at once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use loom::cell::UnsafeCell;
+    ///
+    /// loom::model(|| {
+    ///     let a = UnsafeCell::new(1);
+    ///     let b = UnsafeCell::new(2);
+    ///
+    ///     let read = a
+    ///         .with_optimistic(|ptr| unsafe { *ptr })
+    ///         .join(b.with_optimistic(|ptr| unsafe { *ptr }));
+    ///
+    ///     assert_eq!((1, 2), read.validate());
+    /// });
+    /// ```
+    pub fn join<S>(mut self, other: Optimistic<S>) -> Optimistic<(R, S)> {
+        self.reads.extend(other.reads);
+
+        Optimistic {
+            reads: self.reads,
+            value: (self.value, other.value),
+        }
+    }
 }
 
 impl<T: Default> Default for UnsafeCell<T> {
@@ -0,0 +1,75 @@
+//! Assertions about the state of the current execution.
+
+/// Atomic types supported by the assertions of this module.
+///
+/// This trait is sealed and implemented by the atomic integer types of
+/// [`loom::sync::atomic`](crate::sync::atomic).
+pub trait Atomic: sealed::Sealed {}
+
+pub(crate) mod sealed {
+    pub trait Sealed {
+        type Value: std::fmt::Debug + Copy;
+
+        fn is_visible_to_all(&self, value: Self::Value) -> bool;
+    }
+}
+
+/// Asserts that the newest store of `value` to `atomic` is not yet visible to
+/// every other thread.
+///
+/// The assertion passes if, in the current schedule, at least one other
+/// thread that is still running could load a value older than `value`, i.e.
+/// it did not observe that store, or a later one, and does not
+/// happen-after it. This checks that relaxed code does not rely on its stores
+/// becoming visible immediately.
+///
+/// This is neither a branch point nor an access to the atomic.
+///
+/// # Panics
+///
+/// Panics if `value` is not in the store history of `atomic`, or if every
+/// other running thread already observed it, including when there is no other
+/// running thread.
+///
+/// # Examples
+///
+/// ```
+/// use loom::sync::atomic::{AtomicBool, AtomicUsize};
+/// use loom::sync::Arc;
+/// use loom::thread;
+///
+/// use std::sync::atomic::Ordering::{Acquire, Relaxed, Release};
+///
+/// loom::model(|| {
+///     let data = Arc::new(AtomicUsize::new(0));
+///     let ready = Arc::new(AtomicBool::new(false));
+///
+///     let th = {
+///         let (data, ready) = (data.clone(), ready.clone());
+///
+///         thread::spawn(move || {
+///             while !ready.load(Acquire) {
+///                 thread::yield_now();
+///             }
+///
+///             assert_eq!(1, data.load(Relaxed));
+///         })
+///     };
+///
+///     data.store(1, Relaxed);
+///
+///     // The spawned thread may only rely on `data` once `ready` is set.
+///     loom::debug::assert_not_yet_visible(&*data, 1);
+///
+///     ready.store(true, Release);
+///     th.join().unwrap();
+/// });
+/// ```
+#[track_caller]
+pub fn assert_not_yet_visible<A: Atomic>(atomic: &A, value: A::Value) {
+    assert!(
+        !atomic.is_visible_to_all(value),
+        "the store of {:?} is already visible to all other threads",
+        value
+    );
+}
@@ -0,0 +1,185 @@
+use crate::rt;
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+
+/// Runs a set of futures cooperatively on the current thread.
+///
+/// Spawned futures run until they all complete. Whenever several of them are
+/// ready to be polled, loom explores each choice of the one polled next, so
+/// that tests can catch bugs depending on the order in which a cooperative
+/// executor polls its tasks, e.g. a task starving another one.
+///
+/// # Examples
+///
+/// ```
+/// use loom::future::LocalSet;
+///
+/// use std::cell::RefCell;
+/// use std::rc::Rc;
+///
+/// loom::model(|| {
+///     let order = Rc::new(RefCell::new(vec![]));
+///     let mut set = LocalSet::new();
+///
+///     for i in 0..2 {
+///         let order = order.clone();
+///         set.spawn(async move { order.borrow_mut().push(i) });
+///     }
+///
+///     set.run();
+///
+///     // Both tasks ran, in either order
+///     assert_eq!(2, order.borrow().len());
+/// });
+/// ```
+pub struct LocalSet {
+    tasks: Vec<Task>,
+    shared: Arc<Shared>,
+}
+
+struct Task {
+    /// `None` once the future completed
+    future: Option<Pin<Box<dyn Future<Output = ()>>>>,
+    waker: Waker,
+}
+
+struct Shared {
+    /// Whether each task was woken since it was last polled
+    woken: Mutex<Vec<bool>>,
+
+    /// Guards `woken`, so that wakes from other threads synchronize with
+    /// polling the task.
+    lock: rt::Mutex,
+
+    /// Unblocks the executor when a task is woken
+    notify: rt::Notify,
+}
+
+struct TaskWaker {
+    shared: Arc<Shared>,
+    index: usize,
+}
+
+impl LocalSet {
+    /// Creates a new, empty, `LocalSet`.
+    pub fn new() -> LocalSet {
+        LocalSet {
+            tasks: vec![],
+            shared: Arc::new(Shared {
+                woken: Mutex::new(vec![]),
+                lock: rt::Mutex::new(false),
+                notify: rt::Notify::new(false, true),
+            }),
+        }
+    }
+
+    /// Spawns `future` onto the set. It is first polled by [`run`].
+    ///
+    /// [`run`]: LocalSet::run
+    pub fn spawn<F>(&mut self, future: F)
+    where
+        F: Future<Output = ()> + 'static,
+    {
+        let index = self.tasks.len();
+
+        // Spawned tasks are ready to be polled
+        self.shared.woken.lock().unwrap().push(true);
+
+        self.tasks.push(Task {
+            future: Some(Box::pin(future)),
+            waker: Waker::from(Arc::new(TaskWaker {
+                shared: self.shared.clone(),
+                index,
+            })),
+        });
+    }
+
+    /// Polls the spawned futures until they all complete, blocking the
+    /// current thread while none of them is ready.
+    #[track_caller]
+    pub fn run(mut self) {
+        let location = location!();
+
+        while self.tasks.iter().any(|task| task.future.is_some()) {
+            let index = match self.next_ready(location) {
+                Some(index) => index,
+                None => {
+                    self.shared.notify.wait(location);
+                    continue;
+                }
+            };
+
+            let task = &mut self.tasks[index];
+            let mut cx = Context::from_waker(&task.waker);
+
+            if let Some(future) = task.future.as_mut() {
+                if let Poll::Ready(()) = future.as_mut().poll(&mut cx) {
+                    task.future = None;
+                }
+            }
+        }
+    }
+
+    /// Picks the next task to poll among the ones that were woken, exploring
+    /// each choice.
+    fn next_ready(&self, location: rt::Location) -> Option<usize> {
+        self.shared.lock.acquire_lock(location);
+
+        let mut woken = self.shared.woken.lock().unwrap();
+
+        let ready: Vec<_> = woken
+            .iter()
+            .enumerate()
+            .filter(|&(index, &woken)| woken && self.tasks[index].future.is_some())
+            .map(|(index, _)| index)
+            .collect();
+
+        let index = if ready.is_empty() {
+            None
+        } else {
+            let index = ready[rt::branch_choice(ready.len())];
+            woken[index] = false;
+            Some(index)
+        };
+
+        drop(woken);
+        self.shared.lock.release_lock();
+
+        index
+    }
+}
+
+impl Default for LocalSet {
+    fn default() -> Self {
+        LocalSet::new()
+    }
+}
+
+impl fmt::Debug for LocalSet {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("LocalSet")
+            .field("tasks", &self.tasks.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    #[track_caller]
+    fn wake_by_ref(self: &Arc<Self>) {
+        let location = location!();
+
+        self.shared.lock.acquire_lock(location);
+        self.shared.woken.lock().unwrap()[self.index] = true;
+        self.shared.lock.release_lock();
+
+        self.shared.notify.notify(location);
+    }
+}
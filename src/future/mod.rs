@@ -1,8 +1,12 @@
 //! Future related synchronization primitives.
 
 mod atomic_waker;
+mod local_set;
+mod spawn;
 
 pub use self::atomic_waker::AtomicWaker;
+pub use self::local_set::LocalSet;
+pub use self::spawn::{spawn, JoinHandle};
 
 use crate::rt;
 use crate::sync::Arc;
@@ -13,6 +17,14 @@ use std::pin::pin;
 use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
 
 /// Block the current thread, driving `f` to completion.
+///
+/// # Panics
+///
+/// Panics if `f` keeps waking itself up without completing while no thread
+/// makes progress, e.g. by storing to an atomic, as such a future would
+/// otherwise be polled until the model exceeds its maximum number of branches.
+/// The number of polls allowed is a quarter of the model's `max_branches`,
+/// each poll taking at least two branches.
 #[track_caller]
 pub fn block_on<F>(f: F) -> F::Output
 where
@@ -37,10 +49,35 @@ where
             Poll::Pending => {}
         }
 
+        check_poll_budget(notify.is_notified());
+
         notify.wait(location!());
     }
 }
 
+/// Counts the polls of a future that woke itself up, see `block_on`.
+fn check_poll_budget(woke_itself: bool) {
+    rt::execution(|execution| {
+        let budget = execution.path.max_branches() / 4;
+        let thread = execution.threads.active_mut();
+
+        if !woke_itself {
+            // Another thread must wake the future up
+            thread.polls_since_progress = 0;
+            return;
+        }
+
+        thread.polls_since_progress += 1;
+
+        if thread.polls_since_progress >= budget && !std::thread::panicking() {
+            panic!(
+                "future polled {} times without progress; possible livelock",
+                thread.polls_since_progress
+            );
+        }
+    });
+}
+
 pub(super) fn waker_vtable() -> &'static RawWakerVTable {
     &RawWakerVTable::new(
         clone_arc_raw,
@@ -0,0 +1,76 @@
+use super::block_on;
+use crate::thread;
+
+use std::fmt;
+use std::future::Future;
+
+/// Spawns a new task, driving `f` to completion on a new loom thread.
+///
+/// The task runs [`block_on`] on its own thread, so tasks spawned this way run
+/// concurrently, and loom explores their interleavings as it does for threads.
+/// A task blocks its thread while its future is pending, until it is woken,
+/// e.g. through an [`AtomicWaker`](super::AtomicWaker) by another task.
+///
+/// Like [`thread::spawn`], each task takes one of the
+/// [`MAX_THREADS`](crate::MAX_THREADS) threads of the model.
+///
+/// # Examples
+///
+/// ```
+/// use loom::future;
+/// use loom::sync::atomic::AtomicUsize;
+/// use loom::sync::Arc;
+///
+/// use std::sync::atomic::Ordering::SeqCst;
+///
+/// loom::model(|| {
+///     let num = Arc::new(AtomicUsize::new(0));
+///
+///     let tasks: Vec<_> = (0..2)
+///         .map(|_| {
+///             let num = num.clone();
+///             future::spawn(async move { num.fetch_add(1, SeqCst) })
+///         })
+///         .collect();
+///
+///     for task in tasks {
+///         task.join().unwrap();
+///     }
+///
+///     assert_eq!(2, num.load(SeqCst));
+/// });
+/// ```
+#[track_caller]
+pub fn spawn<F>(f: F) -> JoinHandle<F::Output>
+where
+    F: Future + 'static,
+    F::Output: 'static,
+{
+    JoinHandle(thread::spawn(move || block_on(f)))
+}
+
+/// Handle to a task spawned with [`spawn`], to wait for its completion.
+pub struct JoinHandle<T>(thread::JoinHandle<T>);
+
+impl<T> JoinHandle<T> {
+    /// Blocks the current thread until the task completes, returning its
+    /// output.
+    ///
+    /// As for [`thread::JoinHandle::join`], the output is returned as-is in
+    /// `Ok`, and a panic in the task fails the model instead.
+    #[track_caller]
+    pub fn join(self) -> std::thread::Result<T> {
+        self.0.join()
+    }
+
+    /// Gets a handle to the thread running the task.
+    pub fn thread(&self) -> &thread::Thread {
+        self.0.thread()
+    }
+}
+
+impl<T> fmt::Debug for JoinHandle<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("JoinHandle").finish_non_exhaustive()
+    }
+}
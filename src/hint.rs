@@ -2,13 +2,18 @@
 
 /// Signals the processor that it is entering a busy-wait spin-loop.
 ///
-/// For loom, this is an alias of [`yield_now`] but is provided as a reflection
+/// For loom, this yields like [`yield_now`] but is provided as a reflection
 /// of the [`core::hint::spin_loop`] function. See the [`yield_now`]
 /// documentation for more information on what effect this has.
 ///
+/// Consecutive calls that are not separated by an access to shared state, e.g.
+/// the spins of a backoff loop, only yield once, as nothing observable happens
+/// in between. This keeps backoff loops from exceeding the maximum number of
+/// branches of a permutation.
+///
 /// [`yield_now`]: crate::thread::yield_now
 pub fn spin_loop() {
-    crate::sync::atomic::spin_loop_hint();
+    crate::rt::spin_loop();
 }
 
 /// Informs the compiler that this point in the code is not reachable, enabling
@@ -174,6 +174,13 @@
 //! must include calls to [`loom::thread::yield_now`](thread::yield_now). This tells loom that
 //! another thread needs to be scheduled in order for the current one to make progress.
 //!
+//! ## Panics
+//!
+//! A panic that escapes a thread, including the main model closure, fails the model. Panics may
+//! however be caught with [`std::panic::catch_unwind`] inside the model, in which case execution
+//! continues normally: loom operations performed before the panic remain in effect, so the
+//! recovery code can be checked like any other code.
+//!
 //! # Running Loom Tests
 //!
 //! Loom tests must be run separately, with `RUSTFLAGS="--cfg loom"` specified (assuming you went
@@ -189,6 +196,11 @@
 //! each test closure a large number of times, at which point the speed win from optimized code
 //! makes a big difference.
 //!
+//! Optimized builds compile `debug_assert!`s out, so the invariants they check are not checked
+//! while exploring the permutations. Use [`loom::debug_assert!`](crate::debug_assert) in the code
+//! under test instead, which is always checked, or enable `debug-assertions` in the profile used
+//! for loom tests.
+//!
 //! # Debugging Loom Failures
 //!
 //! Loom's deterministic execution allows the specific chain of events leading to a test failure
@@ -203,6 +215,10 @@
 //! to the file. If the execution fails on the 10,000,000th permutation, it is faster to write to a
 //! file every 10,000 iterations instead of every single one.
 //!
+//! The checkpoint is written as JSON by default. For models with long execution paths, a compact
+//! binary format is faster to write and read: it is used for files with the `.bin` extension, or
+//! when `LOOM_CHECKPOINT_FORMAT=binary` is set (`LOOM_CHECKPOINT_FORMAT=json` forces JSON).
+//!
 //! To isolate the exact failing path, first run the following command to generate the checkpoint
 //! for the failing scenario:
 //!
@@ -332,7 +348,8 @@
 //! However, even with equivalent execution elimination, the number of possible executions grows
 //! significantly with each new thread, to the point where checking becomes infeasible. Loom
 //! therefore specifically limits the number of threads it will model (see [`MAX_THREADS`]), and
-//! tailors its implementation to that limit.
+//! tailors its implementation to that limit. Models that need more threads can raise the limit
+//! with the `large_models` feature.
 //!
 //! # Implementation
 //!
@@ -366,14 +383,16 @@ macro_rules! dbg {
 #[macro_use]
 mod rt;
 
-pub use rt::{explore, skip_branch, stop_exploring};
+pub use rt::{atomic_batch, explore, skip_branch, stop_exploring};
 // Expose for documentation purposes.
 pub use rt::MAX_THREADS;
 
 pub mod alloc;
 pub mod cell;
+pub mod debug;
 pub mod hint;
 pub mod lazy_static;
+pub mod linearizability;
 pub mod model;
 pub mod sync;
 pub mod thread;
@@ -408,7 +427,33 @@ macro_rules! thread_local {
     );
 }
 
+/// Version of `std::debug_assert!` checked regardless of the build profile.
+///
+/// Loom tests are usually run with `--release`, which disables the standard
+/// `debug_assert!`. This asserts like `std::assert!` instead, so a failed
+/// assertion fails the iteration, and is reported with its schedule like any
+/// other panic. Outside of `cfg(loom)`, the code under test can use the
+/// standard macro:
+///
+/// ```
+/// #[cfg(loom)]
+/// use loom::debug_assert;
+/// ```
+// Defined after all other code in `loom`, so that it doesn't replace the
+// standard macro internally.
+#[macro_export]
+macro_rules! debug_assert {
+    ($($arg:tt)*) => {
+        ::core::assert!($($arg)*)
+    };
+}
+
 /// Mock version of `lazy_static::lazy_static!`.
+///
+/// Unlike with the real crate, the values are dropped at the end of each
+/// iteration, once the model closure returns. Their `Drop` implementations run
+/// on the main thread and may perform loom operations, which are modeled like
+/// any other operation. They must not access other lazy statics.
 #[macro_export]
 macro_rules! lazy_static {
     ($(#[$attr:meta])* static ref $N:ident : $T:ty = $e:expr; $($t:tt)*) => {
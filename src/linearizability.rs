@@ -0,0 +1,268 @@
+//! Check the linearizability of concurrent data structures.
+//!
+//! A concurrent data structure is linearizable if each operation appears to
+//! take effect atomically at some point between its invocation and its
+//! response. [`check`] runs a set of operations from multiple threads, records
+//! when each one was invoked and when it returned, and verifies that, for
+//! every interleaving explored by loom, the results could have been produced
+//! by applying the operations one at a time to a sequential specification.
+//!
+//! # Examples
+//!
+//! ```
+//! use loom::linearizability::{self, Spec};
+//! use loom::sync::Mutex;
+//!
+//! #[derive(Clone, Debug)]
+//! enum Op {
+//!     Push(usize),
+//!     Pop,
+//! }
+//!
+//! #[derive(Clone, Default)]
+//! struct Stack(Vec<usize>);
+//!
+//! impl Spec for Stack {
+//!     type Op = Op;
+//!     type Ret = Option<usize>;
+//!
+//!     fn apply(&mut self, op: &Op) -> Option<usize> {
+//!         match *op {
+//!             Op::Push(v) => {
+//!                 self.0.push(v);
+//!                 None
+//!             }
+//!             Op::Pop => self.0.pop(),
+//!         }
+//!     }
+//! }
+//!
+//! linearizability::check(
+//!     vec![vec![Op::Push(1)], vec![Op::Push(2), Op::Pop]],
+//!     Stack::default(),
+//!     || Mutex::new(Vec::new()),
+//!     |stack, op| match *op {
+//!         Op::Push(v) => {
+//!             stack.lock().unwrap().push(v);
+//!             None
+//!         }
+//!         Op::Pop => stack.lock().unwrap().pop(),
+//!     },
+//! );
+//! ```
+
+use crate::model::Builder;
+
+use std::fmt::{self, Write};
+use std::sync::{Arc, Mutex};
+
+/// Sequential specification of a data structure.
+pub trait Spec: Clone {
+    /// Operation performed on the data structure.
+    type Op: fmt::Debug;
+
+    /// Result of an operation.
+    type Ret: fmt::Debug + PartialEq;
+
+    /// Applies `op` to the data structure, returning its result.
+    fn apply(&mut self, op: &Self::Op) -> Self::Ret;
+}
+
+/// Checks that the data structure returned by `new` is linearizable with
+/// respect to `spec`.
+///
+/// Each element of `ops` is the list of operations performed in order by a
+/// separate thread, using `call`. The main thread waits for all of them, then
+/// panics if the recorded history cannot be linearized, describing the
+/// history. As each operation list runs in its own thread, there can be at
+/// most `max_threads - 1` of them.
+///
+/// Operations are recorded using non-loom primitives, so they do not add
+/// branch points to the model.
+///
+/// The model is checked as by [`model`](crate::model), use [`check_with`] to
+/// configure it.
+pub fn check<S, T, N, F>(ops: Vec<Vec<S::Op>>, spec: S, new: N, call: F)
+where
+    S: Spec + Send + Sync + 'static,
+    S::Op: Send + Sync + 'static,
+    S::Ret: Send + 'static,
+    T: Send + Sync + 'static,
+    N: Fn() -> T + Send + Sync + 'static,
+    F: Fn(&T, &S::Op) -> S::Ret + Send + Sync + 'static,
+{
+    let max_threads = Builder::new().max_threads;
+    crate::model(history_model(max_threads, ops, spec, new, call));
+}
+
+/// Checks that the data structure returned by `new` is linearizable with
+/// respect to `spec`, using `builder` to check the model.
+///
+/// See [`check`].
+pub fn check_with<S, T, N, F>(builder: &Builder, ops: Vec<Vec<S::Op>>, spec: S, new: N, call: F)
+where
+    S: Spec + Send + Sync + 'static,
+    S::Op: Send + Sync + 'static,
+    S::Ret: Send + 'static,
+    T: Send + Sync + 'static,
+    N: Fn() -> T + Send + Sync + 'static,
+    F: Fn(&T, &S::Op) -> S::Ret + Send + Sync + 'static,
+{
+    builder.check(history_model(builder.max_threads, ops, spec, new, call));
+}
+
+/// Returns the model running `ops` from separate threads and checking the
+/// recorded history, with at most `max_threads` threads.
+fn history_model<S, T, N, F>(
+    max_threads: usize,
+    ops: Vec<Vec<S::Op>>,
+    spec: S,
+    new: N,
+    call: F,
+) -> impl Fn() + Send + Sync + 'static
+where
+    S: Spec + Send + Sync + 'static,
+    S::Op: Send + Sync + 'static,
+    S::Ret: Send + 'static,
+    T: Send + Sync + 'static,
+    N: Fn() -> T + Send + Sync + 'static,
+    F: Fn(&T, &S::Op) -> S::Ret + Send + Sync + 'static,
+{
+    assert!(
+        ops.len() < max_threads,
+        "at most {} threads of operations can be checked",
+        max_threads - 1
+    );
+
+    let ops = Arc::new(ops);
+    let call = Arc::new(call);
+
+    move || {
+        let object = Arc::new(new());
+        let history = Arc::new(Mutex::new(Vec::new()));
+
+        let threads: Vec<_> = (0..ops.len())
+            .map(|thread| {
+                let ops = ops.clone();
+                let call = call.clone();
+                let object = object.clone();
+                let history = history.clone();
+
+                crate::thread::spawn(move || {
+                    for (index, op) in ops[thread].iter().enumerate() {
+                        let id = (thread, index);
+
+                        history.lock().unwrap().push((id, None));
+                        let ret = call(&object, op);
+                        history.lock().unwrap().push((id, Some(ret)));
+                    }
+                })
+            })
+            .collect();
+
+        for th in threads {
+            th.join().unwrap();
+        }
+
+        let history = std::mem::take(&mut *history.lock().unwrap());
+
+        if !is_linearizable(&spec, &ops, &history) {
+            panic!("history is not linearizable:\n{}", describe(&ops, &history));
+        }
+    }
+}
+
+/// Identifies an operation by thread and position in that thread.
+type OpId = (usize, usize);
+
+/// Invocations, with no result, and responses in the order they happened.
+type History<R> = [(OpId, Option<R>)];
+
+/// A completed operation.
+struct Entry<'a, S: Spec> {
+    op: &'a S::Op,
+    ret: &'a S::Ret,
+
+    /// Position of the invocation in the history
+    call: usize,
+
+    /// Position of the response in the history
+    response: usize,
+}
+
+fn is_linearizable<S: Spec>(spec: &S, ops: &[Vec<S::Op>], history: &History<S::Ret>) -> bool {
+    let mut entries = Vec::new();
+
+    for (response, (id, ret)) in history.iter().enumerate() {
+        if let Some(ret) = ret {
+            let call = history
+                .iter()
+                .position(|(other, ret)| other == id && ret.is_none())
+                .expect("[loom internal bug] response without invocation");
+
+            entries.push(Entry::<S> {
+                op: &ops[id.0][id.1],
+                ret,
+                call,
+                response,
+            });
+        }
+    }
+
+    let mut done = vec![false; entries.len()];
+    linearize(spec, &entries, &mut done)
+}
+
+/// Searches for an order of the remaining operations, each invoked before
+/// all pending operations returned, that is valid for the specification.
+fn linearize<S: Spec>(spec: &S, entries: &[Entry<'_, S>], done: &mut [bool]) -> bool {
+    let first_response = entries
+        .iter()
+        .zip(done.iter())
+        .filter(|(_, done)| !**done)
+        .map(|(entry, _)| entry.response)
+        .min();
+
+    let first_response = match first_response {
+        Some(first_response) => first_response,
+        None => return true,
+    };
+
+    for (i, entry) in entries.iter().enumerate() {
+        if done[i] || entry.call > first_response {
+            continue;
+        }
+
+        let mut next = spec.clone();
+
+        if next.apply(entry.op) != *entry.ret {
+            continue;
+        }
+
+        done[i] = true;
+
+        if linearize(&next, entries, done) {
+            return true;
+        }
+
+        done[i] = false;
+    }
+
+    false
+}
+
+fn describe<O: fmt::Debug, R: fmt::Debug>(ops: &[Vec<O>], history: &History<R>) -> String {
+    let mut out = String::new();
+
+    for ((thread, index), ret) in history {
+        let op = &ops[*thread][*index];
+
+        match ret {
+            None => writeln!(out, "    thread {}: call {:?}", thread, op),
+            Some(ret) => writeln!(out, "    thread {}: {:?} -> {:?}", thread, op, ret),
+        }
+        .unwrap();
+    }
+
+    out
+}
@@ -1,18 +1,32 @@
 //! Model concurrent programs.
 
-use crate::rt::{self, Execution, Scheduler};
-use std::path::PathBuf;
-use std::sync::Arc;
+use crate::rt::orderings::Sites;
+use crate::rt::{self, Choice, Execution, Orderings, Scheduler, Sharing, Watchdog};
+use std::any::Any;
+use std::cell::Cell;
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::ops::ControlFlow;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex, PoisonError};
 use std::time::{Duration, Instant};
+use std::{fmt, mem};
 
-use tracing::{info, subscriber};
-use tracing_subscriber::{fmt, EnvFilter};
+use tracing::{info, subscriber, warn};
+use tracing_subscriber::EnvFilter;
 
 const DEFAULT_MAX_THREADS: usize = 5;
 const DEFAULT_MAX_BRANCHES: usize = 1_000;
+const DEFAULT_CHECKPOINT_INTERVAL: usize = 20_000;
+
+type Progress = Box<dyn FnMut(usize) + Send>;
+type OnComplete = Box<dyn FnOnce(Stats) + Send>;
+type OnIteration = Box<dyn FnMut(usize) -> ControlFlow<()> + Send>;
 
 /// Configure a model
-#[derive(Debug)]
 #[non_exhaustive] // Support adding more fields in the future
 pub struct Builder {
     /// Max number of threads to check as part of the execution.
@@ -36,6 +50,19 @@ pub struct Builder {
     /// Defaults to `LOOM_MAX_DURATION` environment variable.
     pub max_duration: Option<Duration>,
 
+    /// Abort with a report when no thread switch happens for this long.
+    ///
+    /// All loom threads run on the same OS thread, so a loom thread blocking
+    /// on a non-loom operation, such as a contended `std::sync::Mutex` or
+    /// real I/O, hangs the whole model. When set, a watchdog prints which
+    /// thread appears blocked, and at which location if `location` is set,
+    /// then aborts the process. The timeout should be well above the time a
+    /// thread spends computing in between two loom operations.
+    ///
+    /// Defaults to `LOOM_BLOCKED_THREAD_TIMEOUT` environment variable, in
+    /// seconds.
+    pub blocked_thread_timeout: Option<Duration>,
+
     /// Maximum number of thread preemptions to explore
     ///
     /// Defaults to `LOOM_MAX_PREEMPTIONS` environment variable.
@@ -44,9 +71,20 @@ pub struct Builder {
     /// When doing an exhaustive check, uses the file to store and load the
     /// check progress
     ///
+    /// The file records the iteration reached along with its execution path,
+    /// so a resumed check keeps numbering iterations, and counting them
+    /// against `max_permutations`, from where the previous check stopped.
+    ///
     /// Defaults to `LOOM_CHECKPOINT_FILE` environment variable.
     pub checkpoint_file: Option<PathBuf>,
 
+    /// Format of the checkpoint file. When `None`, the format is inferred from
+    /// the extension of the file: `.bin` files are binary, others are JSON.
+    ///
+    /// Defaults to `LOOM_CHECKPOINT_FORMAT` environment variable, `json` or
+    /// `binary`.
+    pub checkpoint_format: Option<CheckpointFormat>,
+
     /// How often to write the checkpoint file
     ///
     /// Defaults to `LOOM_CHECKPOINT_INTERVAL` environment variable.
@@ -69,61 +107,184 @@ pub struct Builder {
     ///
     /// Defaults to existence of `LOOM_LOG` environment variable.
     pub log: bool,
+
+    /// Invariants checked at every branch point.
+    invariants: Vec<rt::Invariant>,
+
+    /// Called with the number of iterations every `checkpoint_interval`.
+    progress: Option<Mutex<Progress>>,
+
+    /// Called with the number of the iteration before running it.
+    on_iteration: Option<Mutex<OnIteration>>,
+
+    /// Called once with the statistics of the check when it completes.
+    on_complete: Mutex<Option<OnComplete>>,
+
+    /// Warn about detached threads still running when the model closure
+    /// returns.
+    warn_detached_threads: bool,
+
+    /// Number of random iterations, and their seed, to run before exploring
+    /// exhaustively.
+    random_warmup: Option<(usize, u64)>,
+
+    /// Suggest atomic operations whose ordering could be `Relaxed`.
+    suggest_orderings: bool,
+
+    /// Minimize the schedule of a failing iteration before reporting it.
+    minimize_failures: bool,
+
+    /// Report synchronization objects only ever accessed by a single thread.
+    report_single_thread_objects: bool,
+
+    /// Report synchronization objects never accessed
+    report_dead_objects: bool,
+
+    /// Maximum number of loom operations per iteration.
+    max_iteration_ops: Option<usize>,
+
+    /// Report threads spinning without making progress.
+    check_lock_freedom: bool,
+
+    /// Selects the threads whose operations are reported on failure.
+    trace_thread: Option<rt::TraceThread>,
+
+    /// Warn about atomics decremented to zero without `Release` ordering.
+    refcount_lint: bool,
+
+    /// Warn about `fetch_update` functions returning different values for the
+    /// same input.
+    check_update_purity: bool,
+
+    /// Explore spurious wakeups of `Condvar::wait`.
+    spurious_wakeups: bool,
+
+    /// File to write the explored schedules to.
+    export_schedules: Option<PathBuf>,
+
+    /// Index of the shard to explore, out of the total number of shards.
+    shard: Option<(usize, usize)>,
 }
 
 impl Builder {
     /// Create a new `Builder` instance with default values.
+    ///
+    /// The defaults can be overridden using environment variables, see
+    /// [`Builder::from_env`].
     pub fn new() -> Builder {
+        Builder::from_env()
+    }
+
+    /// Create a new `Builder` instance with default values, overridden by the
+    /// `LOOM_*` environment variables that are set.
+    pub fn from_env() -> Builder {
         use std::env;
 
-        let checkpoint_interval = env::var("LOOM_CHECKPOINT_INTERVAL")
-            .map(|v| {
-                v.parse()
-                    .expect("invalid value for `LOOM_CHECKPOINT_INTERVAL`")
-            })
-            .unwrap_or(20_000);
+        let mut builder = Builder::without_env();
 
-        let max_branches = env::var("LOOM_MAX_BRANCHES")
-            .map(|v| v.parse().expect("invalid value for `LOOM_MAX_BRANCHES`"))
-            .unwrap_or(DEFAULT_MAX_BRANCHES);
+        if let Ok(v) = env::var("LOOM_CHECKPOINT_INTERVAL") {
+            builder.checkpoint_interval = v
+                .parse()
+                .expect("invalid value for `LOOM_CHECKPOINT_INTERVAL`");
+        }
 
-        let location = env::var("LOOM_LOCATION").is_ok();
+        if let Ok(v) = env::var("LOOM_MAX_BRANCHES") {
+            builder.max_branches = v.parse().expect("invalid value for `LOOM_MAX_BRANCHES`");
+        }
 
-        let log = env::var("LOOM_LOG").is_ok();
+        if env::var("LOOM_LOCATION").is_ok() {
+            builder.location = true;
+        }
 
-        let max_duration = env::var("LOOM_MAX_DURATION")
-            .map(|v| {
-                let secs = v.parse().expect("invalid value for `LOOM_MAX_DURATION`");
-                Duration::from_secs(secs)
-            })
-            .ok();
+        if env::var("LOOM_LOG").is_ok() {
+            builder.log = true;
+        }
+
+        if let Ok(v) = env::var("LOOM_MAX_DURATION") {
+            let secs = v.parse().expect("invalid value for `LOOM_MAX_DURATION`");
+            builder.max_duration = Some(Duration::from_secs(secs));
+        }
 
-        let max_permutations = env::var("LOOM_MAX_PERMUTATIONS")
-            .map(|v| {
+        if let Ok(v) = env::var("LOOM_BLOCKED_THREAD_TIMEOUT") {
+            let secs = v
+                .parse()
+                .expect("invalid value for `LOOM_BLOCKED_THREAD_TIMEOUT`");
+            builder.blocked_thread_timeout = Some(Duration::from_secs(secs));
+        }
+
+        if let Ok(v) = env::var("LOOM_MAX_PERMUTATIONS") {
+            builder.max_permutations = Some(
                 v.parse()
-                    .expect("invalid value for `LOOM_MAX_PERMUTATIONS`")
-            })
-            .ok();
+                    .expect("invalid value for `LOOM_MAX_PERMUTATIONS`"),
+            );
+        }
 
-        let preemption_bound = env::var("LOOM_MAX_PREEMPTIONS")
-            .map(|v| v.parse().expect("invalid value for `LOOM_MAX_PREEMPTIONS`"))
-            .ok();
+        if let Ok(v) = env::var("LOOM_MAX_PREEMPTIONS") {
+            builder.preemption_bound =
+                Some(v.parse().expect("invalid value for `LOOM_MAX_PREEMPTIONS`"));
+        }
 
-        let checkpoint_file = env::var("LOOM_CHECKPOINT_FILE")
-            .map(|v| v.parse().expect("invalid value for `LOOM_CHECKPOINT_FILE`"))
-            .ok();
+        if let Ok(v) = env::var("LOOM_CHECKPOINT_FILE") {
+            builder.checkpoint_file =
+                Some(v.parse().expect("invalid value for `LOOM_CHECKPOINT_FILE`"));
+        }
 
+        if let Ok(v) = env::var("LOOM_CHECKPOINT_FORMAT") {
+            builder.checkpoint_format = Some(match &v[..] {
+                "json" => CheckpointFormat::Json,
+                "binary" => CheckpointFormat::Binary,
+                _ => panic!("invalid value for `LOOM_CHECKPOINT_FORMAT`: {}", v),
+            });
+        }
+
+        if let Ok(v) = env::var("LOOM_SHARD") {
+            let (index, total) = v
+                .split_once('/')
+                .and_then(|(index, total)| Some((index.parse().ok()?, total.parse().ok()?)))
+                .expect("invalid value for `LOOM_SHARD`, expected `index/total`");
+            builder.shard(index, total);
+        }
+
+        builder
+    }
+
+    /// Create a new `Builder` instance with default values, ignoring the
+    /// `LOOM_*` environment variables.
+    ///
+    /// This is useful for models that must not be affected by the environment
+    /// they run in.
+    pub fn without_env() -> Builder {
         Builder {
             max_threads: DEFAULT_MAX_THREADS,
-            max_branches,
-            max_duration,
-            max_permutations,
-            preemption_bound,
-            checkpoint_file,
-            checkpoint_interval,
+            max_branches: DEFAULT_MAX_BRANCHES,
+            max_duration: None,
+            blocked_thread_timeout: None,
+            max_permutations: None,
+            preemption_bound: None,
+            checkpoint_file: None,
+            checkpoint_format: None,
+            checkpoint_interval: DEFAULT_CHECKPOINT_INTERVAL,
             expect_explicit_explore: false,
-            location,
-            log,
+            location: false,
+            log: false,
+            invariants: Vec::new(),
+            progress: None,
+            on_iteration: None,
+            on_complete: Mutex::new(None),
+            warn_detached_threads: false,
+            random_warmup: None,
+            suggest_orderings: false,
+            minimize_failures: false,
+            report_single_thread_objects: false,
+            report_dead_objects: false,
+            max_iteration_ops: None,
+            check_lock_freedom: false,
+            trace_thread: None,
+            refcount_lint: false,
+            check_update_purity: false,
+            spurious_wakeups: false,
+            export_schedules: None,
+            shard: None,
         }
     }
 
@@ -133,8 +294,613 @@ impl Builder {
         self
     }
 
-    /// Check the provided model.
-    pub fn check<F>(&self, f: F)
+    /// Set the maximum number of threads, see [`Builder::max_threads`
+    /// field](Builder#structfield.max_threads).
+    pub fn max_threads(&mut self, max: usize) -> &mut Self {
+        self.max_threads = max;
+        self
+    }
+
+    /// Set the maximum number of thread switches per permutation, see
+    /// [`Builder::max_branches` field](Builder#structfield.max_branches).
+    pub fn max_branches(&mut self, max: usize) -> &mut Self {
+        self.max_branches = max;
+        self
+    }
+
+    /// Set the maximum number of permutations to explore, see
+    /// [`Builder::max_permutations`
+    /// field](Builder#structfield.max_permutations).
+    pub fn max_permutations(&mut self, max: Option<usize>) -> &mut Self {
+        self.max_permutations = max;
+        self
+    }
+
+    /// Set the maximum number of thread preemptions to explore, see
+    /// [`Builder::preemption_bound`
+    /// field](Builder#structfield.preemption_bound).
+    pub fn preemption_bound(&mut self, bound: Option<usize>) -> &mut Self {
+        self.preemption_bound = bound;
+        self
+    }
+
+    /// Set the maximum amount of time to spend on checking, see
+    /// [`Builder::max_duration` field](Builder#structfield.max_duration).
+    pub fn max_duration(&mut self, max: Option<Duration>) -> &mut Self {
+        self.max_duration = max;
+        self
+    }
+
+    /// Set whether locations are captured on each loom operation, see
+    /// [`Builder::location` field](Builder#structfield.location).
+    pub fn location(&mut self, location: bool) -> &mut Self {
+        self.location = location;
+        self
+    }
+
+    /// Add an invariant that must hold at every branch point.
+    ///
+    /// The invariant is called before each loom operation and when each
+    /// thread terminates. If it returns `false`, the model fails, reporting the
+    /// thread and operation at which the violation was detected. As only one
+    /// thread runs between two branch points, this is the thread whose code
+    /// broke the invariant.
+    ///
+    /// The invariant must not perform any loom operations, so state it
+    /// inspects should be tracked using non-loom types.
+    pub fn invariant<F>(&mut self, f: F) -> &mut Self
+    where
+        F: Fn() -> bool + Send + Sync + 'static,
+    {
+        self.invariants.push(Arc::new(f));
+        self
+    }
+
+    /// Set a callback reporting the progress of the check.
+    ///
+    /// The callback is invoked every `checkpoint_interval` iterations with the
+    /// current iteration number. As the total number of iterations
+    /// is not known upfront, this can be used to report a rate or drive a
+    /// progress indicator for long-running models.
+    pub fn progress<F>(&mut self, f: F) -> &mut Self
+    where
+        F: FnMut(usize) + Send + 'static,
+    {
+        self.progress = Some(Mutex::new(Box::new(f)));
+        self
+    }
+
+    /// Set a callback invoked before each iteration, with its number.
+    ///
+    /// The callback runs outside of the model, between iterations, so it does
+    /// not affect the permutations explored. Returning `ControlFlow::Break`
+    /// stops the check early, as `max_permutations` would: the iteration is not
+    /// run and the check is not complete. Unlike [`Builder::progress`], this is
+    /// called for every iteration, which is useful to observe a model as it
+    /// grows, e.g. to sample memory usage.
+    pub fn on_iteration<F>(&mut self, f: F) -> &mut Self
+    where
+        F: FnMut(usize) -> ControlFlow<()> + Send + 'static,
+    {
+        self.on_iteration = Some(Mutex::new(Box::new(f)));
+        self
+    }
+
+    /// Set a callback invoked once the model was checked, with the
+    /// statistics of the check.
+    ///
+    /// Unlike [`Builder::progress`], the callback is invoked exactly once,
+    /// after all iterations ran, whether all permutations were explored or the
+    /// check stopped at `max_permutations` or `max_duration`. It is not invoked
+    /// if the model fails. This can be used to aggregate and report data
+    /// collected across iterations.
+    pub fn on_complete<F>(&mut self, f: F) -> &mut Self
+    where
+        F: FnOnce(Stats) + Send + 'static,
+    {
+        *self.on_complete.get_mut().unwrap() = Some(Box::new(f));
+        self
+    }
+
+    /// Warn about detached threads that are still running when the model
+    /// closure returns.
+    ///
+    /// A thread is detached when its `JoinHandle` is dropped without calling
+    /// `join`. Loom still runs it to completion before ending the iteration,
+    /// but a detached thread touching shared state after the model closure is
+    /// done is often a bug in the test. When enabled, each such thread is
+    /// reported with a `tracing` warning.
+    pub fn warn_detached_threads(&mut self, warn: bool) -> &mut Self {
+        self.warn_detached_threads = warn;
+        self
+    }
+
+    /// Run `iterations` randomly scheduled iterations before starting the
+    /// exhaustive exploration.
+    ///
+    /// In each of these iterations, thread switches and the values read by
+    /// atomic loads are picked at random using a generator seeded with `seed`.
+    /// Bugs reachable by many interleavings are usually caught this way much
+    /// sooner than by the depth-first exhaustive search, which only reaches
+    /// interleavings differing early in the execution after exploring all the
+    /// ones sharing their prefix. The warmup is skipped when resuming from a
+    /// checkpoint.
+    pub fn random_warmup(&mut self, iterations: usize, seed: u64) -> &mut Self {
+        self.random_warmup = Some((iterations, seed));
+        self
+    }
+
+    /// Suggest atomic operations whose ordering could be `Relaxed`.
+    ///
+    /// Once all permutations have been checked, the model is checked again
+    /// for each atomic operation, identified by its location in the code, that
+    /// uses a stronger ordering, this time performing that operation with
+    /// `Relaxed` ordering. If none of the permutations fail, a `tracing`
+    /// warning suggests that `Relaxed` is enough for that operation.
+    ///
+    /// This only holds for what the model checks: a weaker ordering may still
+    /// be required by code or assertions outside of it. As the model is checked
+    /// once more per operation, this can be very slow.
+    pub fn suggest_orderings(&mut self, suggest: bool) -> &mut Self {
+        self.suggest_orderings = suggest;
+        self
+    }
+
+    /// Minimize the schedule of a failing iteration before reporting it.
+    ///
+    /// The first failing iteration found often takes thread switches, reads
+    /// stale values or wakes up spuriously at branches that are irrelevant to
+    /// the failure. When enabled, loom replays that iteration, making the
+    /// default choice at each of these branches in turn: keep running the
+    /// active thread, read the newest value, or don't wake up. A choice is
+    /// dropped when the iteration still fails with the same panic message.
+    /// The model then fails by replaying the minimized schedule, so its
+    /// output, including the log enabled with `LOOM_LOG`, only shows the
+    /// choices needed to reproduce the failure. A `tracing` warning reports
+    /// how many were dropped.
+    pub fn minimize_failures(&mut self, minimize: bool) -> &mut Self {
+        self.minimize_failures = minimize;
+        self
+    }
+
+    /// Report synchronization objects only ever accessed by a single thread.
+    ///
+    /// Once all permutations have been checked, a `tracing` warning is
+    /// emitted for each atomic, `Mutex` or `RwLock`, identified by the location
+    /// where it was created, if none of the objects created there was ever
+    /// accessed by more than one thread. Such an object either does not need to
+    /// be synchronized, or the model does not exercise its concurrent use.
+    pub fn report_single_thread_objects(&mut self, report: bool) -> &mut Self {
+        self.report_single_thread_objects = report;
+        self
+    }
+
+    /// Report synchronization objects never accessed.
+    ///
+    /// Once all permutations have been checked, a `tracing` warning is
+    /// emitted for each atomic, `Mutex` or `RwLock`, identified by the location
+    /// where it was created, if none of the objects created there was ever
+    /// loaded, stored to, modified or locked, in any permutation. Accesses
+    /// with `unsync_load` or `with_mut` don't count. Such an object is likely
+    /// left over from refactored code.
+    pub fn report_dead_objects(&mut self, report: bool) -> &mut Self {
+        self.report_dead_objects = report;
+        self
+    }
+
+    /// Fail any iteration performing more than `ops` loom operations.
+    ///
+    /// Every loom operation, such as an atomic load, a lock acquisition or a
+    /// thread yield, is counted. An iteration exceeding `ops` fails with the
+    /// number of operations and the schedule it followed, listing how many
+    /// operations each thread performed in a row. Unlike a time limit, this is
+    /// deterministic: it catches code under test that takes a slow path, e.g.
+    /// spinning, only in some schedules.
+    pub fn max_iteration_ops(&mut self, ops: usize) -> &mut Self {
+        self.max_iteration_ops = Some(ops);
+        self
+    }
+
+    /// Fail iterations in which all the threads spin without making progress.
+    ///
+    /// A thread makes progress when it stores to an atomic, succeeds a
+    /// read-modify-write operation such as `compare_exchange`, or terminates.
+    /// If every runnable thread yields, with [`yield_now`] or a spin loop
+    /// hint, several times in a row while no thread makes progress, the
+    /// iteration fails with a lock-freedom violation. This catches CAS loops
+    /// in which the threads wait for each other forever, which otherwise only
+    /// show up as exceeding [`max_branches`](Builder::max_branches).
+    ///
+    /// The check is a heuristic: only atomics count as progress, so threads
+    /// that yield while communicating through a `Mutex` or a `Condvar` may be
+    /// reported.
+    ///
+    /// [`yield_now`]: crate::thread::yield_now
+    pub fn check_lock_freedom(&mut self, check: bool) -> &mut Self {
+        self.check_lock_freedom = check;
+        self
+    }
+
+    /// Report the operations of the threads selected by `predicate` when an
+    /// iteration fails.
+    ///
+    /// The predicate is called with each thread of an iteration, e.g. to
+    /// select a thread by [name](crate::thread::Builder::name). The operations
+    /// the selected threads perform on loom objects are recorded, and the last
+    /// ones are added to the panic message of a failing iteration, with the
+    /// kind of operation, the object, as identified in
+    /// [exported schedules](Builder::export_schedules), and its location if
+    /// [captured](Builder::location). This is much less verbose than logging
+    /// every iteration with `LOOM_LOG`.
+    pub fn trace_thread<F>(&mut self, predicate: F) -> &mut Self
+    where
+        F: Fn(&crate::thread::Thread) -> bool + Send + Sync + 'static,
+    {
+        self.trace_thread = Some(Arc::new(predicate));
+        self
+    }
+
+    /// Warn about atomics used as reference counts that are decremented
+    /// without `Release` ordering.
+    ///
+    /// The thread dropping the last reference must observe all the accesses
+    /// of the other threads to the shared data, which requires each decrement
+    /// to be a `Release` operation. Loom only detects a missing `Acquire`
+    /// fence on the last decrement when the data is accessed after it, but
+    /// rarely detects a `Relaxed` decrement. This heuristic instead emits a
+    /// `tracing` warning, once all permutations have been checked, for each
+    /// `fetch_sub` location that decremented an atomic integer to zero with
+    /// `Relaxed` or `Acquire` ordering.
+    pub fn refcount_lint(&mut self, lint: bool) -> &mut Self {
+        self.refcount_lint = lint;
+        self
+    }
+
+    /// Warn about `fetch_update` functions that are not pure.
+    ///
+    /// Loom reruns the model many times and requires every run to be
+    /// deterministic, so the function passed to `fetch_update` must not have
+    /// side effects, such as incrementing a counter shared with the rest of
+    /// the model. With this check, each call to the function is repeated with
+    /// the same value, and a `tracing` warning is emitted, once all
+    /// permutations have been checked, for each `fetch_update` location
+    /// where the two calls returned different values. As the function is
+    /// called twice, its side effects are repeated: this is a debugging aid
+    /// rather than a check to leave enabled.
+    pub fn check_update_purity(&mut self, check: bool) -> &mut Self {
+        self.check_update_purity = check;
+        self
+    }
+
+    /// Explore spurious wakeups of [`Condvar::wait`].
+    ///
+    /// Like with `std`, a wait may return without a notification, so the
+    /// condition must be checked in a loop. When enabled, loom explores a
+    /// wait waking up spuriously once per condition variable and execution,
+    /// failing models that do not re-check their condition.
+    ///
+    /// [`Condvar::wait`]: crate::sync::Condvar::wait
+    pub fn spurious_wakeups(&mut self, explore: bool) -> &mut Self {
+        self.spurious_wakeups = explore;
+        self
+    }
+
+    /// Write each explored schedule to the file at `path`.
+    ///
+    /// The file is a plain text file, with one line per iteration, in the
+    /// order the iterations were explored. A line lists the operations the
+    /// iteration scheduled, in order, separated by a space. Each operation is
+    /// written as `thread:kind:object`, where:
+    ///
+    /// * `thread` is the id of the thread performing the operation, `0` being
+    ///   the main thread of the model,
+    /// * `kind` is one of `load`, `store` and `rmw` for atomics, `ref_inc`,
+    ///   `ref_dec` and `weak_ref` for `Arc`s, `send`, `recv` and `recv_drop`
+    ///   for channels, `read_lock` and `write_lock` for `RwLock`s, and
+    ///   `opaque` for any other operation, such as a `Mutex` lock,
+    /// * `object` identifies the object the operation is performed on, by the
+    ///   order in which the iteration created the objects.
+    ///
+    /// For example, `0:rmw:2 1:load:2 1:opaque:0`. The file is overwritten
+    /// when the check starts. Iterations of a [mutation
+    /// test](Builder::mutation_test_orderings) with weakened orderings are
+    /// not exported.
+    pub fn export_schedules(&mut self, path: impl Into<PathBuf>) -> &mut Self {
+        self.export_schedules = Some(path.into());
+        self
+    }
+
+    /// Only explore the part of the permutations assigned to shard `index`,
+    /// out of `total` shards.
+    ///
+    /// This splits the exploration of a large model between `total`
+    /// processes, e.g. CI jobs, each checking the model with a different
+    /// `index`. Together, the shards explore all the permutations the model
+    /// would explore without sharding: the choices made at the first branches
+    /// of the exploration, such as thread switches, split it into units that
+    /// are assigned to the shards in turn. With a preemption bound, a few
+    /// permutations may be explored by more than one shard.
+    ///
+    /// A shard runs the first permutation of every unit, including the ones
+    /// assigned to other shards, to find where the units start. These
+    /// permutations count in the statistics of the check, but are not
+    /// exported by [`Builder::export_schedules`].
+    ///
+    /// This can also be set with the `LOOM_SHARD` environment variable, as
+    /// `index/total`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not less than `total`.
+    pub fn shard(&mut self, index: usize, total: usize) -> &mut Self {
+        assert!(
+            index < total,
+            "shard index {} out of range for {} shards",
+            index,
+            total
+        );
+
+        self.shard = Some((index, total));
+        self
+    }
+
+    /// Check the provided model, returning the statistics of the check.
+    ///
+    /// [`Stats::complete`] tells whether all permutations were explored, or
+    /// the check stopped at `max_permutations` or `max_duration`. Asserting it
+    /// ensures a model remains exhaustive as it grows.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an iteration of the model fails, with the panic of the
+    /// failing iteration. The panic may be raised at any loom operation,
+    /// including from the function passed to an atomic's `fetch_update`,
+    /// after the load it was called with completed. The model's threads that are
+    /// suspended, or spawned but not started yet, are not unwound: their
+    /// values are leaked rather than dropped. The model does not need to be
+    /// [`UnwindSafe`](std::panic::UnwindSafe) as a failed iteration is never
+    /// resumed.
+    pub fn check<F>(&self, f: F) -> Stats
+    where
+        F: Fn() + Sync + Send + 'static,
+    {
+        let f = Arc::new(f);
+
+        if !self.suggest_orderings {
+            let (stats, _) = self.explore(
+                &f,
+                self.preemption_bound,
+                self.checkpoint_file.as_deref(),
+                Orderings::default(),
+            );
+            self.complete(stats);
+            return stats;
+        }
+
+        let (stats, _, mutations) = self.mutate(&f, true);
+
+        for mutation in mutations {
+            warn!(
+                parent: None,
+                "{:?} ordering at {} could be `Relaxed`",
+                mutation.ordering,
+                mutation.location
+            );
+        }
+
+        self.complete(stats);
+        stats
+    }
+
+    /// Check the provided model with increasing preemption bounds, from
+    /// `initial` to `max` preemptions, returning the statistics of the last
+    /// check.
+    ///
+    /// Most bugs only need a few preemptions to show up, and checking a model
+    /// with a low bound is much faster than exploring all its permutations.
+    /// Deepening the bound progressively finds shallow bugs quickly, while
+    /// still exploring deeper permutations if none is found. The
+    /// [`preemption_bound`](Builder::preemption_bound) field is ignored.
+    /// Checkpoints are not used.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an iteration of the model fails, with the preemption bound at
+    /// which the failure was found and the panic message of the failing
+    /// iteration, or if `initial` is greater than `max`.
+    pub fn check_iterative_deepening<F>(&self, initial: usize, max: usize, f: F) -> Stats
+    where
+        F: Fn() + Sync + Send + 'static,
+    {
+        assert!(
+            initial <= max,
+            "initial preemption bound {} is greater than the maximum {}",
+            initial,
+            max
+        );
+
+        let f = Arc::new(f);
+        let mut stats = None;
+
+        for bound in initial..=max {
+            let res = panic::catch_unwind(AssertUnwindSafe(|| {
+                self.explore(&f, Some(bound), None, Orderings::default())
+            }));
+
+            match res {
+                Ok((bound_stats, _)) => {
+                    info!(parent: None, "No failure found at preemption bound {}", bound);
+                    stats = Some(bound_stats);
+                }
+                Err(payload) => {
+                    panic!(
+                        "failure found at preemption bound {}: {}",
+                        bound,
+                        panic_message(&*payload)
+                    );
+                }
+            }
+        }
+
+        let stats = stats.unwrap();
+        self.complete(stats);
+        stats
+    }
+
+    /// Check the provided model, then report the atomic operations whose
+    /// ordering can be weakened without the model failing.
+    ///
+    /// Each atomic operation, identified by its location in the code, that
+    /// does not use `Relaxed` ordering is mutated in turn, performing it with
+    /// an ordering one step weaker (e.g. `Acquire` instead of `SeqCst` for a
+    /// load, or `Relaxed` instead of `Release` for a store), and the model is
+    /// checked again. Every mutation that does not make any permutation fail is
+    /// returned.
+    ///
+    /// A surviving mutation means the operation is either over-synchronized,
+    /// or that the model does not check what the stronger ordering is
+    /// protecting, such as the data guarded by a lock. As the model is checked
+    /// once more per mutation, this can be very slow. If `max_permutations` or
+    /// `max_duration` stop the initial check before all permutations were
+    /// explored, no mutations are tested.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the model fails without any mutation, like [`Builder::check`].
+    pub fn mutation_test_orderings<F>(&self, f: F) -> Vec<Mutation>
+    where
+        F: Fn() + Sync + Send + 'static,
+    {
+        let (stats, _, mutations) = self.mutate(&Arc::new(f), false);
+        self.complete(stats);
+        mutations
+    }
+
+    /// Check the provided model, then report, for each atomic operation, the
+    /// ordering it uses and whether the model depends on it.
+    ///
+    /// The report lists each atomic operation, identified by its location in
+    /// the code, that does not use `Relaxed` ordering. Its ordering is
+    /// exercised if performing the operation with any ordering one step
+    /// weaker makes the model fail, as tested by
+    /// [`Builder::mutation_test_orderings`]. An ordering that is not exercised
+    /// is either stronger than needed, or protects something the model does
+    /// not check, such as data shared without loom's `UnsafeCell`. Each entry
+    /// of the report is also logged with `tracing`. As the model is checked
+    /// once more per weaker ordering, this can be very slow. If
+    /// `max_permutations` or `max_duration` stop the initial check before all
+    /// permutations were explored, the report is empty.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the model fails with the orderings it uses, like
+    /// [`Builder::check`].
+    pub fn ordering_coverage_report<F>(&self, f: F) -> Vec<OrderingCoverage>
+    where
+        F: Fn() + Sync + Send + 'static,
+    {
+        let (stats, sites, mutations) = self.mutate(&Arc::new(f), false);
+
+        let report: Vec<_> = sites
+            .into_iter()
+            .map(|(location, (_, ordering))| OrderingCoverage {
+                location,
+                ordering,
+                exercised: mutations.iter().all(|m| m.location != location),
+            })
+            .collect();
+
+        for coverage in &report {
+            info!(parent: None, "{}", coverage);
+        }
+
+        self.complete(stats);
+        report
+    }
+
+    /// Invokes the `on_complete` callback, if any.
+    fn complete(&self, stats: Stats) {
+        if let Some(on_complete) = self.on_complete.lock().unwrap().take() {
+            on_complete(stats);
+        }
+    }
+
+    /// Returns the mutations of the orderings of atomic operations which do
+    /// not make the model fail, along with the statistics of the initial check
+    /// and the sites of the operations that were mutated. With
+    /// `relaxed_only`, all operations are weakened to `Relaxed` directly.
+    fn mutate<F>(&self, f: &Arc<F>, relaxed_only: bool) -> (Stats, Sites, Vec<Mutation>)
+    where
+        F: Fn() + Sync + Send + 'static,
+    {
+        let mut mutations = Vec::new();
+
+        // Mutations are only tested if all permutations were explored
+        let (stats, orderings) = self.explore(
+            f,
+            self.preemption_bound,
+            self.checkpoint_file.as_deref(),
+            Orderings::record(),
+        );
+
+        let sites = match orderings {
+            Some(orderings) => orderings.into_sites(),
+            None => return (stats, Sites::new(), mutations),
+        };
+
+        for (&location, &(kind, ordering)) in &sites {
+            let weaker = if relaxed_only {
+                &[Ordering::Relaxed]
+            } else {
+                kind.weaker(ordering)
+            };
+
+            for &weakened in weaker {
+                // Checkpoints are only used for the regular check
+                let res = panic::catch_unwind(AssertUnwindSafe(|| {
+                    self.explore(
+                        f,
+                        self.preemption_bound,
+                        None,
+                        Orderings::weaken(location, weakened),
+                    )
+                }));
+
+                if let Ok((_, Some(_))) = res {
+                    mutations.push(Mutation {
+                        location,
+                        ordering,
+                        weakened,
+                    });
+                }
+            }
+        }
+
+        (stats, sites, mutations)
+    }
+
+    /// Returns the format of the checkpoint file at `path`: the configured
+    /// one, else binary for `.bin` files and JSON for the others.
+    fn checkpoint_format(&self, path: &Path) -> CheckpointFormat {
+        self.checkpoint_format.unwrap_or_else(|| {
+            if path.extension().is_some_and(|ext| ext == "bin") {
+                CheckpointFormat::Binary
+            } else {
+                CheckpointFormat::Json
+            }
+        })
+    }
+
+    /// Explores the permutations of the model with at most `preemption_bound`
+    /// preemptions, returning its statistics, and the tracked orderings if all
+    /// of them were explored.
+    fn explore<F>(
+        &self,
+        f: &Arc<F>,
+        preemption_bound: Option<usize>,
+        checkpoint_file: Option<&Path>,
+        orderings: Orderings,
+    ) -> (Stats, Option<Orderings>)
     where
         F: Fn() + Sync + Send + 'static,
     {
@@ -144,22 +910,79 @@ impl Builder {
         let mut execution = Execution::new(
             self.max_threads,
             self.max_branches,
-            self.preemption_bound,
+            preemption_bound,
             !self.expect_explicit_explore,
         );
         let mut scheduler = Scheduler::new(self.max_threads);
 
-        if let Some(ref path) = self.checkpoint_file {
+        let mut warmup = self.random_warmup;
+
+        if let Some(path) = checkpoint_file {
             if path.exists() {
-                execution.path = checkpoint::load_execution_path(path);
+                let (iteration, execution_path) =
+                    checkpoint::load_execution_path(path, self.checkpoint_format(path));
+                execution.path = execution_path;
                 execution.path.set_max_branches(self.max_branches);
+                warmup = None;
+
+                // Resume counting from the checkpointed iteration
+                i = iteration;
+                _span = tracing::info_span!(parent: None, "iter", message = i).entered();
             }
         }
 
+        if let Some((index, total)) = self.shard {
+            execution.path.shard(index, total);
+        }
+
+        // Number of random iterations left to run
+        let mut warmup = match warmup {
+            Some((iterations, seed)) if iterations > 0 => {
+                execution.path.start_random(seed);
+                iterations
+            }
+            _ => 0,
+        };
+
         execution.log = self.log;
         execution.location = self.location;
+        execution.invariants = self.invariants.clone();
+        execution.max_ops = self.max_iteration_ops;
+        execution.lock_freedom = self.check_lock_freedom;
+        execution.spurious_wakeups = self.spurious_wakeups;
+        execution.trace_thread = self.trace_thread.clone();
+        execution.orderings = orderings;
 
-        let f = Arc::new(f);
+        // Weakened orderings are expected to fail
+        let minimize = self.minimize_failures && !execution.orderings.is_weakened();
+
+        if (self.report_single_thread_objects || self.report_dead_objects)
+            && !execution.orderings.is_weakened()
+        {
+            execution.sharing = Sharing::record();
+        }
+
+        if self.refcount_lint && !execution.orderings.is_weakened() {
+            execution.unreleased_decrements = Some(BTreeSet::new());
+        }
+
+        if self.check_update_purity && !execution.orderings.is_weakened() {
+            execution.impure_updates = Some(BTreeSet::new());
+        }
+
+        let mut schedules = match &self.export_schedules {
+            Some(path) if !execution.orderings.is_weakened() => {
+                execution.schedule = Some(Vec::new());
+
+                let file = File::create(path)
+                    .unwrap_or_else(|err| panic!("failed to create {}: {}", path.display(), err));
+                Some(BufWriter::new(file))
+            }
+            _ => None,
+        };
+
+        let watchdog = self.blocked_thread_timeout.map(Watchdog::start);
+        execution.heartbeat = watchdog.as_ref().map(Watchdog::heartbeat);
 
         let start = Instant::now();
         loop {
@@ -171,37 +994,61 @@ impl Builder {
                 );
                 info!(parent: None, "");
 
-                if let Some(ref path) = self.checkpoint_file {
-                    checkpoint::store_execution_path(&execution.path, path);
+                if let Some(ref progress) = self.progress {
+                    (progress.lock().unwrap())(i);
+                }
+
+                // Random executions cannot be resumed
+                if let (Some(path), 0) = (checkpoint_file, warmup) {
+                    checkpoint::store_execution_path(
+                        i,
+                        &execution.path,
+                        path,
+                        self.checkpoint_format(path),
+                    );
                 }
 
                 if let Some(max_permutations) = self.max_permutations {
                     if i >= max_permutations {
-                        return;
+                        return (Stats::new(i - 1, false, start), None);
                     }
                 }
 
                 if let Some(max_duration) = self.max_duration {
                     if start.elapsed() >= max_duration {
-                        return;
+                        return (Stats::new(i - 1, false, start), None);
                     }
                 }
             }
 
-            let f = f.clone();
-
-            scheduler.run(&mut execution, move || {
-                f();
+            if let Some(ref on_iteration) = self.on_iteration {
+                if (on_iteration.lock().unwrap())(i).is_break() {
+                    return (Stats::new(i - 1, false, start), None);
+                }
+            }
 
-                let lazy_statics = rt::execution(|execution| execution.lazy_statics.drop());
+            if let Some(heartbeat) = &execution.heartbeat {
+                heartbeat.beat(0, rt::Location::disabled());
+            }
 
-                // drop outside of execution
-                drop(lazy_statics);
+            if minimize {
+                let res = panic::catch_unwind(AssertUnwindSafe(|| {
+                    self.run(&mut scheduler, &mut execution, f, i)
+                }));
 
-                rt::thread_done();
-            });
+                if let Err(payload) = res {
+                    self.minimize(f, preemption_bound, execution.path.choices(), payload);
+                }
+            } else {
+                self.run_traced(&mut scheduler, &mut execution, f, i);
+            }
 
-            execution.check_for_leaks();
+            if let (Some(out), Some(schedule)) = (&mut schedules, &execution.schedule) {
+                // Other shards export the permutations of their units
+                if execution.path.is_assigned() {
+                    write_schedule(out, schedule).expect("failed to export the schedule");
+                }
+            }
 
             i += 1;
 
@@ -209,22 +1056,403 @@ impl Builder {
             // execution, as the `Execution` will capture the current span when
             // it's reset.
             _span = tracing::info_span!(parent: None, "iter", message = i).entered();
+            let orderings = mem::take(&mut execution.orderings);
+            let mut sharing = mem::take(&mut execution.sharing);
+            let unreleased_decrements = execution.unreleased_decrements.take();
+            let impure_updates = execution.impure_updates.take();
             if let Some(next) = execution.step() {
                 execution = next;
+                execution.orderings = orderings;
+
+                sharing.step();
+                execution.sharing = sharing;
+                execution.unreleased_decrements = unreleased_decrements;
+                execution.impure_updates = impure_updates;
+
+                if warmup > 0 {
+                    warmup -= 1;
+
+                    if warmup == 0 {
+                        execution.path.stop_random();
+                    }
+                }
             } else {
                 info!(parent: None, "Completed in {} iterations", i - 1);
-                return;
+
+                if self.report_dead_objects {
+                    for (site, kind) in sharing.dead() {
+                        warn!(parent: None, "{} created at {} was never accessed", kind, site);
+                    }
+                }
+
+                if self.report_single_thread_objects {
+                    for (site, kind) in sharing.into_single_thread() {
+                        warn!(
+                            parent: None,
+                            "{} created at {} was only accessed by a single thread",
+                            kind,
+                            site
+                        );
+                    }
+                }
+
+                for site in unreleased_decrements.into_iter().flatten() {
+                    warn!(
+                        parent: None,
+                        "`fetch_sub` at {} decremented an atomic to zero without `Release` \
+                         ordering; if it releases a reference count, accesses by other \
+                         threads may not happen-before freeing the shared data",
+                        site
+                    );
+                }
+
+                for site in impure_updates.into_iter().flatten() {
+                    warn!(
+                        parent: None,
+                        "the function passed to `fetch_update` at {} returned different \
+                         values for the same input; it must not have side effects, as \
+                         loom reruns it in every permutation",
+                        site
+                    );
+                }
+
+                return (Stats::new(i - 1, true, start), Some(orderings));
+            }
+        }
+    }
+
+    /// Runs iteration `i` of the model.
+    fn run<F>(&self, scheduler: &mut Scheduler, execution: &mut Execution, f: &Arc<F>, i: usize)
+    where
+        F: Fn() + Sync + Send + 'static,
+    {
+        let f = f.clone();
+        let warn_detached_threads = self.warn_detached_threads;
+
+        let trace_thread = self.trace_thread.is_some();
+
+        scheduler.run(execution, move || {
+            // Select the main thread for tracing before its first operation
+            if trace_thread {
+                crate::thread::current();
+            }
+
+            f();
+
+            if warn_detached_threads {
+                rt::execution(|execution| {
+                    for (id, thread) in execution.threads.iter() {
+                        if thread.detached && !thread.is_terminated() {
+                            warn!(
+                                parent: None,
+                                "detached thread {} still running when the model closure returned \
+                                 (iteration {})",
+                                id.public_id(),
+                                i
+                            );
+                        }
+                    }
+                });
             }
+
+            let lazy_statics = rt::execution(|execution| execution.lazy_statics.drop());
+
+            // drop outside of execution
+            drop(lazy_statics);
+
+            rt::thread_done();
+        });
+
+        execution.check_for_leaks();
+    }
+
+    /// Runs iteration `i` of the model, adding the operations of the traced
+    /// threads to the panic message if it fails.
+    fn run_traced<F>(
+        &self,
+        scheduler: &mut Scheduler,
+        execution: &mut Execution,
+        f: &Arc<F>,
+        i: usize,
+    ) where
+        F: Fn() + Sync + Send + 'static,
+    {
+        if self.trace_thread.is_none() {
+            return self.run(scheduler, execution, f, i);
+        }
+
+        let res = panic::catch_unwind(AssertUnwindSafe(|| self.run(scheduler, execution, f, i)));
+
+        if let Err(payload) = res {
+            panic!(
+                "{}\n\n{}",
+                panic_message(&*payload),
+                execution.traced_operations()
+            );
+        }
+    }
+
+    /// Returns a new execution following `choices`, with at most
+    /// `preemption_bound` preemptions.
+    fn replay_execution(&self, preemption_bound: Option<usize>, choices: Vec<Choice>) -> Execution {
+        let mut execution = Execution::new(
+            self.max_threads,
+            self.max_branches,
+            preemption_bound,
+            !self.expect_explicit_explore,
+        );
+
+        execution.path.start_replay(choices);
+        execution.log = self.log;
+        execution.location = self.location;
+        execution.invariants = self.invariants.clone();
+        execution.max_ops = self.max_iteration_ops;
+        execution.lock_freedom = self.check_lock_freedom;
+        execution.spurious_wakeups = self.spurious_wakeups;
+        execution.trace_thread = self.trace_thread.clone();
+        execution
+    }
+
+    /// Replays the iteration following `choices`, returning its panic message
+    /// if it failed and the choices it made.
+    fn replay<F>(
+        &self,
+        f: &Arc<F>,
+        preemption_bound: Option<usize>,
+        choices: Vec<Choice>,
+    ) -> (Option<String>, Vec<Choice>)
+    where
+        F: Fn() + Sync + Send + 'static,
+    {
+        let mut execution = self.replay_execution(preemption_bound, choices);
+        let mut scheduler = Scheduler::new(self.max_threads);
+
+        let res = quiet_panics(|| {
+            panic::catch_unwind(AssertUnwindSafe(|| {
+                self.run(&mut scheduler, &mut execution, f, 1)
+            }))
+        });
+
+        let message = res.err().map(|payload| panic_message(&*payload));
+        (message, execution.path.replayed())
+    }
+
+    /// Drops the choices of the failing iteration that are not needed to
+    /// reproduce the failure, then fails again with the remaining ones.
+    fn minimize<F>(
+        &self,
+        f: &Arc<F>,
+        preemption_bound: Option<usize>,
+        choices: Vec<Choice>,
+        payload: Box<dyn Any + Send>,
+    ) -> !
+    where
+        F: Fn() + Sync + Send + 'static,
+    {
+        let message = panic_message(&*payload);
+
+        // Returns the choices made by the iteration if it fails the same way
+        let fails = |choices: Vec<Choice>| match self.replay(f, preemption_bound, choices) {
+            (Some(other), taken) if other == message => Some(taken),
+            _ => None,
+        };
+
+        let forced = |choices: &[Choice]| choices.iter().filter(|c| c.is_some()).count();
+
+        // The model is not deterministic enough to replay the failure
+        let mut best = match fails(choices) {
+            Some(taken) => taken,
+            None => panic::resume_unwind(payload),
+        };
+
+        let original = forced(&best);
+
+        loop {
+            let mut dropped = false;
+            let mut i = 0;
+
+            while i < best.len() {
+                if best[i].is_some() {
+                    let mut choices = best.clone();
+                    choices[i] = None;
+
+                    if let Some(taken) = fails(choices) {
+                        if forced(&taken) < forced(&best) {
+                            best = taken;
+                            dropped = true;
+                        }
+                    }
+                }
+
+                i += 1;
+            }
+
+            if !dropped {
+                break;
+            }
+        }
+
+        warn!(
+            parent: None,
+            "minimized the failing schedule from {} to {} forced branch choices",
+            original,
+            forced(&best)
+        );
+
+        let mut execution = self.replay_execution(preemption_bound, best);
+        self.run_traced(&mut Scheduler::new(self.max_threads), &mut execution, f, 1);
+
+        // Replaying did not fail this time
+        panic::resume_unwind(payload)
+    }
+}
+
+/// Format of the checkpoint file, see [`Builder::checkpoint_format`
+/// field](Builder#structfield.checkpoint_format).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CheckpointFormat {
+    /// Human readable JSON, the default
+    Json,
+
+    /// Compact binary encoding, faster to write and read for large paths
+    Binary,
+}
+
+/// A weakened ordering of an atomic operation with which the model did not
+/// fail, see [`Builder::mutation_test_orderings`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Mutation {
+    /// Location of the operation
+    pub location: &'static std::panic::Location<'static>,
+
+    /// Ordering used by the operation
+    pub ordering: Ordering,
+
+    /// Weaker ordering with which the model still passed
+    pub weakened: Ordering,
+}
+
+/// Coverage of the ordering of an atomic operation, see
+/// [`Builder::ordering_coverage_report`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct OrderingCoverage {
+    /// Location of the operation
+    pub location: &'static std::panic::Location<'static>,
+
+    /// Ordering used by the operation
+    pub ordering: Ordering,
+
+    /// `true` if the model fails with any weaker ordering
+    pub exercised: bool,
+}
+
+/// Formats the entry as reported, e.g. `Release ordering at src/lib.rs:10:5:
+/// ordering not exercised`.
+impl fmt::Display for OrderingCoverage {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            fmt,
+            "{:?} ordering at {}: ordering {}",
+            self.ordering,
+            self.location,
+            if self.exercised {
+                "exercised"
+            } else {
+                "not exercised"
+            }
+        )
+    }
+}
+
+/// Statistics of a check, returned by [`Builder::check`] and passed to
+/// [`Builder::on_complete`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Stats {
+    /// Number of permutations that were checked
+    pub permutations: usize,
+
+    /// `true` if all permutations were checked, `false` if the check stopped
+    /// at `max_permutations` or `max_duration`
+    pub complete: bool,
+
+    /// Time spent checking the permutations
+    pub duration: Duration,
+}
+
+impl Stats {
+    fn new(permutations: usize, complete: bool, start: Instant) -> Stats {
+        Stats {
+            permutations,
+            complete,
+            duration: start.elapsed(),
         }
     }
 }
 
+impl fmt::Debug for Builder {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Builder")
+            .field("max_threads", &self.max_threads)
+            .field("max_branches", &self.max_branches)
+            .field("max_permutations", &self.max_permutations)
+            .field("max_duration", &self.max_duration)
+            .field("blocked_thread_timeout", &self.blocked_thread_timeout)
+            .field("preemption_bound", &self.preemption_bound)
+            .field("checkpoint_file", &self.checkpoint_file)
+            .field("checkpoint_format", &self.checkpoint_format)
+            .field("checkpoint_interval", &self.checkpoint_interval)
+            .field("expect_explicit_explore", &self.expect_explicit_explore)
+            .field("location", &self.location)
+            .field("log", &self.log)
+            .field("invariants", &self.invariants.len())
+            .field("progress", &self.progress.is_some())
+            .field("on_iteration", &self.on_iteration.is_some())
+            .field("on_complete", &self.on_complete.lock().unwrap().is_some())
+            .field("warn_detached_threads", &self.warn_detached_threads)
+            .field("random_warmup", &self.random_warmup)
+            .field("suggest_orderings", &self.suggest_orderings)
+            .field("minimize_failures", &self.minimize_failures)
+            .field(
+                "report_single_thread_objects",
+                &self.report_single_thread_objects,
+            )
+            .field("report_dead_objects", &self.report_dead_objects)
+            .field("max_iteration_ops", &self.max_iteration_ops)
+            .field("check_lock_freedom", &self.check_lock_freedom)
+            .field("trace_thread", &self.trace_thread.is_some())
+            .field("refcount_lint", &self.refcount_lint)
+            .field("check_update_purity", &self.check_update_purity)
+            .field("spurious_wakeups", &self.spurious_wakeups)
+            .field("export_schedules", &self.export_schedules)
+            .field("shard", &self.shard)
+            .finish()
+    }
+}
+
 impl Default for Builder {
     fn default() -> Self {
         Self::new()
     }
 }
 
+/// Writes a schedule as a line of the file exported by
+/// `Builder::export_schedules`.
+fn write_schedule(
+    out: &mut impl Write,
+    schedule: &[(usize, &'static str, usize)],
+) -> io::Result<()> {
+    for (i, (thread, kind, object)) in schedule.iter().enumerate() {
+        let sep = if i == 0 { "" } else { " " };
+        write!(out, "{}{}:{}:{}", sep, thread, kind, object)?;
+    }
+
+    writeln!(out)
+}
+
 /// Run all concurrent permutations of the provided closure.
 ///
 /// Uses a default [`Builder`] which can be affected by environment variables.
@@ -232,7 +1460,7 @@ pub fn model<F>(f: F)
 where
     F: Fn() + Sync + Send + 'static,
 {
-    let subscriber = fmt::Subscriber::builder()
+    let subscriber = tracing_subscriber::fmt::Subscriber::builder()
         .with_env_filter(EnvFilter::from_env("LOOM_LOG"))
         .with_test_writer()
         .without_time()
@@ -243,36 +1471,140 @@ where
     });
 }
 
+/// Returns the message of a panic, or an empty string if it has none.
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        String::new()
+    }
+}
+
+thread_local! {
+    static QUIET_PANICS: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Runs `f` without printing the panics of the current thread.
+///
+/// The panic hook is wrapped while `f` runs, then the previous one is
+/// restored.
+fn quiet_panics<R>(f: impl FnOnce() -> R) -> R {
+    // Serializes the hook changes of models checked concurrently
+    static HOOK: Mutex<()> = Mutex::new(());
+
+    let _lock = HOOK.lock().unwrap_or_else(PoisonError::into_inner);
+    let hook = Arc::new(panic::take_hook());
+
+    panic::set_hook({
+        let hook = hook.clone();
+
+        Box::new(move |info| {
+            if !QUIET_PANICS.with(Cell::get) {
+                hook(info);
+            }
+        })
+    });
+
+    QUIET_PANICS.with(|quiet| quiet.set(true));
+    let ret = panic::catch_unwind(AssertUnwindSafe(f));
+    QUIET_PANICS.with(|quiet| quiet.set(false));
+
+    // Dropping the wrapper releases its reference to the previous hook
+    drop(panic::take_hook());
+
+    match Arc::try_unwrap(hook) {
+        Ok(hook) => panic::set_hook(hook),
+        Err(_) => unreachable!("[loom internal bug] panic hook still referenced"),
+    }
+
+    ret.unwrap_or_else(|payload| panic::resume_unwind(payload))
+}
+
 #[cfg(feature = "checkpoint")]
 mod checkpoint {
+    use super::CheckpointFormat;
+
+    use serde::{Deserialize, Serialize};
     use std::fs::File;
     use std::io::prelude::*;
+    use std::io::{BufReader, BufWriter};
     use std::path::Path;
 
-    pub(crate) fn load_execution_path(fs_path: &Path) -> crate::rt::Path {
-        let mut file = File::open(fs_path).unwrap();
-        let mut contents = String::new();
-        file.read_to_string(&mut contents).unwrap();
-        serde_json::from_str(&contents).unwrap()
+    /// Contents of the checkpoint file
+    #[derive(Serialize, Deserialize)]
+    struct Checkpoint<P> {
+        /// Iteration to resume from
+        iteration: usize,
+
+        /// Execution path of that iteration
+        path: P,
     }
 
-    pub(crate) fn store_execution_path(path: &crate::rt::Path, fs_path: &Path) {
-        let serialized = serde_json::to_string(path).unwrap();
+    pub(crate) fn load_execution_path(
+        fs_path: &Path,
+        format: CheckpointFormat,
+    ) -> (usize, crate::rt::Path) {
+        let file = File::open(fs_path).unwrap();
+
+        let checkpoint: Checkpoint<crate::rt::Path> = match format {
+            CheckpointFormat::Json => {
+                let mut contents = String::new();
+                BufReader::new(file).read_to_string(&mut contents).unwrap();
 
-        let mut file = File::create(fs_path).unwrap();
-        file.write_all(serialized.as_bytes()).unwrap();
+                // Checkpoints written by older versions only contain the path
+                serde_json::from_str(&contents).unwrap_or_else(|_| Checkpoint {
+                    iteration: 1,
+                    path: serde_json::from_str(&contents).unwrap(),
+                })
+            }
+            CheckpointFormat::Binary => bincode::deserialize_from(BufReader::new(file)).unwrap(),
+        };
+
+        (checkpoint.iteration, checkpoint.path)
+    }
+
+    pub(crate) fn store_execution_path(
+        iteration: usize,
+        path: &crate::rt::Path,
+        fs_path: &Path,
+        format: CheckpointFormat,
+    ) {
+        let checkpoint = Checkpoint { iteration, path };
+        let mut file = BufWriter::new(File::create(fs_path).unwrap());
+
+        match format {
+            CheckpointFormat::Json => {
+                let serialized = serde_json::to_string(&checkpoint).unwrap();
+                file.write_all(serialized.as_bytes()).unwrap();
+            }
+            CheckpointFormat::Binary => bincode::serialize_into(&mut file, &checkpoint).unwrap(),
+        }
+
+        file.flush().unwrap();
     }
 }
 
 #[cfg(not(feature = "checkpoint"))]
 mod checkpoint {
+    use super::CheckpointFormat;
+
     use std::path::Path;
 
-    pub(crate) fn load_execution_path(_fs_path: &Path) -> crate::rt::Path {
+    pub(crate) fn load_execution_path(
+        _fs_path: &Path,
+        _format: CheckpointFormat,
+    ) -> (usize, crate::rt::Path) {
         panic!("not compiled with `checkpoint` feature")
     }
 
-    pub(crate) fn store_execution_path(_path: &crate::rt::Path, _fs_path: &Path) {
+    pub(crate) fn store_execution_path(
+        _iteration: usize,
+        _path: &crate::rt::Path,
+        _fs_path: &Path,
+        _format: CheckpointFormat,
+    ) {
         panic!("not compiled with `checkpoint` feature")
     }
 }
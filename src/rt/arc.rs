@@ -1,7 +1,7 @@
 use crate::rt::object;
 use crate::rt::{self, Access, Location, Synchronize, VersionVec};
 
-use std::sync::atomic::Ordering::{Acquire, Release, SeqCst};
+use std::sync::atomic::Ordering::{Acquire, Release};
 
 use tracing::trace;
 #[derive(Debug)]
@@ -14,6 +14,9 @@ pub(super) struct State {
     /// Reference count
     ref_cnt: usize,
 
+    /// Number of `Weak` references
+    weak_cnt: usize,
+
     /// Location where the arc was allocated
     allocated: Location,
 
@@ -23,16 +26,18 @@ pub(super) struct State {
     synchronize: Synchronize,
 
     /// Tracks access to the arc object
-    last_ref_inc: Option<Access>,
     last_ref_dec: Option<Access>,
-    last_ref_inspect: Option<Access>,
-    last_ref_modification: Option<RefModify>,
 }
 
 /// Actions performed on the Arc
 ///
-/// Clones are only dependent with inspections. Drops are dependent between each
-/// other.
+/// Clones are independent of each other. Drops are dependent between each
+/// other, and with upgrading a `Weak`, which is modeled as a drop: it races
+/// with dropping the last `Arc`. Creating and dropping a `Weak` is independent
+/// of everything.
+///
+/// Reading the reference count is not an action: it is advisory and does not
+/// introduce a branch point.
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub(super) enum Action {
     /// Clone the arc
@@ -41,21 +46,22 @@ pub(super) enum Action {
     /// Drop the Arc
     RefDec,
 
-    /// Inspect internals (such as get ref count). This is done with SeqCst
-    /// causality
-    Inspect,
+    /// Create or drop a `Weak`
+    WeakRef,
 }
 
-/// Actions which modify the Arc's reference count
-///
-/// This is used to ascertain dependence for Action::Inspect
+/// Outcome of `Arc::make_mut`
 #[derive(Debug, Copy, Clone, PartialEq)]
-enum RefModify {
-    /// Corresponds to Action::RefInc
-    RefInc,
+pub(crate) enum MakeMut {
+    /// Other `Arc`s point to the value
+    Shared,
 
-    /// Corresponds to Action::RefDec
-    RefDec,
+    /// The `Arc` is the only pointer to the value
+    Unique,
+
+    /// The `Arc` was the only strong pointer to the value, and was released
+    /// as `Weak` pointers remain
+    Released,
 }
 
 impl Arc {
@@ -63,12 +69,10 @@ impl Arc {
         rt::execution(|execution| {
             let state = execution.objects.insert(State {
                 ref_cnt: 1,
+                weak_cnt: 0,
                 allocated: location,
                 synchronize: Synchronize::new(),
-                last_ref_inc: None,
                 last_ref_dec: None,
-                last_ref_inspect: None,
-                last_ref_modification: None,
             });
 
             trace!(?state, %location, "Arc::new");
@@ -77,6 +81,37 @@ impl Arc {
         })
     }
 
+    /// Creates the object of an `Arc` under construction by `new_cyclic`,
+    /// with no strong reference and the `Weak` passed to the closure.
+    pub(crate) fn new_cyclic(location: Location) -> Arc {
+        rt::execution(|execution| {
+            let state = execution.objects.insert(State {
+                ref_cnt: 0,
+                weak_cnt: 1,
+                allocated: location,
+                synchronize: Synchronize::new(),
+                last_ref_dec: None,
+            });
+
+            trace!(?state, %location, "Arc::new_cyclic");
+
+            Arc { state }
+        })
+    }
+
+    /// Completes the construction started by `new_cyclic`, creating the
+    /// first strong reference.
+    pub(crate) fn init_cyclic(&self) {
+        rt::execution(|execution| {
+            let state = self.state.get_mut(&mut execution.objects);
+
+            assert_eq!(0, state.ref_cnt, "Arc is already initialized");
+            state.ref_cnt = 1;
+
+            trace!(state = ?self.state, "Arc::init_cyclic");
+        })
+    }
+
     pub(crate) fn ref_inc(&self, location: Location) {
         self.branch(Action::RefInc, location);
 
@@ -108,6 +143,39 @@ impl Arc {
         })
     }
 
+    /// Validate a `make_mut` call. Like `get_mut`, but when `Weak` pointers
+    /// remain, the only strong reference is released as part of the same
+    /// branch point, as `std` does before moving the value out.
+    pub(crate) fn make_mut(&self, location: Location) -> MakeMut {
+        self.branch(Action::RefDec, location);
+
+        rt::execution(|execution| {
+            let state = self.state.get_mut(&mut execution.objects);
+
+            assert!(state.ref_cnt >= 1, "Arc is released");
+
+            // Synchronize the threads
+            state.synchronize.sync_load(&mut execution.threads, Acquire);
+
+            let make_mut = if state.ref_cnt > 1 {
+                MakeMut::Shared
+            } else if state.weak_cnt == 0 {
+                MakeMut::Unique
+            } else {
+                state.ref_cnt = 0;
+                state
+                    .synchronize
+                    .sync_store(&mut execution.threads, Release);
+
+                MakeMut::Released
+            };
+
+            trace!(state = ?self.state, ?make_mut, %location, "Arc::make_mut");
+
+            make_mut
+        })
+    }
+
     /// Returns true if the memory should be dropped.
     pub(crate) fn ref_dec(&self, location: Location) -> bool {
         self.branch(Action::RefDec, location);
@@ -140,16 +208,72 @@ impl Arc {
         })
     }
 
-    #[track_caller]
-    pub(crate) fn strong_count(&self) -> usize {
-        self.branch(Action::Inspect, location!());
+    /// Creates a `Weak` reference.
+    pub(crate) fn weak_inc(&self, location: Location) {
+        self.branch(Action::WeakRef, location);
 
         rt::execution(|execution| {
             let state = self.state.get_mut(&mut execution.objects);
-            assert!(state.ref_cnt > 0, "Arc is already released");
+            state.weak_cnt = state.weak_cnt.checked_add(1).expect("overflow");
 
-            // Synchronize the threads.
-            state.synchronize.sync_load(&mut execution.threads, SeqCst);
+            trace!(state = ?self.state, weak_cnt = ?state.weak_cnt, %location, "Arc::weak_inc");
+        })
+    }
+
+    /// Drops a `Weak` reference.
+    pub(crate) fn weak_dec(&self, location: Location) {
+        self.branch(Action::WeakRef, location);
+
+        rt::execution(|execution| {
+            let state = self.state.get_mut(&mut execution.objects);
+
+            assert!(state.weak_cnt >= 1, "Weak is already released");
+            state.weak_cnt -= 1;
+
+            trace!(state = ?self.state, weak_cnt = ?state.weak_cnt, %location, "Arc::weak_dec");
+        })
+    }
+
+    /// Upgrades a `Weak` reference. Returns `false` if the value was already
+    /// dropped.
+    pub(crate) fn upgrade(&self, location: Location) -> bool {
+        self.branch(Action::RefDec, location);
+
+        rt::execution(|execution| {
+            let state = self.state.get_mut(&mut execution.objects);
+
+            assert!(state.weak_cnt >= 1, "Weak is already released");
+
+            let upgraded = state.ref_cnt != 0;
+
+            if upgraded {
+                state.ref_cnt = state.ref_cnt.checked_add(1).expect("overflow");
+            }
+
+            trace!(state = ?self.state, ?upgraded, %location, "Arc::upgrade");
+
+            upgraded
+        })
+    }
+
+    /// Returns the strong and weak reference counts as of the current point in
+    /// the execution, including once the value was dropped.
+    ///
+    /// This is not a branch point and does not synchronize with other threads.
+    pub(crate) fn counts(&self) -> (usize, usize) {
+        rt::execution(|execution| {
+            let state = self.state.get(&execution.objects);
+            (state.ref_cnt, state.weak_cnt)
+        })
+    }
+
+    /// Returns the reference count as of the current point in the execution.
+    ///
+    /// This is not a branch point and does not synchronize with other threads.
+    pub(crate) fn strong_count(&self) -> usize {
+        rt::execution(|execution| {
+            let state = self.state.get(&execution.objects);
+            assert!(state.ref_cnt > 0, "Arc is already released");
 
             state.ref_cnt
         })
@@ -169,42 +293,37 @@ impl Arc {
 
 impl State {
     pub(super) fn check_for_leaks(&self, index: usize) {
-        if self.ref_cnt != 0 {
-            if self.allocated.is_captured() {
-                panic!(
-                    "Arc leaked.\n  Allocated: {}\n      Index: {}",
-                    self.allocated, index
-                );
-            } else {
-                panic!("Arc leaked.\n  Index: {}", index);
-            }
+        let kind = if self.ref_cnt != 0 {
+            "Arc"
+        } else if self.weak_cnt != 0 {
+            "Weak"
+        } else {
+            return;
+        };
+
+        if self.allocated.is_captured() {
+            panic!(
+                "{} leaked.\n  Allocated: {}\n      Index: {}",
+                kind, self.allocated, index
+            );
+        } else {
+            panic!("{} leaked.\n  Index: {}", kind, index);
         }
     }
 
     pub(super) fn last_dependent_access(&self, action: Action) -> Option<&Access> {
         match action {
-            // RefIncs are not dependent w/ RefDec, only inspections
-            Action::RefInc => self.last_ref_inspect.as_ref(),
+            // RefIncs are not dependent w/ RefDec
+            Action::RefInc | Action::WeakRef => None,
             Action::RefDec => self.last_ref_dec.as_ref(),
-            Action::Inspect => match self.last_ref_modification {
-                Some(RefModify::RefInc) => self.last_ref_inc.as_ref(),
-                Some(RefModify::RefDec) => self.last_ref_dec.as_ref(),
-                None => None,
-            },
         }
     }
 
     pub(super) fn set_last_access(&mut self, action: Action, path_id: usize, version: &VersionVec) {
         match action {
-            Action::RefInc => {
-                self.last_ref_modification = Some(RefModify::RefInc);
-                Access::set_or_create(&mut self.last_ref_inc, path_id, version)
-            }
-            Action::RefDec => {
-                self.last_ref_modification = Some(RefModify::RefDec);
-                Access::set_or_create(&mut self.last_ref_dec, path_id, version)
-            }
-            Action::Inspect => Access::set_or_create(&mut self.last_ref_inspect, path_id, version),
+            // Nothing is dependent on a RefInc
+            Action::RefInc | Action::WeakRef => {}
+            Action::RefDec => Access::set_or_create(&mut self.last_ref_dec, path_id, version),
         }
     }
 }
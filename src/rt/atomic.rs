@@ -54,23 +54,29 @@
 //! - Fence Synchronization (Collapsed Store)
 //! - Fence Synchronization (Collapsed Load)
 
-use crate::rt::execution::Execution;
+use crate::rt::execution::{self, Execution};
 use crate::rt::location::{self, Location, LocationSet};
 use crate::rt::object;
+use crate::rt::orderings::Site;
 use crate::rt::{
     self, thread, Access, Numeric, Synchronize, VersionVec, MAX_ATOMIC_HISTORY, MAX_THREADS,
 };
 
 use std::cmp;
+use std::fmt;
 use std::marker::PhantomData;
+use std::panic::{self, AssertUnwindSafe};
 use std::sync::atomic::Ordering;
-use std::u16;
 
 use tracing::trace;
 
-#[derive(Debug)]
 pub(crate) struct Atomic<T> {
     state: object::Ref<State>,
+
+    /// Where the atomic was created. Unlike the index of `state`, this is the
+    /// same in every execution, so it labels the atomic in the trace output.
+    site: Site,
+
     _p: PhantomData<fn() -> T>,
 }
 
@@ -79,6 +85,10 @@ pub(super) struct State {
     /// Where the atomic was created
     created_location: Location,
 
+    /// When `true`, locations are captured for accesses to this cell even if
+    /// location tracking is disabled for the execution.
+    track_locations: bool,
+
     /// Transitive closure of all atomic loads from the cell.
     loaded_at: VersionVec,
 
@@ -124,6 +134,23 @@ pub(super) struct State {
 
     /// The total number of stores to the cell.
     cnt: u16,
+
+    /// Number of stores and successful read-modify-write operations performed
+    /// by each thread.
+    modifications: [usize; MAX_THREADS],
+
+    /// `true` while every atomic access to the cell has used `SeqCst`
+    /// ordering. The initialization of the cell is not counted.
+    seq_cst_only: bool,
+
+    /// True if a weak read-modify-write on the atomic already failed
+    /// spuriously. This limits spurious failures to one per atomic and
+    /// execution.
+    did_spur: bool,
+    /// When set, stores must not decrease the value of the cell. Given the
+    /// previous and the stored value, returns a description of the decrease,
+    /// if any.
+    monotonic: Option<fn(u64, u64) -> Option<String>>,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq)]
@@ -210,22 +237,60 @@ fn fence_seqcst(execution: &mut Execution) {
 
 impl<T: Numeric> Atomic<T> {
     /// Create a new, atomic cell initialized with the provided value
-    pub(crate) fn new(value: T, location: Location) -> Atomic<T> {
+    pub(crate) fn new(value: T, location: Location, site: Site) -> Atomic<T> {
         rt::execution(|execution| {
             let state = State::new(&mut execution.threads, value.into_u64(), location);
             let state = execution.objects.insert(state);
 
-            trace!(?state, "Atomic::new");
-
-            Atomic {
+            let atomic = Atomic {
                 state,
+                site,
                 _p: PhantomData,
-            }
+            };
+
+            trace!(?atomic, ?state, "Atomic::new");
+
+            atomic
         })
     }
 
     /// Loads a value from the atomic cell.
     pub(crate) fn load(&self, location: Location, ordering: Ordering) -> T {
+        self.load_from(location, ordering).0
+    }
+
+    /// Loads a value from the atomic cell with `consume` ordering, passing it
+    /// to `f`.
+    ///
+    /// The load is `Relaxed`, except for the accesses performed by `f`, which
+    /// depend on the value: they happen after the store the value was read
+    /// from, as with an `Acquire` load. Once `f` returns, the thread's
+    /// causality is reverted, keeping only what it acquired from other
+    /// synchronizations performed by `f`.
+    pub(crate) fn load_consume<R>(&self, location: Location, f: impl FnOnce(T) -> R) -> R {
+        let (value, happens_before) = self.load_from(location, Ordering::Relaxed);
+
+        let (before, entered) = rt::execution(|execution| {
+            let causality = &mut execution.threads.active_mut().causality;
+            let before = *causality;
+
+            causality.join(&happens_before);
+            (before, *causality)
+        });
+
+        let ret = f(value);
+
+        rt::execution(|execution| {
+            let causality = &mut execution.threads.active_mut().causality;
+            causality.revert_unchanged(&entered, &before);
+        });
+
+        ret
+    }
+
+    /// Loads a value from the atomic cell, also returning the causality
+    /// released by the store it was read from.
+    fn load_from(&self, location: Location, ordering: Ordering) -> (T, VersionVec) {
         self.branch(Action::Load, location);
 
         super::synchronize(|execution| {
@@ -237,15 +302,19 @@ impl<T: Numeric> Atomic<T> {
 
                 let n = state.match_load_to_stores(&execution.threads, &mut seed[..], ordering);
 
-                execution.path.push_load(&seed[..n]);
+                execution.path.push_load(&seed[..n], index(state.cnt - 1));
             }
 
             // Get the store to return from this load.
             let index = execution.path.branch_load();
 
-            trace!(state = ?self.state, ?ordering, "Atomic::load");
+            trace!(atomic = ?self, ?ordering, "Atomic::load");
 
-            T::from_u64(state.load(&mut execution.threads, index, location, ordering))
+            let value = state.load(&mut execution.threads, index, location, ordering);
+            (
+                T::from_u64(value),
+                state.stores[index].sync.happens_before(),
+            )
         })
     }
 
@@ -261,7 +330,7 @@ impl<T: Numeric> Atomic<T> {
             // An unsync load counts as a "read" access
             state.track_unsync_load(&execution.threads);
 
-            trace!(state = ?self.state, "Atomic::unsync_load");
+            trace!(atomic = ?self, "Atomic::unsync_load");
 
             // Return the value
             let index = index(state.cnt - 1);
@@ -281,14 +350,19 @@ impl<T: Numeric> Atomic<T> {
             // An atomic store counts as a read access to the underlying memory
             // cell.
             state.track_store(&execution.threads);
+            state.track_ordering(ordering);
+            state.track_modification(&execution.threads);
+
+            trace!(atomic = ?self, ?ordering, "Atomic::store");
 
-            trace!(state = ?self.state, ?ordering, "Atomic::store");
+            execution.threads.progress();
 
             // Do the store
             state.store(
                 &mut execution.threads,
                 Synchronize::new(),
                 val.into_u64(),
+                location,
                 ordering,
             );
         })
@@ -301,12 +375,44 @@ impl<T: Numeric> Atomic<T> {
         failure: Ordering,
         f: F,
     ) -> Result<T, E>
+    where
+        F: FnOnce(T) -> Result<T, E>,
+    {
+        self.try_rmw(location, success, failure, None, f)
+    }
+
+    /// Like `rmw`, but a successful update may fail spuriously instead,
+    /// returning the current value, as `compare_exchange_weak` does.
+    ///
+    /// Only one spurious failure is explored per atomic and execution, so that
+    /// retry loops terminate.
+    pub(crate) fn rmw_weak<F>(
+        &self,
+        location: Location,
+        success: Ordering,
+        failure: Ordering,
+        f: F,
+    ) -> Result<T, T>
+    where
+        F: FnOnce(T) -> Result<T, T>,
+    {
+        self.try_rmw(location, success, failure, Some(|actual| actual), f)
+    }
+
+    fn try_rmw<F, E>(
+        &self,
+        location: Location,
+        success: Ordering,
+        failure: Ordering,
+        spurious: Option<fn(T) -> E>,
+        f: F,
+    ) -> Result<T, E>
     where
         F: FnOnce(T) -> Result<T, E>,
     {
         self.branch(Action::Rmw, location);
 
-        super::synchronize(|execution| {
+        let res = super::synchronize(|execution| {
             let state = self.state.get_mut(&mut execution.objects);
 
             // If necessary, generate the list of stores to permute through
@@ -314,24 +420,150 @@ impl<T: Numeric> Atomic<T> {
                 let mut seed = [0; MAX_ATOMIC_HISTORY];
 
                 let n = state.match_rmw_to_stores(&mut seed[..]);
-                execution.path.push_load(&seed[..n]);
+                execution.path.push_load(&seed[..n], index(state.cnt - 1));
             }
 
             // Get the store to use for the read portion of the rmw operation.
             let index = execution.path.branch_load();
 
-            trace!(state = ?self.state, ?success, ?failure, "Atomic::rmw");
+            trace!(atomic = ?self, ?success, ?failure, "Atomic::rmw");
 
-            state
-                .rmw(
-                    &mut execution.threads,
-                    index,
-                    location,
-                    success,
-                    failure,
-                    |num| f(T::from_u64(num)).map(T::into_u64),
-                )
-                .map(T::from_u64)
+            let may_spur = !state.did_spur;
+            let path = &mut execution.path;
+            let mut spurred = false;
+            let mut panicked = None;
+
+            // If `f` panics, the operation completes as a failed update
+            // before the panic resumes, outside of the execution.
+            let res = state.rmw(
+                &mut execution.threads,
+                index,
+                location,
+                success,
+                failure,
+                |num| {
+                    let res = match panic::catch_unwind(AssertUnwindSafe(|| f(T::from_u64(num)))) {
+                        Ok(res) => res,
+                        Err(payload) => {
+                            panicked = Some(payload);
+                            return Err(None);
+                        }
+                    };
+
+                    match (res, spurious) {
+                        // Fail spuriously, leaving the value unchanged
+                        (Ok(_), Some(spurious)) if may_spur && path.branch_spurious() => {
+                            spurred = true;
+                            Err(Some(spurious(T::from_u64(num))))
+                        }
+                        (res, _) => res.map(T::into_u64).map_err(Some),
+                    }
+                },
+            );
+
+            state.did_spur |= spurred;
+
+            match panicked {
+                Some(payload) => Err(payload),
+                None => Ok(res.map(T::from_u64).map_err(Option::unwrap)),
+            }
+        });
+
+        res.unwrap_or_else(|payload| panic::resume_unwind(payload))
+    }
+
+    /// Tracks the threads accessing the cell.
+    pub(crate) fn track_sharing(&self) {
+        self.state.track_sharing("atomic", self.site);
+    }
+
+    /// Returns the value of the most recent store, for `Debug` output.
+    ///
+    /// This is neither a branch point nor an access to the cell. Returns `None`
+    /// when called from outside the execution.
+    pub(crate) fn debug_value(&self) -> Option<T> {
+        rt::try_execution(|execution| {
+            let state = self.state.get(&execution.objects);
+            T::from_u64(state.stores[index(state.cnt - 1)].value)
+        })
+    }
+
+    /// Returns `true` if every other live thread already observed the newest
+    /// store of `value`, or a store that follows it in modification order.
+    ///
+    /// This is neither a branch point nor an access to the cell.
+    pub(crate) fn is_visible_to_all(&self, value: T) -> bool {
+        rt::execution(|execution| {
+            let state = self.state.get(&execution.objects);
+            let threads = &execution.threads;
+            let value = value.into_u64();
+
+            // Stores in the history, newest first
+            let history = cmp::min(state.cnt, MAX_ATOMIC_HISTORY as u16);
+            let stores = || (1..=history).map(|k| &state.stores[index(state.cnt - k)]);
+
+            let store_i = stores()
+                .find(|store| store.value == value)
+                .expect("value was not stored to the atomic cell");
+
+            threads
+                .iter()
+                .filter(|&(id, thread)| id != threads.active_id() && !thread.is_terminated())
+                .all(|(_, thread)| {
+                    stores().any(|store_j| {
+                        (std::ptr::eq(store_i, store_j)
+                            || store_i.modification_order < store_j.modification_order)
+                            && store_j
+                                .first_seen
+                                .is_seen_by(&thread.causality, threads.execution_id())
+                    })
+                })
+        })
+    }
+
+    /// Capture locations for accesses to this cell, regardless of the
+    /// execution's setting.
+    pub(crate) fn track_locations(&self) {
+        rt::execution(|execution| {
+            self.state.get_mut(&mut execution.objects).track_locations = true;
+        })
+    }
+
+    /// Panics if a later store or read-modify-write decreases the value of
+    /// the cell. Locations are captured for accesses to the cell, so that the
+    /// offending store is reported.
+    pub(crate) fn assert_monotonic(&self)
+    where
+        T: Ord + fmt::Debug,
+    {
+        fn decrease<T: Numeric + fmt::Debug + Ord>(prev: u64, next: u64) -> Option<String> {
+            let (prev, next) = (T::from_u64(prev), T::from_u64(next));
+            (next < prev).then(|| format!("from {:?} to {:?}", prev, next))
+        }
+
+        rt::execution(|execution| {
+            let state = self.state.get_mut(&mut execution.objects);
+            state.monotonic = Some(decrease::<T>);
+            state.track_locations = true;
+        })
+    }
+
+    /// Returns the location to record for an access from `caller`.
+    pub(crate) fn location(&self, caller: &'static std::panic::Location<'static>) -> Location {
+        rt::execution(|execution| {
+            if execution.location || self.state.get(&execution.objects).track_locations {
+                Location::from(caller)
+            } else {
+                Location::disabled()
+            }
+        })
+    }
+
+    /// Returns the number of stores and successful read-modify-write
+    /// operations performed by `thread` in the current execution.
+    pub(crate) fn modification_count(&self, thread: thread::Id) -> usize {
+        rt::execution(|execution| {
+            self.state.get(&execution.objects).modifications[thread.as_usize()]
         })
     }
 
@@ -349,7 +581,7 @@ impl<T: Numeric> Atomic<T> {
             state.track_unsync_mut(&execution.threads);
             state.is_mutating = true;
 
-            trace!(state = ?self.state, "Atomic::with_mut");
+            trace!(atomic = ?self, "Atomic::with_mut");
 
             // Return the value of the most recent store
             let index = index(state.cnt - 1);
@@ -396,12 +628,19 @@ impl<T: Numeric> Atomic<T> {
     }
 }
 
+impl<T> fmt::Debug for Atomic<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "Atomic@{}:{}", self.site.file(), self.site.line())
+    }
+}
+
 // ===== impl State =====
 
 impl State {
     fn new(threads: &mut thread::Set, value: u64, location: Location) -> State {
         let mut state = State {
             created_location: location,
+            track_locations: false,
             loaded_at: VersionVec::new(),
             loaded_locations: LocationSet::new(),
             unsync_loaded_at: VersionVec::new(),
@@ -415,6 +654,10 @@ impl State {
             last_non_load_access: None,
             stores: Default::default(),
             cnt: 0,
+            modifications: [0; MAX_THREADS],
+            seq_cst_only: true,
+            did_spur: false,
+            monotonic: None,
         };
 
         // All subsequent accesses must happen-after.
@@ -427,7 +670,13 @@ impl State {
         // creation of this atomic cell.
         //
         // This is verified using `cell`.
-        state.store(threads, Synchronize::new(), value, Ordering::Release);
+        state.store(
+            threads,
+            Synchronize::new(),
+            value,
+            location,
+            Ordering::Release,
+        );
 
         state
     }
@@ -442,14 +691,18 @@ impl State {
         self.loaded_locations.track(location, threads);
         // Validate memory safety
         self.track_load(threads);
+        self.track_ordering(ordering);
 
         // Apply coherence rules
         self.apply_load_coherence(threads, index);
 
         let store = &mut self.stores[index];
 
+        let before = threads.active().causality;
+
         store.first_seen.touch(threads);
         store.sync.sync_load(threads, ordering);
+        threads.track_acquire(&before, location);
         store.value
     }
 
@@ -458,8 +711,27 @@ impl State {
         threads: &mut thread::Set,
         mut sync: Synchronize,
         value: u64,
+        location: Location,
         ordering: Ordering,
     ) {
+        if let Some(decrease) = self.monotonic {
+            let prev = self.stores[index(self.cnt - 1)].value;
+
+            if let Some(decrease) = decrease(prev, value) {
+                location::panic(format!(
+                    "Monotonicity violation: atomic value decreased {}.",
+                    decrease
+                ))
+                .location("created", self.created_location)
+                .thread(
+                    "store",
+                    threads.active_id(),
+                    self.stored_locations[&*threads],
+                )
+                .fire();
+            }
+        }
+
         let index = index(self.cnt);
 
         // Increment the count
@@ -484,6 +756,13 @@ impl State {
 
         sync.sync_store(threads, ordering);
 
+        if matches!(
+            ordering,
+            Ordering::Release | Ordering::AcqRel | Ordering::SeqCst
+        ) {
+            threads.track_release(location);
+        }
+
         let mut first_seen = FirstSeen::new();
         first_seen.touch(threads);
 
@@ -512,6 +791,8 @@ impl State {
         // Track the load is happening in order to ensure correct
         // synchronization to the underlying cell.
         self.track_load(threads);
+        self.track_ordering(success);
+        self.track_ordering(failure);
 
         // Apply coherence rules.
         self.apply_load_coherence(threads, index);
@@ -525,26 +806,34 @@ impl State {
                 self.stored_locations.track(location, threads);
                 // Track a store operation happened
                 self.track_store(threads);
+                self.track_modification(threads);
 
                 // Perform load synchronization using the `success` ordering.
+                let before = threads.active().causality;
                 self.stores[index].sync.sync_load(threads, success);
+                threads.track_acquire(&before, location);
 
                 // Store the new value, initializing with the `sync` value from
                 // the load. This is our (hacky) way to establish a release
                 // sequence.
                 let sync = self.stores[index].sync;
-                self.store(threads, sync, next, success);
+                threads.progress();
+                self.store(threads, sync, next, location, success);
 
                 Ok(prev)
             }
             Err(e) => {
+                let before = threads.active().causality;
                 self.stores[index].sync.sync_load(threads, failure);
+                threads.track_acquire(&before, location);
                 Err(e)
             }
         }
     }
 
     fn apply_load_coherence(&mut self, threads: &mut thread::Set, index: usize) {
+        let prev = self.stores[index].modification_order;
+
         for i in 0..self.stores.len() {
             // Skip if the is current.
             if index == i {
@@ -563,6 +852,27 @@ impl State {
                 self.stores[index].modification_order.join(&mo);
             }
         }
+
+        // Stores that were ordered after the loaded one must stay so, or a
+        // thread could read an older store than its own latest one.
+        let mo = self.stores[index].modification_order;
+
+        for (i, store) in self.stores.iter_mut().enumerate() {
+            if i != index && i < self.cnt as usize && prev < store.modification_order {
+                store.modification_order.join(&mo);
+            }
+        }
+    }
+
+    /// Track a modification of the atomic by the active thread
+    fn track_modification(&mut self, threads: &thread::Set) {
+        self.modifications[threads.active_id().as_usize()] += 1;
+    }
+
+    /// Track the ordering of an operation, to know whether the atomic is only
+    /// accessed with `SeqCst` ordering
+    fn track_ordering(&mut self, ordering: Ordering) {
+        self.seq_cst_only &= is_seq_cst(ordering);
     }
 
     /// Track an atomic load
@@ -576,6 +886,10 @@ impl State {
                 .location("created", self.created_location)
                 .thread("with_mut", mut_at, self.unsync_mut_locations[mut_at])
                 .thread("load", threads.active_id(), self.loaded_locations[threads])
+                .unordered(
+                    ("`with_mut`", mut_at, &self.unsync_mut_at),
+                    ("load", threads),
+                )
                 .fire();
         }
 
@@ -597,6 +911,10 @@ impl State {
                     threads.active_id(),
                     self.unsync_loaded_locations[threads],
                 )
+                .unordered(
+                    ("`with_mut`", mut_at, &self.unsync_mut_at),
+                    ("`unsync_load`", threads),
+                )
                 .fire();
         }
 
@@ -609,6 +927,10 @@ impl State {
                     threads.active_id(),
                     self.unsync_loaded_locations[threads],
                 )
+                .unordered(
+                    ("atomic store", stored, &self.stored_at),
+                    ("`unsync_load`", threads),
+                )
                 .fire();
         }
 
@@ -630,6 +952,10 @@ impl State {
                     threads.active_id(),
                     self.stored_locations[threads],
                 )
+                .unordered(
+                    ("`with_mut`", mut_at, &self.unsync_mut_at),
+                    ("atomic store", threads),
+                )
                 .fire();
         }
 
@@ -644,6 +970,10 @@ impl State {
                 threads.active_id(),
                 self.stored_locations[threads],
             )
+            .unordered(
+                ("`unsync_load`", loaded, &self.unsync_loaded_at),
+                ("atomic store", threads),
+            )
             .fire();
         }
 
@@ -665,6 +995,10 @@ impl State {
                     threads.active_id(),
                     self.unsync_mut_locations[threads],
                 )
+                .unordered(
+                    ("atomic load", loaded, &self.loaded_at),
+                    ("`with_mut`", threads),
+                )
                 .fire();
         }
 
@@ -679,6 +1013,10 @@ impl State {
                 threads.active_id(),
                 self.unsync_mut_locations[threads],
             )
+            .unordered(
+                ("`unsync_load`", loaded, &self.unsync_loaded_at),
+                ("`with_mut`", threads),
+            )
             .fire();
         }
 
@@ -693,6 +1031,10 @@ impl State {
                 threads.active_id(),
                 self.unsync_mut_locations[threads],
             )
+            .unordered(
+                ("atomic store", stored, &self.stored_at),
+                ("`with_mut`", threads),
+            )
             .fire();
         }
 
@@ -705,6 +1047,10 @@ impl State {
                     threads.active_id(),
                     self.unsync_mut_locations[threads],
                 )
+                .unordered(
+                    ("`with_mut`", mut_at, &self.unsync_mut_at),
+                    ("`with_mut`", threads),
+                )
                 .fire();
         }
 
@@ -718,6 +1064,13 @@ impl State {
         dst: &mut [u8],
         ordering: Ordering,
     ) -> usize {
+        // When the cell has only ever been accessed with `SeqCst`, a `SeqCst`
+        // load must read the most recent store in the total order, which is
+        // the newest store in modification order.
+        if is_seq_cst(ordering) && self.seq_cst_only {
+            return self.match_rmw_to_stores(dst);
+        }
+
         let mut n = 0;
         let cnt = self.cnt as usize;
 
@@ -867,17 +1220,21 @@ impl Default for Store {
 
 impl FirstSeen {
     fn new() -> FirstSeen {
-        FirstSeen([u16::max_value(); MAX_THREADS])
+        FirstSeen([u16::MAX; MAX_THREADS])
     }
 
     fn touch(&mut self, threads: &thread::Set) {
-        if self.0[threads.active_id().as_usize()] == u16::max_value() {
+        if self.0[threads.active_id().as_usize()] == u16::MAX {
             self.0[threads.active_id().as_usize()] = threads.active_atomic_version();
         }
     }
 
     fn is_seen_by_current(&self, threads: &thread::Set) -> bool {
-        for (thread_id, version) in threads.active().causality.versions(threads.execution_id()) {
+        self.is_seen_by(&threads.active().causality, threads.execution_id())
+    }
+
+    fn is_seen_by(&self, causality: &VersionVec, execution_id: execution::Id) -> bool {
+        for (thread_id, version) in causality.versions(execution_id) {
             match self.0[thread_id.as_usize()] {
                 u16::MAX => {}
                 v if v <= version => return true,
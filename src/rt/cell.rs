@@ -41,6 +41,18 @@ pub(crate) struct Writing {
     state: object::Ref<State>,
 }
 
+/// An immutable access whose causality check is deferred until `validate`.
+#[derive(Debug)]
+pub(crate) struct OptimisticRead {
+    state: object::Ref<State>,
+
+    /// Where the read happened
+    location: Location,
+
+    /// `true` if the read started in the middle of a `with_mut` closure.
+    torn: bool,
+}
+
 impl Cell {
     pub(crate) fn new(location: Location) -> Cell {
         rt::execution(|execution| {
@@ -82,6 +94,24 @@ impl Cell {
             Writing { state: self.state }
         })
     }
+
+    /// Reads the cell without checking for concurrent mutable accesses.
+    ///
+    /// The read is not tracked as an immutable access, so writers are not
+    /// checked against it either. Instead, the read is considered to last
+    /// until `OptimisticRead::validate`, which checks that every write up to
+    /// that point happens-before the validating thread.
+    pub(crate) fn optimistic_read(&self, location: Location) -> OptimisticRead {
+        rt::synchronize(|execution| {
+            let state = self.state.get_mut(&mut execution.objects);
+
+            OptimisticRead {
+                state: self.state,
+                location,
+                torn: state.is_writing,
+            }
+        })
+    }
 }
 
 impl State {
@@ -149,6 +179,42 @@ impl State {
     }
 }
 
+// === impl OptimisticRead ===
+
+impl OptimisticRead {
+    /// Checks that the read did not race with a mutable access, from the
+    /// point of view of the current thread.
+    pub(crate) fn validate(self, location: Location) {
+        rt::execution(|execution| {
+            let state = self.state.get(&execution.objects);
+            let current = &execution.threads.active().causality;
+
+            if self.torn || state.is_writing {
+                location::panic(
+                    "Causality violation: Optimistic read of `UnsafeCell` validated \
+                     while it was being written to.",
+                )
+                .location("created", state.created_location)
+                .location("read", self.location)
+                .location("validated", location)
+                .fire();
+            }
+
+            if let Some(writer) = current.ahead(&state.write_access) {
+                location::panic(
+                    "Causality violation: Optimistic read of `UnsafeCell` validated \
+                     while racing with a write.",
+                )
+                .location("created", state.created_location)
+                .location("read", self.location)
+                .location("validated", location)
+                .thread("write", writer, state.write_locations[writer])
+                .fire();
+            }
+        })
+    }
+}
+
 // === impl Reading ===
 
 impl Drop for Reading {
@@ -19,6 +19,10 @@ pub(super) struct State {
 
     /// Threads waiting on the condvar
     waiters: VecDeque<thread::Id>,
+
+    /// True if a wait on the condvar already woke up spuriously. This limits
+    /// spurious wakeups to one per condvar and execution.
+    did_spur: bool,
 }
 
 impl Condvar {
@@ -28,6 +32,7 @@ impl Condvar {
             let state = execution.objects.insert(State {
                 last_access: None,
                 waiters: VecDeque::new(),
+                did_spur: false,
             });
 
             trace!(?state, "Condvar::new");
@@ -40,20 +45,36 @@ impl Condvar {
     pub(crate) fn wait(&self, mutex: &Mutex, location: Location) {
         self.state.branch_opaque(location);
 
-        rt::execution(|execution| {
-            trace!(state = ?self.state, ?mutex, "Condvar::wait");
+        let spurious = rt::execution(|execution| {
+            let spurious =
+                if !execution.spurious_wakeups || self.state.get(&execution.objects).did_spur {
+                    false
+                } else {
+                    execution.path.branch_spurious()
+                };
+
+            trace!(state = ?self.state, ?mutex, ?spurious, "Condvar::wait");
 
             let state = self.state.get_mut(&mut execution.objects);
 
-            // Track the current thread as a waiter
-            state.waiters.push_back(execution.threads.active_id());
+            if spurious {
+                state.did_spur = true;
+            } else {
+                // Track the current thread as a waiter
+                state.waiters.push_back(execution.threads.active_id());
+            }
+
+            spurious
         });
 
         // Release the lock
         mutex.release_lock();
 
-        // Disable the current thread
-        rt::park(location);
+        // Disable the current thread, unless it wakes up spuriously. In that
+        // case, reacquiring the lock is the next branch point.
+        if !spurious {
+            rt::park(location);
+        }
 
         // Acquire the lock again
         mutex.acquire_lock(location);
@@ -1,7 +1,10 @@
 use crate::rt::alloc::Allocation;
-use crate::rt::{lazy_static, object, thread, Path};
+use crate::rt::orderings::Site;
+use crate::rt::{
+    lazy_static, object, thread, Heartbeat, Location, Orderings, Path, Sharing, MAX_THREADS,
+};
 
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::fmt;
 
 use tracing::info;
@@ -26,7 +29,7 @@ pub(crate) struct Execution {
     pub(crate) arc_objs: HashMap<*const (), std::sync::Arc<super::Arc>>,
 
     /// Maximum number of concurrent threads
-    pub(super) max_threads: usize,
+    pub(crate) max_threads: usize,
 
     pub(super) max_history: usize,
 
@@ -35,8 +38,69 @@ pub(crate) struct Execution {
 
     /// Log execution output to STDOUT
     pub(crate) log: bool,
+
+    /// Invariants checked at every branch point
+    pub(crate) invariants: Vec<Invariant>,
+
+    /// `true` while the invariants are being checked
+    pub(super) checking_invariants: bool,
+
+    /// Orderings of atomic operations. The model moves these over to the next
+    /// execution, as it needs them once the last one completes.
+    pub(crate) orderings: Orderings,
+
+    /// Threads accessing each object. Moved over to the next execution by the
+    /// model, like `orderings`.
+    pub(crate) sharing: Sharing,
+
+    /// Sites of the `fetch_sub` operations that decremented an atomic to zero
+    /// without `Release` ordering, when recorded. Moved over to the next
+    /// execution by the model, like `orderings`.
+    pub(crate) unreleased_decrements: Option<BTreeSet<Site>>,
+
+    /// Sites of the `fetch_update` operations whose function returned
+    /// different values for the same input, when checked. Moved over to the
+    /// next execution by the model, like `orderings`.
+    pub(crate) impure_updates: Option<BTreeSet<Site>>,
+
+    /// Signals the blocked thread watchdog on each thread switch
+    pub(crate) heartbeat: Option<Heartbeat>,
+
+    /// Maximum number of operations in the execution, see
+    /// `model::Builder::max_iteration_ops`.
+    pub(crate) max_ops: Option<usize>,
+
+    /// Report threads spinning without making progress, see
+    /// `model::Builder::check_lock_freedom`.
+    pub(crate) lock_freedom: bool,
+
+    /// Explore spurious wakeups of condition variables, see
+    /// `model::Builder::spurious_wakeups`.
+    pub(crate) spurious_wakeups: bool,
+
+    /// Number of operations performed so far, and these operations as runs of
+    /// consecutive operations by the same thread. Only tracked when `max_ops`
+    /// is set.
+    ops: (usize, Vec<(thread::Id, usize)>),
+
+    /// The operations scheduled so far, as `(thread, kind, object)` tuples,
+    /// when recorded for `model::Builder::export_schedules`.
+    pub(crate) schedule: Option<Vec<(usize, &'static str, usize)>>,
+
+    /// Selects the threads whose operations are recorded, see
+    /// `model::Builder::trace_thread`.
+    pub(crate) trace_thread: Option<TraceThread>,
+
+    /// The operations scheduled so far by the traced threads.
+    traced: Vec<(thread::Id, object::Operation)>,
 }
 
+/// A model invariant, see `model::Builder::invariant`.
+pub(crate) type Invariant = std::sync::Arc<dyn Fn() -> bool + Send + Sync>;
+
+/// Selects the traced threads, see `model::Builder::trace_thread`.
+pub(crate) type TraceThread = std::sync::Arc<dyn Fn(&crate::thread::Thread) -> bool + Send + Sync>;
+
 #[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
 pub(crate) struct Id(usize);
 
@@ -51,6 +115,13 @@ impl Execution {
         preemption_bound: Option<usize>,
         exploring: bool,
     ) -> Execution {
+        assert!(
+            max_threads <= MAX_THREADS,
+            "max_threads is {} but loom supports at most {} threads, see the `large_models` feature",
+            max_threads,
+            MAX_THREADS
+        );
+
         let id = Id::new();
         let threads = thread::Set::new(id, max_threads);
 
@@ -69,6 +140,20 @@ impl Execution {
             max_history: 7,
             location: false,
             log: false,
+            invariants: Vec::new(),
+            checking_invariants: false,
+            orderings: Orderings::default(),
+            sharing: Sharing::default(),
+            unreleased_decrements: None,
+            impure_updates: None,
+            heartbeat: None,
+            max_ops: None,
+            lock_freedom: false,
+            spurious_wakeups: false,
+            ops: (0, Vec::new()),
+            schedule: None,
+            trace_thread: None,
+            traced: Vec::new(),
         }
     }
 
@@ -97,6 +182,15 @@ impl Execution {
         let max_history = self.max_history;
         let location = self.location;
         let log = self.log;
+        let invariants = self.invariants;
+        let heartbeat = self.heartbeat;
+        let max_ops = self.max_ops;
+        let lock_freedom = self.lock_freedom;
+        let spurious_wakeups = self.spurious_wakeups;
+        let (_, mut runs) = self.ops;
+        let mut schedule = self.schedule;
+        let trace_thread = self.trace_thread;
+        let mut traced = self.traced;
         let mut path = self.path;
         let mut objects = self.objects;
         let mut lazy_statics = self.lazy_statics;
@@ -113,6 +207,12 @@ impl Execution {
         lazy_statics.reset();
         raw_allocations.clear();
         arc_objs.clear();
+        runs.clear();
+        traced.clear();
+
+        if let Some(schedule) = &mut schedule {
+            schedule.clear();
+        }
 
         threads.clear(id);
 
@@ -128,6 +228,20 @@ impl Execution {
             max_history,
             location,
             log,
+            invariants,
+            checking_invariants: false,
+            orderings: Orderings::default(),
+            sharing: Sharing::default(),
+            unreleased_decrements: None,
+            impure_updates: None,
+            heartbeat,
+            max_ops,
+            lock_freedom,
+            spurious_wakeups,
+            ops: (0, runs),
+            schedule,
+            trace_thread,
+            traced,
         })
     }
 
@@ -139,6 +253,10 @@ impl Execution {
 
         let curr_thread = self.threads.active_id();
 
+        if let Some(max_ops) = self.max_ops {
+            self.count_op(max_ops);
+        }
+
         for (th_id, th) in self.threads.iter() {
             let operation = match th.operation {
                 Some(operation) => operation,
@@ -160,6 +278,17 @@ impl Execution {
             }
         }
 
+        // A thread in a critical section is not preempted while it can make
+        // progress. Its operations are attributed to the branch point from
+        // which it ran the critical section, so that DPOR backtracks before the
+        // whole section.
+        let active = self.threads.active();
+
+        if let (true, Some(path_id)) = (active.is_runnable(), active.critical_branch) {
+            self.track_operation(path_id);
+            return false;
+        }
+
         // It's important to avoid pre-emption as much as possible
         let mut initial = Some(self.threads.active_id());
 
@@ -224,7 +353,41 @@ impl Execution {
             return true;
         }
 
-        // TODO: refactor
+        if self.threads.active().critical {
+            self.threads.active_mut().critical_branch = Some(path_id);
+        }
+
+        self.track_operation(path_id);
+
+        // Reactivate yielded threads, but only if the current active thread is
+        // not yielded.
+        for (id, th) in self.threads.iter_mut() {
+            if th.is_yield() && Some(id) != next {
+                th.set_runnable();
+            }
+        }
+
+        if switched {
+            info!("~~~~~~~~ THREAD {} ~~~~~~~~", self.threads.active_id());
+        }
+
+        if let Some(heartbeat) = &self.heartbeat {
+            let location = self
+                .threads
+                .active()
+                .operation
+                .map(|operation| operation.location())
+                .unwrap_or_else(Location::disabled);
+
+            heartbeat.beat(self.threads.active_id().public_id(), location);
+        }
+
+        curr_thread != self.threads.active_id()
+    }
+
+    /// Tracks the operation the active thread is about to perform for DPOR,
+    /// attributing it to the branch point at `path_id`.
+    fn track_operation(&mut self, path_id: usize) {
         if let Some(operation) = self.threads.active().operation {
             let threads = &mut self.threads;
             let th_id = threads.active_id();
@@ -237,21 +400,81 @@ impl Execution {
 
             self.objects
                 .set_last_access(operation, path_id, &threads.active().dpor_vv);
+
+            if let Some(schedule) = &mut self.schedule {
+                schedule.push((th_id.public_id(), operation.kind(), operation.object_id()));
+            }
+
+            if threads.active().traced {
+                self.traced.push((th_id, operation));
+            }
         }
+    }
 
-        // Reactivate yielded threads, but only if the current active thread is
-        // not yielded.
-        for (id, th) in self.threads.iter_mut() {
-            if th.is_yield() && Some(id) != next {
-                th.set_runnable();
+    /// Counts an operation by the active thread, failing the execution once
+    /// there are more than `max_ops`.
+    fn count_op(&mut self, max_ops: usize) {
+        let thread = self.threads.active_id();
+        let (count, runs) = &mut self.ops;
+
+        *count += 1;
+
+        match runs.last_mut() {
+            Some((id, n)) if *id == thread => *n += 1,
+            _ => runs.push((thread, 1)),
+        }
+
+        // Objects dropped while unwinding keep performing operations
+        if *count <= max_ops || std::thread::panicking() {
+            return;
+        }
+
+        // Spinning threads switch often, only show the end of the schedule.
+        const SHOWN_RUNS: usize = 32;
+
+        let mut schedule = if runs.len() > SHOWN_RUNS { "..., " } else { "" }.to_string();
+
+        for (i, (id, n)) in runs.iter().rev().take(SHOWN_RUNS).rev().enumerate() {
+            if i > 0 {
+                schedule.push_str(", ");
             }
+
+            schedule.push_str(&format!("thread {} x{}", id.public_id(), n));
         }
 
-        if switched {
-            info!("~~~~~~~~ THREAD {} ~~~~~~~~", self.threads.active_id());
+        panic!(
+            "iteration exceeded `max_iteration_ops` with {} loom operations; schedule: {}",
+            count, schedule
+        );
+    }
+
+    /// Describes the operations of the traced threads, one per line, for the
+    /// report of a failed execution.
+    pub(crate) fn traced_operations(&self) -> String {
+        // Spinning threads perform many operations, only show the last ones.
+        const SHOWN_OPERATIONS: usize = 64;
+
+        let skipped = self.traced.len().saturating_sub(SHOWN_OPERATIONS);
+        let mut out = String::from("operations of the traced threads:");
+
+        if skipped > 0 {
+            out.push_str(&format!("\n  ... {} earlier operations", skipped));
         }
 
-        curr_thread != self.threads.active_id()
+        for (id, operation) in &self.traced[skipped..] {
+            out.push_str(&format!(
+                "\n  thread {}: {} object {}",
+                id.public_id(),
+                operation.kind(),
+                operation.object_id()
+            ));
+
+            if operation.location().is_captured() {
+                out.push_str(&format!(" at {}", operation.location()));
+            }
+        }
+
+        out
     }
 
     /// Panics if any leaks were detected
@@ -14,8 +14,9 @@ macro_rules! location {
     }};
 }
 
-use crate::rt::{thread, MAX_THREADS};
+use crate::rt::{thread, VersionVec, MAX_THREADS};
 
+use std::backtrace::{Backtrace, BacktraceStatus};
 use std::ops;
 
 #[derive(Debug)]
@@ -26,6 +27,7 @@ pub(super) struct LocationSet {
 pub(super) struct PanicBuilder {
     msg: String,
     locations: Vec<(String, Option<usize>, Location)>,
+    notes: Vec<String>,
 }
 
 // ===== impl LocationSet ======
@@ -66,6 +68,7 @@ pub(super) fn panic(msg: impl ToString) -> PanicBuilder {
     PanicBuilder {
         msg: msg.to_string(),
         locations: Vec::new(),
+        notes: Vec::new(),
     }
 }
 
@@ -86,6 +89,73 @@ impl PanicBuilder {
         self
     }
 
+    /// Explains why an access of `thread`, whose causality is `version`, is
+    /// concurrent with the access of the active thread.
+    ///
+    /// Versions count the synchronization points of each thread: the access
+    /// happens-before the active thread only if the active thread synchronized
+    /// with a point of `thread` at or after the access. When locations are
+    /// captured, the atomic operations through which the active thread
+    /// synchronized are named.
+    pub(super) fn unordered(
+        &mut self,
+        (key, thread, version): (&str, usize, &VersionVec),
+        (active_key, threads): (&str, &thread::Set),
+    ) -> &mut Self {
+        let active = threads.active_id().as_usize();
+        let current = &threads.active().causality;
+
+        let mut note = format!(
+            "no happens-before path exists between the {} of thread #{} and the {} of thread #{}:",
+            key, thread, active_key, active
+        );
+
+        note.push_str(&format!(
+            "\n    the {} follows point {} of thread #{}, ",
+            key, version[thread], thread
+        ));
+
+        if current[thread] == 0 {
+            note.push_str(&format!(
+                "but thread #{} never synchronized with thread #{}",
+                active, thread
+            ));
+        } else {
+            note.push_str(&format!(
+                "but thread #{} only synchronized with thread #{} up to point {}{}",
+                active,
+                thread,
+                current[thread],
+                synchronized_at(threads, thread, current[thread])
+            ));
+        }
+
+        // The synchronization points through which the active thread observed
+        // the remaining threads.
+        let observed: Vec<_> = (0..MAX_THREADS)
+            .filter(|&i| i != active && i != thread && current[i] > 0)
+            .map(|i| {
+                format!(
+                    "thread #{} up to point {}{}",
+                    i,
+                    current[i],
+                    synchronized_at(threads, i, current[i])
+                )
+            })
+            .collect();
+
+        if !observed.is_empty() {
+            note.push_str(&format!(
+                "\n    thread #{} also synchronized with {}",
+                active,
+                observed.join(", ")
+            ));
+        }
+
+        self.notes.push(note);
+        self
+    }
+
     pub(super) fn fire(&self) {
         let mut msg = self.msg.clone();
 
@@ -112,10 +182,48 @@ impl PanicBuilder {
             }
         }
 
+        for note in &self.notes {
+            msg.push_str(&format!("\n\n{}", note));
+        }
+
+        // Include the call stack leading to the offending operation when
+        // backtraces are enabled with `RUST_LIB_BACKTRACE`. With
+        // `RUST_BACKTRACE`, the panic hook already prints it.
+        // The section is omitted when no frame could be resolved.
+        if !panic_hook_prints_backtrace() {
+            let backtrace = Backtrace::capture();
+
+            if backtrace.status() == BacktraceStatus::Captured {
+                let frames = backtrace.to_string();
+
+                if !frames.trim().is_empty() {
+                    msg.push_str(&format!("\n\nbacktrace:\n{}", frames));
+                }
+            }
+        }
+
         panic!("{}\n", msg);
     }
 }
 
+/// Describes the `Release` store at point `version` of `thread` and the
+/// `Acquire` load through which the active thread observed it, if known.
+fn synchronized_at(threads: &thread::Set, thread: usize, version: u16) -> String {
+    match threads.sync_locations(thread, version) {
+        Some((release, acquire)) => format!(
+            ",\n      released by the store at {}\n      and acquired by the load at {}",
+            release, acquire
+        ),
+        None => String::new(),
+    }
+}
+
+/// Returns `true` if the default panic hook prints a backtrace, as enabled by
+/// the `RUST_BACKTRACE` environment variable.
+fn panic_hook_prints_backtrace() -> bool {
+    std::env::var_os("RUST_BACKTRACE").is_some_and(|v| v != "0")
+}
+
 // ===== impl Location cfg =====
 
 mod cfg {
@@ -9,7 +9,7 @@ mod alloc;
 pub(crate) use self::alloc::{alloc, dealloc, Allocation};
 
 mod arc;
-pub(crate) use self::arc::Arc;
+pub(crate) use self::arc::{Arc, MakeMut};
 
 mod atomic;
 pub(crate) use self::atomic::{fence, Atomic};
@@ -21,7 +21,7 @@ mod condvar;
 pub(crate) use self::condvar::Condvar;
 
 mod execution;
-pub(crate) use self::execution::Execution;
+pub(crate) use self::execution::{Execution, Invariant, TraceThread};
 
 mod notify;
 pub(crate) use self::notify::Notify;
@@ -29,6 +29,12 @@ pub(crate) use self::notify::Notify;
 mod num;
 pub(crate) use self::num::Numeric;
 
+pub(crate) mod once;
+pub(crate) use self::once::Once;
+
+pub(crate) mod orderings;
+pub(crate) use self::orderings::Orderings;
+
 #[macro_use]
 pub(crate) mod object;
 
@@ -39,7 +45,7 @@ mod mutex;
 pub(crate) use self::mutex::Mutex;
 
 mod path;
-pub(crate) use self::path::Path;
+pub(crate) use self::path::{Choice, Path};
 
 mod rwlock;
 pub(crate) use self::rwlock::RwLock;
@@ -47,6 +53,9 @@ pub(crate) use self::rwlock::RwLock;
 mod scheduler;
 pub(crate) use self::scheduler::Scheduler;
 
+mod sharing;
+pub(crate) use self::sharing::Sharing;
+
 mod synchronize;
 pub(crate) use self::synchronize::Synchronize;
 
@@ -56,11 +65,25 @@ pub(crate) mod thread;
 mod vv;
 pub(crate) use self::vv::VersionVec;
 
+mod watchdog;
+pub(crate) use self::watchdog::{Heartbeat, Watchdog};
+
 use tracing::trace;
 
 /// Maximum number of threads that can be included in a model.
+///
+/// This is 5, or 16 with the `large_models` feature. Every thread adds to the
+/// memory used by loom to track the accesses of all threads.
+#[cfg(not(feature = "large_models"))]
 pub const MAX_THREADS: usize = 5;
 
+/// Maximum number of threads that can be included in a model.
+///
+/// This is 5, or 16 with the `large_models` feature. Every thread adds to the
+/// memory used by loom to track the accesses of all threads.
+#[cfg(feature = "large_models")]
+pub const MAX_THREADS: usize = 16;
+
 /// Maximum number of atomic store history to track per-cell.
 pub(crate) const MAX_ATOMIC_HISTORY: usize = 7;
 
@@ -113,12 +136,54 @@ pub(crate) fn park(location: Location) {
     }
 }
 
+/// Panics if any of the model invariants does not hold.
+///
+/// The invariants are called outside of the execution context. As only one
+/// thread runs between two branch points, a violation is attributed to the
+/// active thread and the operation it is about to perform.
+fn check_invariants(location: Location) {
+    if std::thread::panicking() {
+        return;
+    }
+
+    let invariants = execution(|execution| {
+        assert!(
+            !execution.checking_invariants,
+            "loom operations are not permitted in model invariants"
+        );
+
+        if execution.invariants.is_empty() {
+            return None;
+        }
+
+        execution.checking_invariants = true;
+        Some((execution.threads.active_id(), execution.invariants.clone()))
+    });
+
+    let (thread, invariants) = match invariants {
+        Some(invariants) => invariants,
+        None => return,
+    };
+
+    let holds = invariants.iter().all(|invariant| invariant());
+
+    execution(|execution| execution.checking_invariants = false);
+
+    if !holds {
+        location::panic("model invariant violated")
+            .thread("before", thread, location)
+            .fire();
+    }
+}
+
 /// Add an execution branch point.
 fn branch<F, R>(f: F) -> R
 where
     F: FnOnce(&mut Execution) -> R,
 {
     let (ret, switch) = execution(|execution| {
+        execution.threads.active_mut().spinning = false;
+
         let ret = f(execution);
         let switch = execution.schedule();
 
@@ -239,11 +304,49 @@ where
 /// }
 /// ```
 pub fn yield_now() {
+    yield_now_checked();
+}
+
+/// Number of yields of each runnable thread, without any of them making
+/// progress, after which the threads are considered livelocked.
+const LIVELOCK_YIELDS: usize = 3;
+
+/// Like [`yield_now`], returning `true` if another thread ran before the
+/// current thread was rescheduled.
+///
+/// `false` means that no other thread was runnable, so the current thread
+/// kept running. This helps understand why a spin loop does not make progress:
+/// the thread it waits for may be blocked.
+///
+/// # Examples
+///
+/// ```
+/// use loom::thread;
+///
+/// loom::model(|| {
+///     // The main thread is the only thread
+///     assert!(!thread::yield_now_checked());
+/// });
+/// ```
+pub fn yield_now_checked() -> bool {
     let switch = execution(|execution| {
         let thread = execution.threads.active_id();
 
         execution.threads.active_mut().set_yield();
         execution.threads.active_mut().operation = None;
+
+        if execution.lock_freedom {
+            execution.threads.active_mut().yields_since_progress += 1;
+
+            if execution.threads.is_livelocked(LIVELOCK_YIELDS) && !std::thread::panicking() {
+                panic!(
+                    "Lock-freedom violation: all runnable threads yielded {} times without \
+                     making progress (livelock)",
+                    LIVELOCK_YIELDS
+                );
+            }
+        }
+
         let switch = execution.schedule();
 
         trace!(?thread, ?switch, "yield_now");
@@ -254,6 +357,23 @@ pub fn yield_now() {
     if switch {
         Scheduler::switch();
     }
+
+    switch
+}
+
+/// Signals a busy-wait spin loop, yielding the thread.
+///
+/// Nothing observable happens between two calls that are not separated by an
+/// access to shared state, so only the first of them yields. This collapses
+/// the spins of a backoff loop into a single branch.
+pub(crate) fn spin_loop() {
+    let spinning = execution(|execution| {
+        std::mem::replace(&mut execution.threads.active_mut().spinning, true)
+    });
+
+    if !spinning {
+        yield_now();
+    }
 }
 
 pub(crate) fn execution<F, R>(f: F) -> R
@@ -263,23 +383,42 @@ where
     Scheduler::with_execution(f)
 }
 
-pub fn thread_done() {
+/// Like `execution`, but returns `None` instead of panicking when called from
+/// outside a model or while the execution is already being accessed.
+pub(crate) fn try_execution<F, R>(f: F) -> Option<R>
+where
+    F: FnOnce(&mut Execution) -> R,
+{
+    Scheduler::try_with_execution(f)
+}
+
+/// Runs the destructors of the current thread's thread-locals.
+///
+/// The destructors run outside of the execution context, so they may perform
+/// loom operations.
+pub(crate) fn drop_locals() {
     let locals = execution(|execution| {
         let thread = execution.threads.active_id();
 
-        trace!(?thread, "thread_done: drop locals");
+        trace!(?thread, "drop locals");
 
         execution.threads.active_mut().drop_locals()
     });
 
     // Drop outside of the execution context
     drop(locals);
+}
+
+pub fn thread_done() {
+    drop_locals();
+    check_invariants(Location::disabled());
 
     execution(|execution| {
         let thread = execution.threads.active_id();
 
         execution.threads.active_mut().operation = None;
         execution.threads.active_mut().set_terminated();
+        execution.threads.progress();
         let switch = execution.schedule();
         trace!(?thread, ?switch, "thread_done: terminate");
     });
@@ -302,6 +441,74 @@ pub fn stop_exploring() {
     })
 }
 
+/// Runs `f` as a single unit, without preempting the current thread in the
+/// middle of it.
+///
+/// This is useful for a sequence of atomic operations that are known to be
+/// performed as a unit in the code under test, e.g. under a lock that is
+/// modeled separately: loom does not explore the interleavings of other
+/// threads between these operations, which reduces the number of iterations.
+/// Other threads may still run before the first operation of `f`, or after
+/// its last one.
+///
+/// Unlike [`stop_exploring`], this does not change which schedules are
+/// explored outside of `f`. If the current thread blocks in `f`, e.g. on a
+/// `Mutex`, other threads run until it is unblocked.
+///
+/// # Examples
+///
+/// ```
+/// use loom::sync::atomic::AtomicUsize;
+/// use loom::sync::Arc;
+/// use loom::thread;
+///
+/// use std::sync::atomic::Ordering::SeqCst;
+///
+/// loom::model(|| {
+///     let num = Arc::new(AtomicUsize::new(0));
+///
+///     let th = {
+///         let num = num.clone();
+///         thread::spawn(move || {
+///             loom::atomic_batch(|| {
+///                 num.fetch_add(1, SeqCst);
+///                 num.fetch_add(1, SeqCst);
+///             });
+///         })
+///     };
+///
+///     // The intermediate value is never observed
+///     assert_ne!(1, num.load(SeqCst));
+///     th.join().unwrap();
+/// });
+/// ```
+pub fn atomic_batch<F, R>(f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    struct Reset(bool);
+
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            execution(|execution| {
+                let thread = execution.threads.active_mut();
+                thread.critical = self.0;
+
+                if !self.0 {
+                    thread.critical_branch = None;
+                }
+            });
+        }
+    }
+
+    let _reset = execution(|execution| {
+        let thread = execution.threads.active_mut();
+        Reset(std::mem::replace(&mut thread.critical, true))
+    });
+
+    f()
+}
+
 /// Tells loom to stop exploring possible concurrent execution starting at this
 /// point.
 ///
@@ -309,3 +516,18 @@ pub fn stop_exploring() {
 pub fn skip_branch() {
     execution(|execution| execution.path.skip_branch())
 }
+
+/// Returns an index in `0..n`, exploring each of them.
+///
+/// The choice is made with up to `n - 1` binary branches: the first index is
+/// chosen by default, and each branch skips one more index.
+#[cfg(feature = "futures")]
+pub(crate) fn branch_choice(n: usize) -> usize {
+    assert!(n > 0, "[loom internal bug] nothing to choose from");
+
+    execution(|execution| {
+        (0..n - 1)
+            .find(|_| !execution.path.branch_spurious())
+            .unwrap_or(n - 1)
+    })
+}
@@ -1,6 +1,7 @@
-use crate::rt::{object, Access, Location, Synchronize, VersionVec};
+use crate::rt::{object, thread, Access, Location, Synchronize, VersionVec};
 use std::collections::VecDeque;
 use std::sync::atomic::Ordering::{Acquire, Release};
+use std::sync::mpsc::{RecvTimeoutError, TryRecvError, TrySendError};
 
 #[derive(Debug)]
 pub(crate) struct Channel {
@@ -12,10 +13,28 @@ pub(super) struct State {
     /// Count of messages in the channel.
     msg_cnt: usize,
 
+    /// Maximum number of messages buffered by the channel, `None` if it is
+    /// unbounded. A bound of zero makes it a rendezvous channel.
+    bound: Option<usize>,
+
+    /// `true` once the receiver has been dropped.
+    receiver_dropped: bool,
+
+    /// Count of senders, the channel is disconnected once they are all
+    /// dropped.
+    sender_cnt: usize,
+
+    /// `true` if a receive already timed out. This limits timeouts to one per
+    /// channel and execution.
+    did_timeout: bool,
+
     /// Last access that was a send operation.
     last_send_access: Option<Access>,
     /// Last access that was a receive operation.
     last_recv_access: Option<Access>,
+    /// Last access of any kind, sends and receives are dependent on bounded
+    /// channels as they may block each other.
+    last_access: Option<Access>,
 
     /// A synchronization point for synchronizing the sending threads and the
     /// channel.
@@ -36,6 +55,9 @@ pub(super) struct State {
     /// A synchronization point per message synchronizing the receiving thread
     /// with the channel state at the point when the received message was sent.
     receiver_synchronize: VecDeque<Synchronize>,
+    /// On rendezvous channels, synchronizes the sender handing off a message
+    /// with the receiver waiting for it.
+    rendezvous_synchronize: Synchronize,
 
     created: Location,
 }
@@ -47,98 +69,369 @@ pub(super) enum Action {
     MsgSend,
     /// Receive a message
     MsgRecv,
+    /// Receive a message without blocking
+    MsgTryRecv,
+    /// Drop the receiver
+    RecvDrop,
+    /// Drop a sender
+    SendDrop,
 }
 
 impl Channel {
     pub(crate) fn new(location: Location) -> Self {
+        Self::with_bound(None, location)
+    }
+
+    /// Creates a channel buffering at most `bound` messages. Sends block while
+    /// the buffer is full, and a bound of zero makes each send wait for the
+    /// receiver.
+    pub(crate) fn new_bounded(bound: usize, location: Location) -> Self {
+        Self::with_bound(Some(bound), location)
+    }
+
+    fn with_bound(bound: Option<usize>, location: Location) -> Self {
         super::execution(|execution| {
             let state = execution.objects.insert(State {
                 msg_cnt: 0,
+                bound,
+                receiver_dropped: false,
+                sender_cnt: 1,
+                did_timeout: false,
                 last_send_access: None,
                 last_recv_access: None,
+                last_access: None,
                 sender_synchronize: Synchronize::new(),
                 receiver_synchronize: VecDeque::new(),
+                rendezvous_synchronize: Synchronize::new(),
                 created: location,
             });
 
-            tracing::trace!(?state, %location, "mpsc::channel");
+            tracing::trace!(?state, ?bound, %location, "mpsc::channel");
             Self { state }
         })
     }
 
-    pub(crate) fn send(&self, location: Location) {
+    /// Returns `false` if the message could not be sent because the receiver
+    /// has been dropped.
+    ///
+    /// On bounded channels, blocks until there is room for the message.
+    pub(crate) fn send(&self, location: Location) -> bool {
+        loop {
+            let blocked = super::execution(|execution| !self.can_send(execution));
+            self.state
+                .branch_disable(Action::MsgSend, blocked, location);
+
+            // Another sender may have filled the buffer since this thread was
+            // unblocked, in which case it blocks again.
+            let sent = super::execution(|execution| {
+                if !self.can_send(execution) {
+                    return None;
+                }
+
+                Some(self.post_send(execution))
+            });
+
+            if let Some(sent) = sent {
+                return sent;
+            }
+        }
+    }
+
+    /// Sends a message without blocking, failing if the channel is full or
+    /// the receiver has been dropped.
+    pub(crate) fn try_send(&self, location: Location) -> Result<(), TrySendError<()>> {
         self.state.branch_action(Action::MsgSend, location);
         super::execution(|execution| {
-            let state = self.state.get_mut(&mut execution.objects);
-            state.msg_cnt = state.msg_cnt.checked_add(1).expect("overflow");
+            if !self.can_send(execution) {
+                return Err(TrySendError::Full(()));
+            }
 
+            if self.post_send(execution) {
+                Ok(())
+            } else {
+                Err(TrySendError::Disconnected(()))
+            }
+        })
+    }
+
+    /// Returns `true` if a send would not block.
+    fn can_send(&self, execution: &mut super::Execution) -> bool {
+        let state = self.state.get(&execution.objects);
+
+        match state.bound {
+            _ if state.receiver_dropped => true,
+            None => true,
+            // The message is handed off to a receiver waiting for it.
+            Some(0) => state.msg_cnt == 0 && self.is_waiting(&execution.threads, Action::MsgRecv),
+            Some(bound) => state.msg_cnt < bound,
+        }
+    }
+
+    fn post_send(&self, execution: &mut super::Execution) -> bool {
+        let state = self.state.get_mut(&mut execution.objects);
+
+        if state.receiver_dropped {
+            return false;
+        }
+
+        state.msg_cnt = state.msg_cnt.checked_add(1).expect("overflow");
+
+        if state.bound == Some(0) {
             state
-                .sender_synchronize
-                .sync_store(&mut execution.threads, Release);
-            state
-                .receiver_synchronize
-                .push_back(state.sender_synchronize);
-
-            if state.msg_cnt == 1 {
-                // Unblock all threads that are blocked waiting on this channel
-                let thread_id = execution.threads.active_id();
-                for (id, thread) in execution.threads.iter_mut() {
-                    if id == thread_id {
-                        continue;
-                    }
+                .rendezvous_synchronize
+                .sync_load(&mut execution.threads, Acquire);
+        }
+
+        state
+            .sender_synchronize
+            .sync_store(&mut execution.threads, Release);
+        state
+            .receiver_synchronize
+            .push_back(state.sender_synchronize);
+
+        if state.msg_cnt == 1 {
+            // Unblock all threads that are blocked waiting on this channel
+            self.unblock(&mut execution.threads, Action::MsgRecv);
+        }
+
+        true
+    }
+
+    /// Returns `false` if no message could be received because the channel is
+    /// empty and all the senders have been dropped.
+    pub(crate) fn recv(&self, location: Location) -> bool {
+        super::execution(|execution| {
+            let state = self.state.get_mut(&mut execution.objects);
+
+            if state.bound == Some(0) {
+                // Senders blocked on a rendezvous channel can now hand off
+                // their message.
+                state
+                    .rendezvous_synchronize
+                    .sync_store(&mut execution.threads, Release);
+                self.unblock(&mut execution.threads, Action::MsgSend);
+            }
+        });
+
+        let blocked = super::execution(|execution| {
+            let state = self.state.get(&execution.objects);
+            state.msg_cnt == 0 && state.sender_cnt != 0
+        });
+
+        self.state
+            .branch_disable(Action::MsgRecv, blocked, location);
+        super::execution(|execution| {
+            if self.is_disconnected(execution) {
+                return false;
+            }
+
+            self.post_recv(execution);
+            true
+        })
+    }
+
+    /// Receives a message without blocking, failing if the channel is empty.
+    pub(crate) fn try_recv(&self, location: Location) -> Result<(), TryRecvError> {
+        self.state.branch_action(Action::MsgTryRecv, location);
+        super::execution(|execution| {
+            if self.is_disconnected(execution) {
+                return Err(TryRecvError::Disconnected);
+            }
+
+            if self.state.get(&execution.objects).msg_cnt == 0 {
+                return Err(TryRecvError::Empty);
+            }
+
+            self.post_recv(execution);
+            Ok(())
+        })
+    }
+
+    /// Receives a message, blocking until one is sent unless the receive
+    /// times out.
+    ///
+    /// Loom has no clock: when the receive would block, both the timeout and
+    /// the blocking receive are explored. Timeouts are explored once per
+    /// channel and execution.
+    pub(crate) fn recv_timeout(&self, location: Location) -> Result<(), RecvTimeoutError> {
+        let timeout = super::execution(|execution| {
+            let state = self.state.get(&execution.objects);
 
-                    let obj = thread
-                        .operation
-                        .as_ref()
-                        .map(|operation| operation.object());
+            if state.did_timeout || state.msg_cnt != 0 || state.sender_cnt == 0 {
+                return false;
+            }
+
+            let timeout = execution.path.branch_spurious();
+
+            if timeout {
+                self.state.get_mut(&mut execution.objects).did_timeout = true;
+            }
+
+            timeout
+        });
 
-                    if obj == Some(self.state.erase()) {
-                        thread.set_runnable();
+        if timeout {
+            self.state.branch_action(Action::MsgTryRecv, location);
+            return Err(RecvTimeoutError::Timeout);
+        }
+
+        if self.recv(location) {
+            Ok(())
+        } else {
+            Err(RecvTimeoutError::Disconnected)
+        }
+    }
+
+    /// Returns `true` if the channel is empty and all the senders have been
+    /// dropped, synchronizing with them in that case.
+    fn is_disconnected(&self, execution: &mut super::Execution) -> bool {
+        let state = self.state.get_mut(&mut execution.objects);
+
+        if state.msg_cnt != 0 || state.sender_cnt != 0 {
+            return false;
+        }
+
+        state
+            .sender_synchronize
+            .sync_load(&mut execution.threads, Acquire);
+        true
+    }
+
+    fn post_recv(&self, execution: &mut super::Execution) {
+        let state = self.state.get_mut(&mut execution.objects);
+        let thread_id = execution.threads.active_id();
+        state.msg_cnt = state
+            .msg_cnt
+            .checked_sub(1)
+            .expect("expected to be able to read the message");
+        let mut synchronize = state.receiver_synchronize.pop_front().unwrap();
+        synchronize.sync_load(&mut execution.threads, Acquire);
+
+        if matches!(state.bound, Some(bound) if state.msg_cnt + 1 == bound) {
+            // The receive freed a slot for a blocked sender
+            self.unblock(&mut execution.threads, Action::MsgSend);
+        }
+
+        if state.msg_cnt == 0 && state.sender_cnt != 0 {
+            // Block all **other** threads attempting to read from the channel
+            for (id, thread) in execution.threads.iter_mut() {
+                if id == thread_id {
+                    continue;
+                }
+
+                if let Some(operation) = thread.operation.as_ref() {
+                    if operation.object() == self.state.erase()
+                        && operation.action() == object::Action::Channel(Action::MsgRecv)
+                    {
+                        let location = operation.location();
+                        thread.set_blocked(location);
                     }
                 }
             }
+        }
+    }
+
+    /// Makes runnable the **other** threads about to perform `action` on the
+    /// channel.
+    fn unblock(&self, threads: &mut thread::Set, action: Action) {
+        let thread_id = threads.active_id();
+
+        for (id, thread) in threads.iter_mut() {
+            if id == thread_id {
+                continue;
+            }
+
+            if let Some(operation) = thread.operation.as_ref() {
+                if operation.object() == self.state.erase()
+                    && operation.action() == object::Action::Channel(action)
+                {
+                    thread.set_runnable();
+                }
+            }
+        }
+    }
+
+    /// Returns `true` if another thread is about to perform `action` on the
+    /// channel.
+    fn is_waiting(&self, threads: &thread::Set, action: Action) -> bool {
+        let thread_id = threads.active_id();
+
+        threads.iter().any(|(id, thread)| {
+            id != thread_id
+                && !thread.is_terminated()
+                && thread.operation.as_ref().is_some_and(|operation| {
+                    operation.object() == self.state.erase()
+                        && operation.action() == object::Action::Channel(action)
+                })
         })
     }
 
-    pub(crate) fn recv(&self, location: Location) {
-        self.state
-            .branch_disable(Action::MsgRecv, self.is_empty(), location);
+    /// Drops the receiver, along with any message still in the channel. Sends
+    /// racing with the drop fail depending on the schedule.
+    pub(crate) fn drop_receiver(&self, location: Location) {
+        self.branch_drop(Action::RecvDrop, location);
         super::execution(|execution| {
             let state = self.state.get_mut(&mut execution.objects);
-            let thread_id = execution.threads.active_id();
-            state.msg_cnt = state
-                .msg_cnt
-                .checked_sub(1)
-                .expect("expected to be able to read the message");
-            let mut synchronize = state.receiver_synchronize.pop_front().unwrap();
-            dbg!(synchronize.sync_load(&mut execution.threads, Acquire));
-            if state.msg_cnt == 0 {
-                // Block all **other** threads attempting to read from the channel
-                for (id, thread) in execution.threads.iter_mut() {
-                    if id == thread_id {
-                        continue;
-                    }
+            state.receiver_dropped = true;
+            state.msg_cnt = 0;
 
-                    if let Some(operation) = thread.operation.as_ref() {
-                        if operation.object() == self.state.erase()
-                            && operation.action() == object::Action::Channel(Action::MsgRecv)
-                        {
-                            let location = operation.location();
-                            thread.set_blocked(location);
-                        }
-                    }
-                }
+            // Execution has deadlocked, cleanup does not matter.
+            if !execution.threads.is_active() {
+                return;
             }
+
+            // Blocked senders fail
+            self.unblock(&mut execution.threads, Action::MsgSend);
+
+            // The remaining messages are dropped by the receiving thread.
+            for mut synchronize in state.receiver_synchronize.drain(..) {
+                synchronize.sync_load(&mut execution.threads, Acquire);
+            }
+
+            tracing::trace!(state = ?self.state, %location, "mpsc::drop_receiver");
         })
     }
 
-    /// Returns `true` if the channel is currently empty
-    pub(crate) fn is_empty(&self) -> bool {
-        super::execution(|execution| self.get_state(&mut execution.objects).msg_cnt == 0)
+    /// Registers a new sender, cloned from an existing one.
+    pub(crate) fn clone_sender(&self) {
+        super::execution(|execution| {
+            let state = self.state.get_mut(&mut execution.objects);
+            state.sender_cnt = state.sender_cnt.checked_add(1).expect("overflow");
+        })
     }
 
-    fn get_state<'a>(&self, objects: &'a mut object::Store) -> &'a mut State {
-        self.state.get_mut(objects)
+    /// Drops a sender. Once all of them are dropped, receives on an empty
+    /// channel fail instead of blocking.
+    pub(crate) fn drop_sender(&self, location: Location) {
+        self.branch_drop(Action::SendDrop, location);
+        super::execution(|execution| {
+            let state = self.state.get_mut(&mut execution.objects);
+            state.sender_cnt -= 1;
+
+            // Execution has deadlocked, cleanup does not matter.
+            if !execution.threads.is_active() {
+                return;
+            }
+
+            state
+                .sender_synchronize
+                .sync_store(&mut execution.threads, Release);
+
+            if state.sender_cnt == 0 {
+                // Blocked receives fail
+                self.unblock(&mut execution.threads, Action::MsgRecv);
+            }
+
+            tracing::trace!(state = ?self.state, %location, "mpsc::drop_sender");
+        })
+    }
+
+    /// Branches before dropping an end of the channel. Dropping while
+    /// unwinding, which may abort the execution, is not a branch point.
+    fn branch_drop(&self, action: Action, location: Location) {
+        if !std::thread::panicking() {
+            self.state.branch_action(action, location);
+        }
     }
 }
 
@@ -163,16 +456,36 @@ impl State {
     }
 
     pub(super) fn last_dependent_access(&self, action: Action) -> Option<&Access> {
+        if self.bound.is_some() {
+            return self.last_access.as_ref();
+        }
+
         match action {
-            Action::MsgSend => self.last_send_access.as_ref(),
-            Action::MsgRecv => self.last_recv_access.as_ref(),
+            // Sends and dropping the receiver are dependent: whether the
+            // send succeeds depends on their order.
+            Action::MsgSend | Action::RecvDrop => self.last_send_access.as_ref(),
+            // Whether a receive fails depends on the senders being dropped.
+            Action::MsgRecv | Action::SendDrop => self.last_recv_access.as_ref(),
+            // Whether a non-blocking receive finds a message depends on the
+            // sends.
+            Action::MsgTryRecv => self.last_access.as_ref(),
         }
     }
 
     pub(super) fn set_last_access(&mut self, action: Action, path_id: usize, version: &VersionVec) {
+        Access::set_or_create(&mut self.last_access, path_id, version);
+
         match action {
-            Action::MsgSend => Access::set_or_create(&mut self.last_send_access, path_id, version),
-            Action::MsgRecv => Access::set_or_create(&mut self.last_recv_access, path_id, version),
+            Action::MsgSend | Action::RecvDrop => {
+                Access::set_or_create(&mut self.last_send_access, path_id, version)
+            }
+            Action::MsgRecv | Action::SendDrop => {
+                Access::set_or_create(&mut self.last_recv_access, path_id, version)
+            }
+            Action::MsgTryRecv => {
+                Access::set_or_create(&mut self.last_send_access, path_id, version);
+                Access::set_or_create(&mut self.last_recv_access, path_id, version);
+            }
         }
     }
 }
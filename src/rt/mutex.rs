@@ -1,4 +1,6 @@
+use crate::rt::location::{self, LocationSet};
 use crate::rt::object;
+use crate::rt::orderings::Site;
 use crate::rt::{thread, Access, Location, Synchronize, VersionVec};
 
 use std::sync::atomic::Ordering::{Acquire, Release};
@@ -18,11 +20,28 @@ pub(super) struct State {
     /// references the thread that currently holds the mutex.
     lock: Option<thread::Id>,
 
+    /// `true` if a thread panicked while holding the lock.
+    poisoned: bool,
+
     /// Tracks access to the mutex
     last_access: Option<Access>,
 
     /// Causality transfers between threads
     synchronize: Synchronize,
+
+    /// Version at which the mutex was last locked or unlocked, per thread
+    locked_at: VersionVec,
+
+    /// Where the mutex was last locked, per thread
+    lock_locations: LocationSet,
+
+    /// Version at which the data was last accessed through exclusive
+    /// ownership of the mutex, with `get_mut` or `into_inner`, per thread
+    exclusive_at: VersionVec,
+
+    /// Where the data was last accessed through exclusive ownership, per
+    /// thread
+    exclusive_locations: LocationSet,
 }
 
 impl Mutex {
@@ -31,8 +50,13 @@ impl Mutex {
             let state = execution.objects.insert(State {
                 seq_cst,
                 lock: None,
+                poisoned: false,
                 last_access: None,
                 synchronize: Synchronize::new(),
+                locked_at: VersionVec::new(),
+                lock_locations: LocationSet::new(),
+                exclusive_at: VersionVec::new(),
+                exclusive_locations: LocationSet::new(),
             });
 
             trace!(?state, ?seq_cst, "Mutex::new");
@@ -41,14 +65,22 @@ impl Mutex {
         })
     }
 
+    /// Tracks the threads accessing the mutex, created at `site`.
+    pub(crate) fn track_sharing(&self, site: Site) {
+        self.state.track_sharing("mutex", site);
+    }
+
     pub(crate) fn acquire_lock(&self, location: Location) {
         self.state.branch_acquire(self.is_locked(), location);
-        assert!(self.post_acquire(), "expected to be able to acquire lock");
+        assert!(
+            self.post_acquire(location),
+            "expected to be able to acquire lock"
+        );
     }
 
     pub(crate) fn try_acquire_lock(&self, location: Location) -> bool {
         self.state.branch_opaque(location);
-        self.post_acquire()
+        self.post_acquire(location)
     }
 
     pub(crate) fn release_lock(&self) {
@@ -66,6 +98,7 @@ impl Mutex {
             state
                 .synchronize
                 .sync_store(&mut execution.threads, Release);
+            state.locked_at.join(&execution.threads.active().causality);
 
             if state.seq_cst {
                 // Establish sequential consistency between the lock's operations.
@@ -93,7 +126,58 @@ impl Mutex {
         });
     }
 
-    fn post_acquire(&self) -> bool {
+    /// Marks the mutex as poisoned, as the thread holding the lock panicked.
+    pub(crate) fn poison(&self) {
+        super::execution(|execution| {
+            self.state.get_mut(&mut execution.objects).poisoned = true;
+        })
+    }
+
+    /// Returns `true` if the mutex is poisoned.
+    ///
+    /// This is not a branch point.
+    pub(crate) fn is_poisoned(&self) -> bool {
+        super::execution(|execution| self.state.get(&execution.objects).poisoned)
+    }
+
+    /// Clears the poisoned state of the mutex.
+    ///
+    /// This is not a branch point.
+    pub(crate) fn clear_poison(&self) {
+        super::execution(|execution| {
+            self.state.get_mut(&mut execution.objects).poisoned = false;
+        })
+    }
+
+    /// Tracks an access to the data through exclusive ownership of the mutex,
+    /// which does not lock it. The access must not be concurrent with locking
+    /// the mutex.
+    pub(crate) fn exclusive_access(&self, location: Location) {
+        super::execution(|execution| {
+            let state = self.state.get_mut(&mut execution.objects);
+            let current = &execution.threads.active().causality;
+            let thread_id = execution.threads.active_id();
+
+            state
+                .exclusive_locations
+                .track(location, &execution.threads);
+
+            if let Some(locked) = current.ahead(&state.locked_at) {
+                location::panic("Causality violation: Concurrent lock and exclusive accesses.")
+                    .thread("lock", locked, state.lock_locations[locked])
+                    .thread("exclusive access", thread_id, location)
+                    .unordered(
+                        ("lock", locked, &state.locked_at),
+                        ("exclusive access", &execution.threads),
+                    )
+                    .fire();
+            }
+
+            state.exclusive_at.join(current);
+        });
+    }
+
+    fn post_acquire(&self, location: Location) -> bool {
         super::execution(|execution| {
             let state = self.state.get_mut(&mut execution.objects);
             let thread_id = execution.threads.active_id();
@@ -105,7 +189,28 @@ impl Mutex {
             // Set the lock to the current thread
             state.lock = Some(thread_id);
 
-            dbg!(state.synchronize.sync_load(&mut execution.threads, Acquire));
+            state.synchronize.sync_load(&mut execution.threads, Acquire);
+
+            let current = &execution.threads.active().causality;
+
+            state.lock_locations.track(location, &execution.threads);
+
+            if let Some(exclusive) = current.ahead(&state.exclusive_at) {
+                location::panic("Causality violation: Concurrent exclusive access and lock.")
+                    .thread(
+                        "exclusive access",
+                        exclusive,
+                        state.exclusive_locations[exclusive],
+                    )
+                    .thread("lock", thread_id, location)
+                    .unordered(
+                        ("exclusive access", exclusive, &state.exclusive_at),
+                        ("lock", &execution.threads),
+                    )
+                    .fire();
+            }
+
+            state.locked_at.join(current);
 
             if state.seq_cst {
                 // Establish sequential consistency between locks
@@ -84,6 +84,13 @@ impl Notify {
         });
     }
 
+    /// Returns `true` if there is a pending notification, which `wait`
+    /// consumes without blocking.
+    #[cfg(feature = "futures")]
+    pub(crate) fn is_notified(self) -> bool {
+        rt::execution(|execution| self.state.get(&execution.objects).notified)
+    }
+
     pub(crate) fn wait(self, location: Location) {
         let (notified, spurious) = rt::execution(|execution| {
             let spurious = if self.state.get(&execution.objects).might_spur() {
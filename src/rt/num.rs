@@ -27,6 +27,9 @@ macro_rules! impl_num {
 
 impl_num!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
 
+// The casts expose the pointer's provenance, which the cast back to a pointer
+// picks up again, so the pointers loaded from an `AtomicPtr` compare equal to
+// and can be used like the stored ones.
 impl<T> Numeric for *mut T {
     fn into_u64(self) -> u64 {
         self as u64
@@ -1,4 +1,5 @@
 use crate::rt;
+use crate::rt::orderings::Site;
 use crate::rt::{Access, Execution, Location, VersionVec};
 
 use std::fmt;
@@ -138,6 +139,9 @@ objects! {
     // State associated with a modeled channel.
     Channel(rt::mpsc::State),
 
+    // State associated with a modeled one-time initialization.
+    Once(rt::once::State),
+
     // Tracks access to a memory cell
     Cell(rt::cell::State),
 }
@@ -217,6 +221,7 @@ impl Store {
             Entry::Notify(entry) => entry.last_dependent_access(),
             Entry::RwLock(entry) => entry.last_dependent_access(),
             Entry::Channel(entry) => entry.last_dependent_access(operation.action.into()),
+            Entry::Once(entry) => entry.last_dependent_access(),
             obj => panic!(
                 "object is not branchable {:?}; ref = {:?}",
                 obj, operation.obj
@@ -242,6 +247,7 @@ impl Store {
             Entry::Channel(entry) => {
                 entry.set_last_access(operation.action.into(), path_id, dpor_vv)
             }
+            Entry::Once(entry) => entry.set_last_access(path_id, dpor_vv),
             _ => panic!("object is not branchable"),
         }
     }
@@ -260,6 +266,12 @@ impl Store {
 }
 
 impl<T> Ref<T> {
+    /// Records where the object was created, in order to track the threads
+    /// accessing it.
+    pub(super) fn track_sharing(self, kind: &'static str, site: Site) {
+        rt::execution(|execution| execution.sharing.created(self.index, kind, site))
+    }
+
     /// Erase the type marker
     pub(super) fn erase(self) -> Ref<()> {
         Ref {
@@ -271,6 +283,10 @@ impl<T> Ref<T> {
     pub(super) fn ref_eq(self, other: Ref<T>) -> bool {
         self.index == other.index
     }
+
+    pub(super) fn as_usize(self) -> usize {
+        self.index
+    }
 }
 
 impl<T: Object> Ref<T> {
@@ -310,10 +326,7 @@ impl Ref {
 
 impl<T> Clone for Ref<T> {
     fn clone(&self) -> Ref<T> {
-        Ref {
-            index: self.index,
-            _p: PhantomData,
-        }
+        *self
     }
 }
 
@@ -331,6 +344,8 @@ impl<T> fmt::Debug for Ref<T> {
 impl<T: Object<Entry = Entry>> Ref<T> {
     // TODO: rename `branch_disable`
     pub(super) fn branch_acquire(self, is_locked: bool, location: Location) {
+        super::check_invariants(location);
+
         super::branch(|execution| {
             trace!(obj = ?self, ?is_locked, "Object::branch_acquire");
 
@@ -348,6 +363,8 @@ impl<T: Object<Entry = Entry>> Ref<T> {
         action: impl Into<Action> + std::fmt::Debug,
         location: Location,
     ) {
+        super::check_invariants(location);
+
         super::branch(|execution| {
             trace!(obj = ?self, ?action, "Object::branch_action");
 
@@ -361,6 +378,8 @@ impl<T: Object<Entry = Entry>> Ref<T> {
         disable: bool,
         location: Location,
     ) {
+        super::check_invariants(location);
+
         super::branch(|execution| {
             trace!(obj = ?self, ?action, ?disable, "Object::branch_disable");
 
@@ -384,6 +403,9 @@ impl<T: Object<Entry = Entry>> Ref<T> {
             self
         );
 
+        let thread = execution.threads.active_id().as_usize();
+        execution.sharing.accessed(self.index, thread);
+
         execution.threads.active_mut().operation = Some(Operation {
             obj: self.erase(),
             action,
@@ -404,6 +426,36 @@ impl Operation {
     pub(super) fn location(&self) -> Location {
         self.location
     }
+
+    /// Index of the object in the store, which identifies it within an
+    /// execution.
+    pub(super) fn object_id(&self) -> usize {
+        self.obj.index
+    }
+
+    /// Name of the action, as written to exported schedules.
+    pub(super) fn kind(&self) -> &'static str {
+        use rt::{arc, atomic, mpsc, rwlock};
+
+        match self.action {
+            Action::Arc(arc::Action::RefInc) => "ref_inc",
+            Action::Arc(arc::Action::RefDec) => "ref_dec",
+            Action::Arc(arc::Action::WeakRef) => "weak_ref",
+            Action::Atomic(atomic::Action::Load) => "load",
+            Action::Atomic(atomic::Action::Store) => "store",
+            Action::Atomic(atomic::Action::Rmw) => "rmw",
+            Action::Channel(mpsc::Action::MsgSend) => "send",
+            Action::Channel(mpsc::Action::MsgRecv) => "recv",
+            Action::Channel(mpsc::Action::MsgTryRecv) => "try_recv",
+            Action::Channel(mpsc::Action::RecvDrop) => "recv_drop",
+            Action::Channel(mpsc::Action::SendDrop) => "send_drop",
+            Action::RwLock(rwlock::Action::Read) => "read_lock",
+            Action::RwLock(rwlock::Action::Write) => "write_lock",
+            Action::RwLock(rwlock::Action::TryRead) => "try_read_lock",
+            Action::RwLock(rwlock::Action::TryWrite) => "try_write_lock",
+            Action::Opaque => "opaque",
+        }
+    }
 }
 
 impl From<Action> for rt::arc::Action {
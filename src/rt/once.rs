@@ -0,0 +1,203 @@
+use crate::rt::object;
+use crate::rt::{Access, Execution, Location, Synchronize, VersionVec};
+
+use std::sync::atomic::Ordering::{Acquire, Release};
+
+use tracing::trace;
+
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Once {
+    state: object::Ref<State>,
+}
+
+/// Outcome of `Once::begin`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Begin {
+    /// The current thread runs the initialization, then calls `Once::end`
+    /// or `Once::abort`.
+    Run,
+    /// The initialization already completed.
+    Complete,
+    /// The initialization panicked.
+    Poisoned,
+}
+
+#[derive(Debug)]
+pub(super) struct State {
+    /// `true` once initialization completed.
+    complete: bool,
+
+    /// `true` while a thread runs the initialization, between `begin` and
+    /// `end` or `abort`.
+    running: bool,
+
+    /// `true` if the initialization panicked.
+    poisoned: bool,
+
+    /// Tracks access to the once object
+    last_access: Option<Access>,
+
+    /// Causality transfers between threads
+    synchronize: Synchronize,
+}
+
+impl Once {
+    pub(crate) fn new() -> Once {
+        super::execution(|execution| {
+            let state = execution.objects.insert(State {
+                complete: false,
+                running: false,
+                poisoned: false,
+                last_access: None,
+                synchronize: Synchronize::new(),
+            });
+
+            trace!(?state, "Once::new");
+
+            Once { state }
+        })
+    }
+
+    /// Returns `true` if initialization completed, synchronizing with the
+    /// thread that completed it.
+    pub(crate) fn is_complete(&self, location: Location) -> bool {
+        self.state.branch_opaque(location);
+        self.post_acquire()
+    }
+
+    /// Blocks until initialization completed, synchronizing with the thread
+    /// that completed it.
+    pub(crate) fn wait(&self, location: Location) {
+        loop {
+            let complete =
+                super::execution(|execution| self.state.get(&execution.objects).complete);
+
+            self.state.branch_acquire(!complete, location);
+
+            // The thread is also unblocked when an initialization is aborted
+            if self.post_acquire() {
+                return;
+            }
+        }
+    }
+
+    /// Begins running the initialization, unless it already completed or
+    /// panicked. Blocks while another thread runs the initialization.
+    pub(crate) fn begin(&self, location: Location) -> Begin {
+        loop {
+            let running = super::execution(|execution| self.state.get(&execution.objects).running);
+
+            self.state.branch_acquire(running, location);
+
+            // Another thread unblocked at the same time may have started
+            // running the initialization again, in which case this one blocks
+            // again.
+            let begin = super::execution(|execution| {
+                let state = self.state.get_mut(&mut execution.objects);
+
+                if state.poisoned {
+                    Some(Begin::Poisoned)
+                } else if state.complete {
+                    state.synchronize.sync_load(&mut execution.threads, Acquire);
+                    Some(Begin::Complete)
+                } else if state.running {
+                    None
+                } else {
+                    state.running = true;
+                    Some(Begin::Run)
+                }
+            });
+
+            if let Some(begin) = begin {
+                return begin;
+            }
+        }
+    }
+
+    /// Completes the initialization begun by the current thread.
+    pub(crate) fn end(&self) {
+        self.finish(true, false)
+    }
+
+    /// Aborts the initialization begun by the current thread, as it panicked.
+    /// Unless the `Once` is poisoned, other threads may begin it again.
+    pub(crate) fn abort(&self, poison: bool) {
+        self.finish(false, poison)
+    }
+
+    fn finish(&self, complete: bool, poison: bool) {
+        super::execution(|execution| {
+            let state = self.state.get_mut(&mut execution.objects);
+
+            state.running = false;
+            state.complete = complete;
+            state.poisoned = poison;
+
+            // Execution has deadlocked, cleanup does not matter.
+            if !execution.threads.is_active() {
+                return;
+            }
+
+            self.unblock_waiters(execution);
+        })
+    }
+
+    fn unblock_waiters(&self, execution: &mut Execution) {
+        let state = self.state.get_mut(&mut execution.objects);
+
+        state
+            .synchronize
+            .sync_store(&mut execution.threads, Release);
+
+        // Unblock the threads waiting for the initialization
+        let thread_id = execution.threads.active_id();
+
+        for (id, thread) in execution.threads.iter_mut() {
+            if id == thread_id {
+                continue;
+            }
+
+            let obj = thread
+                .operation
+                .as_ref()
+                .map(|operation| operation.object());
+
+            if obj == Some(self.state.erase()) {
+                trace!(state = ?self.state, thread = ?id, "Once::unblock_waiters");
+                thread.set_runnable();
+            }
+        }
+    }
+
+    /// Returns `true` if initialization completed, for `Debug` output.
+    ///
+    /// This is neither a branch point nor does it synchronize. Returns `false`
+    /// when called from outside the execution.
+    pub(crate) fn debug_is_complete(&self) -> bool {
+        super::try_execution(|execution| self.state.get(&execution.objects).complete)
+            .unwrap_or(false)
+    }
+
+    fn post_acquire(&self) -> bool {
+        super::execution(|execution| {
+            let state = self.state.get_mut(&mut execution.objects);
+
+            if !state.complete {
+                return false;
+            }
+
+            state.synchronize.sync_load(&mut execution.threads, Acquire);
+            true
+        })
+    }
+}
+
+impl State {
+    pub(crate) fn last_dependent_access(&self) -> Option<&Access> {
+        self.last_access.as_ref()
+    }
+
+    pub(crate) fn set_last_access(&mut self, path_id: usize, version: &VersionVec) {
+        Access::set_or_create(&mut self.last_access, path_id, version);
+    }
+}
@@ -0,0 +1,109 @@
+use std::collections::BTreeMap;
+use std::panic::Location;
+use std::sync::atomic::Ordering::{self, *};
+
+/// Call site of an atomic operation.
+pub(crate) type Site = &'static Location<'static>;
+
+/// Sites of atomic operations, with the kind of operation and the ordering
+/// used there.
+pub(crate) type Sites = BTreeMap<Site, (Kind, Ordering)>;
+
+/// Kind of atomic operation, which determines the orderings it accepts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Kind {
+    Load,
+    Store,
+    Rmw,
+}
+
+/// Tracks the orderings of atomic operations in order to suggest weaker ones,
+/// see `model::Builder::suggest_orderings`.
+#[derive(Debug, Default)]
+pub(crate) struct Orderings {
+    /// When `true`, the sites of all non-`Relaxed` atomic operations are
+    /// recorded.
+    record: bool,
+
+    /// Recorded sites, with the kind of operation and the ordering used there.
+    sites: Sites,
+
+    /// Site whose operations are performed with a weaker ordering.
+    weakened: Option<(Site, Ordering)>,
+}
+
+impl Orderings {
+    /// Record the sites of all non-`Relaxed` operations.
+    pub(crate) fn record() -> Orderings {
+        Orderings {
+            record: true,
+            ..Orderings::default()
+        }
+    }
+
+    /// Perform the operations at `site` with `ordering` instead.
+    pub(crate) fn weaken(site: Site, ordering: Ordering) -> Orderings {
+        Orderings {
+            weakened: Some((site, ordering)),
+            ..Orderings::default()
+        }
+    }
+
+    /// Returns `true` if the operations at a site are performed with a
+    /// weaker ordering.
+    pub(crate) fn is_weakened(&self) -> bool {
+        self.weakened.is_some()
+    }
+
+    /// Returns the recorded sites, with the kind of operation and the ordering
+    /// used there.
+    pub(crate) fn into_sites(self) -> Sites {
+        self.sites
+    }
+
+    /// Returns the orderings to use for an operation at `site`, recording it
+    /// if needed.
+    pub(crate) fn apply(
+        &mut self,
+        site: Site,
+        kind: Kind,
+        success: Ordering,
+        failure: Ordering,
+    ) -> (Ordering, Ordering) {
+        if let Some((weakened_site, weakened)) = self.weakened {
+            if weakened_site == site {
+                return (weakened, failure_for(weakened, failure));
+            }
+        }
+
+        if self.record && (success != Relaxed || failure != Relaxed) {
+            self.sites.entry(site).or_insert((kind, success));
+        }
+
+        (success, failure)
+    }
+}
+
+impl Kind {
+    /// Returns the orderings one step weaker than `ordering` that are valid
+    /// for this kind of operation.
+    pub(crate) fn weaker(self, ordering: Ordering) -> &'static [Ordering] {
+        match (self, ordering) {
+            (Kind::Load, SeqCst) => &[Acquire],
+            (Kind::Store, SeqCst) => &[Release],
+            (Kind::Rmw, SeqCst) => &[AcqRel],
+            (Kind::Rmw, AcqRel) => &[Acquire, Release],
+            (_, Acquire) | (_, Release) => &[Relaxed],
+            _ => &[],
+        }
+    }
+}
+
+/// Returns the failure ordering to use with the weakened `success` ordering.
+fn failure_for(success: Ordering, failure: Ordering) -> Ordering {
+    match (success, failure) {
+        (Relaxed, _) | (Release, _) | (_, Relaxed) => Relaxed,
+        (Acquire, _) | (AcqRel, _) => Acquire,
+        _ => failure,
+    }
+}
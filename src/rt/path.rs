@@ -32,6 +32,71 @@ pub(crate) struct Path {
 
     /// How to reset the `exploring` state
     exploring_on_start: bool,
+
+    /// When set, branches are picked at random instead of being explored
+    /// exhaustively.
+    #[cfg_attr(feature = "checkpoint", serde(skip))]
+    random: Option<Rng>,
+
+    /// When set, branches follow the given choices instead of being explored
+    /// exhaustively.
+    #[cfg_attr(feature = "checkpoint", serde(skip))]
+    replay: Option<Replay>,
+
+    /// When set, only part of the execution space is explored, see
+    /// `Path::shard`.
+    shard: Option<Shard>,
+}
+
+/// Splits the exploration between shards.
+///
+/// The first `SHARD_DEPTH` branches of the path are its top. Each combination
+/// of choices at the top is a unit of the exploration, assigned to a shard in
+/// turn. A shard only explores the units assigned to it, but still runs the
+/// first execution of every other unit, so all shards discover the same
+/// alternatives at the top of the path and number the units the same way.
+///
+/// DPOR may request other alternatives at the top of the path from the
+/// remaining executions of a unit, which only its shard runs. These are
+/// private: explored by that shard without starting a new unit.
+#[derive(Debug)]
+#[cfg_attr(feature = "checkpoint", derive(Serialize, Deserialize))]
+struct Shard {
+    index: usize,
+    total: usize,
+
+    /// Number of the current unit, in exploration order
+    unit: usize,
+
+    /// `true` during the first execution of a unit
+    discovering: bool,
+
+    /// Position of the first branch at the top of the path taking a private
+    /// choice in the current execution.
+    private_from: Option<usize>,
+
+    /// Alternatives known to all shards, for each branch at the top of the
+    /// path. For schedules, a bit per thread. For loads and spurious
+    /// branches, either all alternatives or none.
+    shared: Vec<u32>,
+}
+
+/// Number of branches at the top of a sharded path.
+const SHARD_DEPTH: usize = 16;
+
+/// A choice made at a branch point: the index of the scheduled thread, the
+/// position of the store read by a load, or `1` for a spurious wakeup. `None`
+/// stands for the default choice: keep running the active thread, read the
+/// newest store, and don't wake up spuriously.
+pub(crate) type Choice = Option<u8>;
+
+/// Choices to follow in replay mode, see `Path::start_replay`.
+#[derive(Debug)]
+struct Replay {
+    choices: Vec<Choice>,
+
+    /// The choices that were actually made, with the default ones as `None`.
+    taken: Vec<Choice>,
 }
 
 #[derive(Debug)]
@@ -75,6 +140,10 @@ pub(crate) struct Spurious {
     exploring: bool,
 }
 
+/// `splitmix64` generator used to pick branches in random mode.
+#[derive(Debug)]
+struct Rng(u64);
+
 objects! {
     #[derive(Debug)]
     #[cfg_attr(feature = "checkpoint", derive(Serialize, Deserialize))]
@@ -133,6 +202,111 @@ impl Path {
             exploring,
             skipping: false,
             exploring_on_start: exploring,
+            random: None,
+            replay: None,
+            shard: None,
+        }
+    }
+
+    /// Only explore the units of the execution space assigned to shard
+    /// `index` out of `total`, see `Shard`.
+    ///
+    /// A path restored from a checkpoint keeps its progress.
+    pub(crate) fn shard(&mut self, index: usize, total: usize) {
+        if let Some(shard) = &self.shard {
+            if (shard.index, shard.total) == (index, total) {
+                return;
+            }
+        }
+
+        assert!(
+            self.branches.len() == 0,
+            "cannot shard a path that is already being explored"
+        );
+
+        self.shard = Some(Shard {
+            index,
+            total,
+            unit: 0,
+            discovering: true,
+            private_from: None,
+            shared: Vec::new(),
+        });
+    }
+
+    /// Returns `false` if the current execution only runs to discover the top
+    /// of the path, as it belongs to a unit assigned to another shard.
+    pub(crate) fn is_assigned(&self) -> bool {
+        match &self.shard {
+            Some(shard) if self.random.is_none() && shard.private_from.is_none() => {
+                shard.unit % shard.total == shard.index
+            }
+            _ => true,
+        }
+    }
+
+    /// Pick branches at random, using the given seed, until `stop_random` is
+    /// called. Each call to `step` then starts a new random execution.
+    pub(crate) fn start_random(&mut self, seed: u64) {
+        self.random = Some(Rng(seed));
+    }
+
+    /// Return to exhaustively exploring the execution space.
+    pub(crate) fn stop_random(&mut self) {
+        self.random = None;
+        self.branches.clear();
+    }
+
+    /// Follow `choices` for the branches of a single execution. Branches with
+    /// no choice, or whose choice is not possible, take the default choice.
+    pub(crate) fn start_replay(&mut self, choices: Vec<Choice>) {
+        self.replay = Some(Replay {
+            choices,
+            taken: Vec::new(),
+        });
+    }
+
+    /// Returns the choices made by the execution replayed with
+    /// `start_replay`, with the default ones as `None`.
+    pub(crate) fn replayed(&self) -> Vec<Choice> {
+        self.replay
+            .as_ref()
+            .map(|replay| replay.taken.clone())
+            .unwrap_or_default()
+    }
+
+    /// Returns the choices made by the current execution, up to the current
+    /// branch.
+    pub(crate) fn choices(&self) -> Vec<Choice> {
+        (0..self.pos)
+            .map(|i| {
+                let entry = object::Ref::from_usize(i);
+
+                if let Some(schedule) = entry.downcast::<Schedule>(&self.branches) {
+                    schedule.get(&self.branches).active_thread_index()
+                } else if let Some(load) = entry.downcast::<Load>(&self.branches) {
+                    Some(load.get(&self.branches).pos)
+                } else if let Some(spurious) = entry.downcast::<Spurious>(&self.branches) {
+                    Some(spurious.get(&self.branches).spur as u8)
+                } else {
+                    unreachable!();
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the choice to replay at the next new branch, if any.
+    fn replay_choice(&self) -> Choice {
+        self.replay
+            .as_ref()
+            .and_then(|replay| replay.choices.get(self.branches.len()).copied())
+            .flatten()
+    }
+
+    /// Records the choice made at a new branch, when replaying.
+    fn replay_taken(&mut self, choice: Choice) {
+        if let Some(replay) = &mut self.replay {
+            replay.taken.push(choice);
         }
     }
 
@@ -155,6 +329,12 @@ impl Path {
         self.skipping = true;
     }
 
+    /// Maximum number of branches of the path
+    #[cfg(feature = "futures")]
+    pub(crate) fn max_branches(&self) -> usize {
+        self.branches.capacity()
+    }
+
     pub(crate) fn set_max_branches(&mut self, max_branches: usize) {
         self.branches
             .reserve_exact(max_branches - self.branches.len());
@@ -170,10 +350,13 @@ impl Path {
         self.pos
     }
 
-    /// Push a new atomic-load branch
-    pub(super) fn push_load(&mut self, seed: &[u8]) {
+    /// Push a new atomic-load branch. `newest` is the newest store, which is
+    /// read by default when replaying.
+    pub(super) fn push_load(&mut self, seed: &[u8], newest: usize) {
         assert_path_len!(self.branches);
 
+        let replay_choice = self.replay_choice();
+
         let load_ref = self.branches.insert(Load {
             values: [0; MAX_ATOMIC_HISTORY],
             pos: 0,
@@ -200,6 +383,27 @@ impl Path {
             load.values[i] = store;
             load.len += 1;
         }
+
+        if let Some(rng) = &mut self.random {
+            load.pos = rng.gen_range(load.len as usize) as u8;
+        }
+
+        if self.replay.is_some() {
+            let default = load.values[..load.len as usize]
+                .iter()
+                .position(|&store| store as usize == newest)
+                .unwrap_or(0) as u8;
+
+            load.pos = match replay_choice {
+                Some(pos) if pos < load.len => pos,
+                _ => default,
+            };
+
+            let taken = Some(load.pos).filter(|&pos| pos != default);
+            self.replay_taken(taken);
+        }
+
+        self.shard_branch();
     }
 
     /// Returns the atomic write to read
@@ -221,10 +425,21 @@ impl Path {
         if self.is_traversed() {
             assert_path_len!(self.branches);
 
+            let spur = match &mut self.random {
+                Some(rng) => rng.gen_range(2) == 0,
+                None => self.replay_choice() == Some(1),
+            };
+
+            if self.replay.is_some() {
+                self.replay_taken(Some(1).filter(|_| spur));
+            }
+
             self.branches.insert(Spurious {
-                spur: false,
+                spur,
                 exploring: self.exploring,
             });
+
+            self.shard_branch();
         }
 
         let spurious = object::Ref::from_usize(self.pos)
@@ -248,6 +463,7 @@ impl Path {
 
             // Find the last thread scheduling branch in the path
             let prev = self.last_schedule();
+            let replay_choice = self.replay_choice();
 
             // Entering a new exploration space.
             //
@@ -316,6 +532,31 @@ impl Path {
             let schedule = schedule_ref.get_mut(&mut self.branches);
             schedule.initial_active = initial_active;
             schedule.preemptions = preemptions;
+
+            if let Some(rng) = &mut self.random {
+                if self
+                    .preemption_bound
+                    .map_or(true, |bound| preemptions < bound)
+                {
+                    schedule.pick_random(rng);
+                }
+            }
+
+            if self.replay.is_some() {
+                let preemptible = self
+                    .preemption_bound
+                    .map_or(true, |bound| preemptions < bound);
+
+                let taken = replay_choice.filter(|&index| {
+                    preemptible
+                        && index != active.unwrap_or(u8::MAX)
+                        && schedule.pick(index as usize)
+                });
+
+                self.replay_taken(taken);
+            }
+
+            self.shard_branch();
         }
 
         let schedule = object::Ref::from_usize(self.pos)
@@ -334,15 +575,18 @@ impl Path {
     }
 
     pub(super) fn backtrack(&mut self, mut point: usize, thread_id: thread::Id) {
+        // Random and replayed executions are not explored further
+        if self.random.is_some() || self.replay.is_some() {
+            return;
+        }
+
         let schedule = loop {
             if let Some(schedule_ref) =
                 object::Ref::from_usize(point).downcast::<Schedule>(&self.branches)
             {
-                let schedule = schedule_ref.get_mut(&mut self.branches);
-
-                if schedule.exploring {
-                    schedule.backtrack(thread_id, self.preemption_bound);
-                    break schedule;
+                if schedule_ref.get(&self.branches).exploring {
+                    self.backtrack_schedule(schedule_ref, thread_id);
+                    break schedule_ref.get(&self.branches);
                 }
             }
 
@@ -367,18 +611,16 @@ impl Path {
                     let active_a = curr.get(&self.branches).active_thread_index();
                     let active_b = prev.get(&self.branches).active_thread_index();
 
-                    if active_a != active_b && curr.get_mut(&mut self.branches).exploring {
-                        curr.get_mut(&mut self.branches)
-                            .backtrack(thread_id, self.preemption_bound);
+                    if active_a != active_b && curr.get(&self.branches).exploring {
+                        self.backtrack_schedule(curr, thread_id);
                         return;
                     }
 
                     curr = prev;
                 } else {
-                    if curr.get(&mut self.branches).exploring {
+                    if curr.get(&self.branches).exploring {
                         // This is the very first schedule
-                        curr.get_mut(&mut self.branches)
-                            .backtrack(thread_id, self.preemption_bound);
+                        self.backtrack_schedule(curr, thread_id);
                     }
                     return;
                 }
@@ -386,6 +628,21 @@ impl Path {
         }
     }
 
+    fn backtrack_schedule(&mut self, schedule_ref: object::Ref<Schedule>, thread_id: thread::Id) {
+        let schedule = schedule_ref.get_mut(&mut self.branches);
+        schedule.backtrack(thread_id, self.preemption_bound);
+
+        // Alternatives requested by the first execution of a unit are known
+        // to all shards.
+        if let Some(shard) = &mut self.shard {
+            if let Some(shared) = shard.shared.get_mut(schedule_ref.as_usize()) {
+                if shard.discovering {
+                    schedule.share(thread_id, self.preemption_bound, shared);
+                }
+            }
+        }
+    }
+
     /// Reset the path to prepare for the next exploration of the model.
     ///
     /// This function will also trim the object store, dropping any objects that
@@ -399,13 +656,37 @@ impl Path {
         self.exploring = self.exploring_on_start;
         self.skipping = false;
 
+        if self.random.is_some() {
+            self.branches.clear();
+            return true;
+        }
+
+        // Skip the rest of a unit assigned to another shard
+        if !self.is_assigned() && self.branches.len() > SHARD_DEPTH {
+            self.branches
+                .truncate(object::Ref::from_usize(SHARD_DEPTH - 1));
+        }
+
+        match self.advance() {
+            Some(last) => {
+                self.shard_advanced(last);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Advances the path to the next execution, returning the position of
+    /// the branch taking a new choice, or `None` if the path was fully
+    /// explored.
+    fn advance(&mut self) -> Option<usize> {
         // Set the final branch to try the next option. If all options have been
         // traversed, pop the final branch and try again w/ the one under it.
         //
         // This is depth-first tree traversal.
         //
-        for last in (0..self.branches.len()).rev() {
-            let last = object::Ref::from_usize(last);
+        for index in (0..self.branches.len()).rev() {
+            let last = object::Ref::from_usize(index);
 
             // Remove all objects that were created **after** this branch
             self.branches.truncate(last);
@@ -433,7 +714,7 @@ impl Path {
                     .is_some();
 
                 if rem {
-                    return true;
+                    return Some(index);
                 }
             } else if let Some(load_ref) = last.downcast::<Load>(&self.branches) {
                 let load = load_ref.get_mut(&mut self.branches);
@@ -445,7 +726,7 @@ impl Path {
                 load.pos += 1;
 
                 if load.pos < load.len {
-                    return true;
+                    return Some(index);
                 }
             } else if let Some(spurious_ref) = last.downcast::<Spurious>(&self.branches) {
                 let spurious = spurious_ref.get_mut(&mut self.branches);
@@ -456,18 +737,89 @@ impl Path {
 
                 if !spurious.spur {
                     spurious.spur = true;
-                    return true;
+                    return Some(index);
                 }
             } else {
                 unreachable!();
             }
         }
 
-        false
+        None
+    }
+
+    /// Tracks the branch at the end of the path, that was just created, for
+    /// sharding. Alternatives of a branch at the top of the path created by
+    /// the first execution of a unit are known to all shards.
+    fn shard_branch(&mut self) {
+        if self.random.is_some() || self.replay.is_some() {
+            return;
+        }
+
+        let index = self.branches.len() - 1;
+
+        let shard = match &mut self.shard {
+            Some(shard) if index < SHARD_DEPTH => shard,
+            _ => return,
+        };
+
+        let entry = object::Ref::from_usize(index);
+
+        let shared = if !shard.discovering {
+            0
+        } else if let Some(schedule) = entry.downcast::<Schedule>(&self.branches) {
+            schedule
+                .get(&self.branches)
+                .active_thread_index()
+                .map_or(0, |index| 1 << index)
+        } else {
+            u32::MAX
+        };
+
+        shard.shared.truncate(index);
+        shard.shared.push(shared);
+    }
+
+    /// Tracks the branch at `index` taking a new choice, for sharding. Taking
+    /// a shared choice at the top of the path starts a new unit.
+    fn shard_advanced(&mut self, index: usize) {
+        let shard = match &mut self.shard {
+            Some(shard) => shard,
+            None => return,
+        };
+
+        if index >= SHARD_DEPTH {
+            shard.discovering = false;
+            return;
+        }
+
+        shard.shared.truncate(index + 1);
+
+        let entry = object::Ref::from_usize(index);
+        let mask = shard.shared[index];
+
+        let shared = if let Some(schedule) = entry.downcast::<Schedule>(&self.branches) {
+            schedule
+                .get(&self.branches)
+                .active_thread_index()
+                .is_some_and(|index| mask & (1 << index) != 0)
+        } else {
+            mask != 0
+        };
+
+        let private_from = shard.private_from.filter(|&private| private < index);
+
+        if shared && private_from.is_none() {
+            shard.unit += 1;
+            shard.discovering = true;
+            shard.private_from = None;
+        } else {
+            shard.discovering = false;
+            shard.private_from = private_from.or(Some(index));
+        }
     }
 
     fn last_schedule(&self) -> Option<object::Ref<Schedule>> {
-        self.branches.iter_ref::<Schedule>().rev().next()
+        self.branches.iter_ref::<Schedule>().next_back()
     }
 }
 
@@ -490,6 +842,57 @@ impl Schedule {
         self.preemptions
     }
 
+    /// Replace the active thread with the thread at `index`, returning `false`
+    /// if it is not runnable.
+    fn pick(&mut self, index: usize) -> bool {
+        let runnable = |th: &Thread| matches!(th, Thread::Active | Thread::Skip);
+
+        if !self.threads.get(index).is_some_and(runnable) {
+            return false;
+        }
+
+        for (i, th) in self.threads.iter_mut().enumerate() {
+            if runnable(th) {
+                *th = if i == index {
+                    Thread::Active
+                } else {
+                    Thread::Skip
+                };
+            }
+        }
+
+        true
+    }
+
+    /// Replace the active thread with one picked at random among the
+    /// runnable threads.
+    fn pick_random(&mut self, rng: &mut Rng) {
+        let runnable = self
+            .threads
+            .iter()
+            .filter(|th| matches!(th, Thread::Active | Thread::Skip))
+            .count();
+
+        if runnable == 0 {
+            return;
+        }
+
+        let pick = rng.gen_range(runnable);
+
+        let runnable = self
+            .threads
+            .iter_mut()
+            .filter(|th| matches!(th, Thread::Active | Thread::Skip));
+
+        for (i, th) in runnable.enumerate() {
+            *th = if i == pick {
+                Thread::Active
+            } else {
+                Thread::Skip
+            };
+        }
+    }
+
     fn backtrack(&mut self, thread_id: thread::Id, preemption_bound: Option<u8>) {
         assert!(self.exploring);
 
@@ -520,6 +923,52 @@ impl Schedule {
             }
         }
     }
+
+    /// Marks the threads DPOR requests as known to all shards, in `shared`.
+    ///
+    /// A thread that was already explored as a private choice is explored
+    /// again, as other shards explore it as a new unit.
+    fn share(&mut self, thread_id: thread::Id, preemption_bound: Option<u8>, shared: &mut u32) {
+        if preemption_bound == Some(self.preemptions) {
+            return;
+        }
+
+        let thread_id = thread_id.as_usize();
+
+        if thread_id >= self.threads.len() {
+            return;
+        }
+
+        let all = !self.threads[thread_id].is_enabled();
+
+        for (i, th) in self.threads.iter_mut().enumerate() {
+            if (all || i == thread_id) && *shared & (1 << i) == 0 {
+                match th {
+                    Thread::Pending => {}
+                    Thread::Skip | Thread::Visited => *th = Thread::Pending,
+                    _ => continue,
+                }
+
+                *shared |= 1 << i;
+            }
+        }
+    }
+}
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a value in `0..n`
+    fn gen_range(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
 }
 
 impl Thread {
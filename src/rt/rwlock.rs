@@ -1,7 +1,9 @@
+use crate::rt::location::{self, LocationSet};
 use crate::rt::object;
+use crate::rt::orderings::Site;
 use crate::rt::{thread, Access, Execution, Location, Synchronize, VersionVec};
 
-use std::collections::HashSet;
+use std::collections::HashMap;
 use std::sync::atomic::Ordering::{Acquire, Release};
 
 #[derive(Debug, Copy, Clone)]
@@ -11,7 +13,8 @@ pub(crate) struct RwLock {
 
 #[derive(Debug, PartialEq)]
 enum Locked {
-    Read(HashSet<thread::Id>),
+    /// Number of read guards held by each thread
+    Read(HashMap<thread::Id, usize>),
     Write(thread::Id),
 }
 
@@ -22,12 +25,18 @@ pub(super) enum Action {
 
     /// Write lock
     Write,
+
+    /// Read lock attempt, which does not block
+    TryRead,
+
+    /// Write lock attempt, which does not block
+    TryWrite,
 }
 
 #[derive(Debug)]
 pub(super) struct State {
     /// A single `thread::Id` when Write locked.
-    /// A set of `thread::Id` when Read locked.
+    /// The `thread::Id`s holding read guards when Read locked.
     lock: Option<Locked>,
 
     /// Tracks write access to the rwlock.
@@ -35,6 +44,20 @@ pub(super) struct State {
 
     /// Causality transfers between threads
     synchronize: Synchronize,
+
+    /// Version at which the rwlock was last locked or unlocked, per thread
+    locked_at: VersionVec,
+
+    /// Where the rwlock was last locked, per thread
+    lock_locations: LocationSet,
+
+    /// Version at which the data was last accessed through exclusive
+    /// ownership of the rwlock, with `get_mut` or `into_inner`, per thread
+    exclusive_at: VersionVec,
+
+    /// Where the data was last accessed through exclusive ownership, per
+    /// thread
+    exclusive_locations: LocationSet,
 }
 
 impl RwLock {
@@ -45,12 +68,21 @@ impl RwLock {
                 lock: None,
                 last_access: None,
                 synchronize: Synchronize::new(),
+                locked_at: VersionVec::new(),
+                lock_locations: LocationSet::new(),
+                exclusive_at: VersionVec::new(),
+                exclusive_locations: LocationSet::new(),
             });
 
             RwLock { state }
         })
     }
 
+    /// Tracks the threads accessing the rwlock, created at `site`.
+    pub(crate) fn track_sharing(&self, site: Site) {
+        self.state.track_sharing("rwlock", site);
+    }
+
     /// Acquire the read lock.
     /// Fail to acquire read lock if already *write* locked.
     pub(crate) fn acquire_read_lock(&self, location: Location) {
@@ -58,7 +90,7 @@ impl RwLock {
             .branch_disable(Action::Read, self.is_write_locked(), location);
 
         assert!(
-            self.post_acquire_read_lock(),
+            self.post_acquire_read_lock(location),
             "expected to be able to acquire read lock"
         );
     }
@@ -73,19 +105,19 @@ impl RwLock {
         );
 
         assert!(
-            self.post_acquire_write_lock(),
+            self.post_acquire_write_lock(location),
             "expected to be able to acquire write lock"
         );
     }
 
     pub(crate) fn try_acquire_read_lock(&self, location: Location) -> bool {
-        self.state.branch_action(Action::Read, location);
-        self.post_acquire_read_lock()
+        self.state.branch_action(Action::TryRead, location);
+        self.post_acquire_read_lock(location)
     }
 
     pub(crate) fn try_acquire_write_lock(&self, location: Location) -> bool {
-        self.state.branch_action(Action::Write, location);
-        self.post_acquire_write_lock()
+        self.state.branch_action(Action::TryWrite, location);
+        self.post_acquire_write_lock(location)
     }
 
     pub(crate) fn release_read_lock(&self) {
@@ -96,6 +128,7 @@ impl RwLock {
             state
                 .synchronize
                 .sync_store(&mut execution.threads, Release);
+            state.locked_at.join(&execution.threads.active().causality);
 
             // Establish sequential consistency between the lock's operations.
             execution.threads.seq_cst();
@@ -105,7 +138,14 @@ impl RwLock {
                 _ => panic!("invalid internal loom state"),
             };
 
-            readers.remove(&thread_id);
+            let count = readers
+                .get_mut(&thread_id)
+                .expect("invalid internal loom state");
+            *count -= 1;
+
+            if *count == 0 {
+                readers.remove(&thread_id);
+            }
 
             if readers.is_empty() {
                 state.lock = None;
@@ -124,6 +164,7 @@ impl RwLock {
             state
                 .synchronize
                 .sync_store(&mut execution.threads, Release);
+            state.locked_at.join(&execution.threads.active().causality);
 
             // Establish sequential consistency between the lock's operations.
             execution.threads.seq_cst();
@@ -153,6 +194,69 @@ impl RwLock {
         }
     }
 
+    /// Tracks an access to the data through exclusive ownership of the
+    /// rwlock, which does not lock it. The access must not be concurrent with
+    /// locking the rwlock.
+    pub(crate) fn exclusive_access(&self, location: Location) {
+        super::execution(|execution| {
+            let state = self.state.get_mut(&mut execution.objects);
+            let current = &execution.threads.active().causality;
+            let thread_id = execution.threads.active_id();
+
+            state
+                .exclusive_locations
+                .track(location, &execution.threads);
+
+            if let Some(locked) = current.ahead(&state.locked_at) {
+                location::panic("Causality violation: Concurrent lock and exclusive accesses.")
+                    .thread("lock", locked, state.lock_locations[locked])
+                    .thread("exclusive access", thread_id, location)
+                    .unordered(
+                        ("lock", locked, &state.locked_at),
+                        ("exclusive access", &execution.threads),
+                    )
+                    .fire();
+            }
+
+            state.exclusive_at.join(current);
+        });
+    }
+
+    /// Checks that locking the rwlock is not concurrent with an exclusive
+    /// access to the data, then tracks the lock.
+    fn track_lock(&self, execution: &mut Execution, location: Location) {
+        let state = self.state.get_mut(&mut execution.objects);
+        let current = &execution.threads.active().causality;
+        let thread_id = execution.threads.active_id();
+
+        state.lock_locations.track(location, &execution.threads);
+
+        if let Some(exclusive) = current.ahead(&state.exclusive_at) {
+            location::panic("Causality violation: Concurrent exclusive access and lock.")
+                .thread(
+                    "exclusive access",
+                    exclusive,
+                    state.exclusive_locations[exclusive],
+                )
+                .thread("lock", thread_id, location)
+                .unordered(
+                    ("exclusive access", exclusive, &state.exclusive_at),
+                    ("lock", &execution.threads),
+                )
+                .fire();
+        }
+
+        state.locked_at.join(current);
+    }
+
+    /// Returns the number of read guards currently held.
+    pub(crate) fn reader_count(&self) -> usize {
+        super::execution(|execution| match &self.state.get(&execution.objects).lock {
+            Some(Locked::Read(readers)) => readers.values().sum(),
+            _ => 0,
+        })
+    }
+
     /// Returns `true` if RwLock is read locked
     fn is_read_locked(&self) -> bool {
         super::execution(|execution| {
@@ -162,14 +266,14 @@ impl RwLock {
     }
 
     /// Returns `true` if RwLock is write locked.
-    fn is_write_locked(&self) -> bool {
+    pub(crate) fn is_write_locked(&self) -> bool {
         super::execution(|execution| {
             let lock = &self.state.get(&execution.objects).lock;
             matches!(lock, Some(Locked::Write(_)))
         })
     }
 
-    fn post_acquire_read_lock(&self) -> bool {
+    fn post_acquire_read_lock(&self, location: Location) -> bool {
         super::execution(|execution| {
             let state = self.state.get_mut(&mut execution.objects);
             let thread_id = execution.threads.active_id();
@@ -178,12 +282,12 @@ impl RwLock {
             let mut already_locked = false;
             state.lock = match state.lock.take() {
                 None => {
-                    let mut threads: HashSet<thread::Id> = HashSet::new();
-                    threads.insert(thread_id);
+                    let mut threads: HashMap<thread::Id, usize> = HashMap::new();
+                    threads.insert(thread_id, 1);
                     Some(Locked::Read(threads))
                 }
                 Some(Locked::Read(mut threads)) => {
-                    threads.insert(thread_id);
+                    *threads.entry(thread_id).or_insert(0) += 1;
                     Some(Locked::Read(threads))
                 }
                 Some(Locked::Write(writer)) => {
@@ -197,7 +301,8 @@ impl RwLock {
                 return false;
             }
 
-            dbg!(state.synchronize.sync_load(&mut execution.threads, Acquire));
+            state.synchronize.sync_load(&mut execution.threads, Acquire);
+            self.track_lock(execution, location);
 
             execution.threads.seq_cst();
 
@@ -222,7 +327,7 @@ impl RwLock {
         })
     }
 
-    fn post_acquire_write_lock(&self) -> bool {
+    fn post_acquire_write_lock(&self, location: Location) -> bool {
         super::execution(|execution| {
             let state = self.state.get_mut(&mut execution.objects);
             let thread_id = execution.threads.active_id();
@@ -235,19 +340,23 @@ impl RwLock {
             };
 
             state.synchronize.sync_load(&mut execution.threads, Acquire);
+            self.track_lock(execution, location);
 
             // Establish sequential consistency between locks
             execution.threads.seq_cst();
 
-            // Block all other threads attempting to acquire rwlock
-            // Block all writer threads from attempting to acquire the RwLock
+            // Block all other threads attempting to acquire the RwLock. Lock
+            // attempts are not blocked, as they fail instead.
             for (id, th) in execution.threads.iter_mut() {
                 if id == thread_id {
                     continue;
                 }
 
                 match th.operation.as_ref() {
-                    Some(op) if op.object() == self.state.erase() => {
+                    Some(op)
+                        if op.object() == self.state.erase()
+                            && (op.action() == Action::Read || op.action() == Action::Write) =>
+                    {
                         let location = op.location();
                         th.set_blocked(location);
                     }
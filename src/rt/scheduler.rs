@@ -6,6 +6,8 @@ use generator::{self, Generator, Gn};
 use scoped_tls::scoped_thread_local;
 use std::cell::RefCell;
 use std::collections::VecDeque;
+use std::mem;
+use std::panic::{self, AssertUnwindSafe};
 
 pub(crate) struct Scheduler {
     max_threads: usize,
@@ -43,6 +45,22 @@ impl Scheduler {
         Self::with_state(|state| f(state.execution))
     }
 
+    /// Access the execution if called from a model and the execution is not
+    /// already being accessed.
+    pub(crate) fn try_with_execution<F, R>(f: F) -> Option<R>
+    where
+        F: FnOnce(&mut Execution) -> R,
+    {
+        if !STATE.is_set() {
+            return None;
+        }
+
+        STATE.with(|state| {
+            let mut state = state.try_borrow_mut().ok()?;
+            Some(f(state.execution))
+        })
+    }
+
     /// Perform a context switch
     pub(crate) fn switch() {
         use std::future::Future;
@@ -114,9 +132,18 @@ impl Scheduler {
             queued_spawn: &mut queued_spawn,
         });
 
-        STATE.set(unsafe { transmute_lt(&state) }, || {
-            thread.resume();
+        let res = STATE.set(unsafe { transmute_lt(&state) }, || {
+            panic::catch_unwind(AssertUnwindSafe(|| thread.resume()))
         });
+
+        if let Err(payload) = res {
+            // The threads spawned by the panicking thread never ran. Like the
+            // suspended threads, they are not unwound: dropping their closures
+            // would perform loom operations outside of the execution.
+            mem::forget(queued_spawn);
+            panic::resume_unwind(payload);
+        }
+
         queued_spawn
     }
 
@@ -148,7 +175,7 @@ fn spawn_thread(f: Box<dyn FnOnce()>, stack_size: Option<usize>) -> Thread {
         generator::done!();
     };
     let mut g = match stack_size {
-        Some(stack_size) => Gn::new_opt(stack_size, body),
+        Some(stack_size) => Gn::new_opt(stack_words(stack_size), body),
         None => Gn::new(body),
     };
     g.resume();
@@ -156,6 +183,18 @@ fn spawn_thread(f: Box<dyn FnOnce()>, stack_size: Option<usize>) -> Thread {
     g
 }
 
+/// Converts a stack size in bytes, as requested with `thread::Builder`, to the
+/// number of words `generator` expects.
+///
+/// Sizes smaller than the default stack size of loom threads are rounded up to
+/// it, as the scheduler itself needs some stack. An odd number of words makes
+/// `generator` fill the whole stack to track its usage, so the number of words
+/// is also rounded up to an even one.
+fn stack_words(stack_size: usize) -> usize {
+    let words = stack_size.div_ceil(std::mem::size_of::<usize>());
+    (words.max(generator::DEFAULT_STACK_SIZE) + 1) & !1
+}
+
 unsafe fn transmute_lt<'a, 'b>(state: &'a RefCell<State<'b>>) -> &'a RefCell<State<'static>> {
     ::std::mem::transmute(state)
 }
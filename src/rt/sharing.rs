@@ -0,0 +1,74 @@
+use crate::rt::orderings::Site;
+
+use std::collections::{BTreeMap, HashMap};
+
+/// Tracks which threads access each synchronization object, in order to report
+/// the ones that are only ever accessed by a single thread, see
+/// `model::Builder::report_single_thread_objects`, or never accessed, see
+/// `model::Builder::report_dead_objects`.
+#[derive(Debug, Default)]
+pub(crate) struct Sharing {
+    /// When `true`, objects and their accesses are recorded.
+    record: bool,
+
+    /// Creation site and the set of threads that accessed each object of the
+    /// current execution, by index in the object store.
+    objects: HashMap<usize, (Site, u32)>,
+
+    /// For each creation site, the kind of object created there and the
+    /// largest number of threads that accessed one of these objects.
+    sites: BTreeMap<Site, (&'static str, u32)>,
+}
+
+impl Sharing {
+    /// Record the objects and their accesses.
+    pub(crate) fn record() -> Sharing {
+        Sharing {
+            record: true,
+            ..Sharing::default()
+        }
+    }
+
+    /// Forgets the objects of the current execution, keeping the results.
+    pub(crate) fn step(&mut self) {
+        self.objects.clear();
+    }
+
+    /// Returns the creation sites, with the kind of object, of the objects
+    /// that were never accessed.
+    pub(crate) fn dead(&self) -> Vec<(Site, &'static str)> {
+        self.sites
+            .iter()
+            .filter(|&(_, &(_, threads))| threads == 0)
+            .map(|(&site, &(kind, _))| (site, kind))
+            .collect()
+    }
+
+    /// Returns the creation sites, with the kind of object, of the objects
+    /// that were only ever accessed by a single thread.
+    pub(crate) fn into_single_thread(self) -> Vec<(Site, &'static str)> {
+        self.sites
+            .into_iter()
+            .filter(|&(_, (_, threads))| threads == 1)
+            .map(|(site, (kind, _))| (site, kind))
+            .collect()
+    }
+
+    pub(super) fn created(&mut self, index: usize, kind: &'static str, site: Site) {
+        if !self.record {
+            return;
+        }
+
+        self.objects.insert(index, (site, 0));
+        self.sites.entry(site).or_insert((kind, 0));
+    }
+
+    pub(super) fn accessed(&mut self, index: usize, thread: usize) {
+        if let Some((site, threads)) = self.objects.get_mut(&index) {
+            *threads |= 1 << thread;
+
+            let (_, max) = self.sites.get_mut(site).expect("[loom internal bug]");
+            *max = (*max).max(threads.count_ones());
+        }
+    }
+}
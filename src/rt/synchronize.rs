@@ -53,6 +53,12 @@ impl Synchronize {
         }
     }
 
+    /// Returns the causality acquired by loading from this synchronization
+    /// point with `Acquire` ordering.
+    pub fn happens_before(&self) -> VersionVec {
+        self.happens_before
+    }
+
     fn sync_acq(&mut self, threads: &mut thread::Set) {
         threads.active_mut().causality.join(&self.happens_before);
     }
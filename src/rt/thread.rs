@@ -1,6 +1,7 @@
 use crate::rt::execution;
 use crate::rt::object::Operation;
 use crate::rt::vv::VersionVec;
+use crate::rt::MAX_THREADS;
 
 use std::{any::Any, collections::HashMap, fmt, ops};
 
@@ -11,9 +12,13 @@ pub(crate) struct Thread {
     /// If the thread is runnable, blocked, or terminated.
     pub state: State,
 
-    /// True if the thread is in a critical section
+    /// True if the thread is in a critical section, see `atomic_batch`
     pub critical: bool,
 
+    /// Position of the branch point from which the thread ran its critical
+    /// section. Operations in the critical section are attributed to it.
+    pub critical_branch: Option<usize>,
+
     /// The operation the thread is about to take
     pub(super) operation: Option<Operation>,
 
@@ -23,6 +28,15 @@ pub(crate) struct Thread {
     /// Tracks the view of the lastest release fence
     pub released: VersionVec,
 
+    /// Versions at which the thread performed `Release` atomic stores, with
+    /// their locations. Only tracked when locations are captured.
+    pub releases: Vec<(u16, Location)>,
+
+    /// Points of other threads acquired by the `Acquire` atomic loads of the
+    /// thread, with the locations of the loads. Only tracked when locations
+    /// are captured.
+    pub acquires: Vec<(usize, u16, Location)>,
+
     /// Tracks DPOR relations
     pub dpor_vv: VersionVec,
 
@@ -32,6 +46,25 @@ pub(crate) struct Thread {
     /// Number of times the thread yielded
     pub yield_count: usize,
 
+    /// True if the thread called `spin_loop` and did not access shared state
+    /// since then.
+    pub spinning: bool,
+
+    /// Number of times the thread yielded since a thread last made progress,
+    /// see `Set::progress`.
+    pub yields_since_progress: usize,
+
+    /// Number of times `block_on` polled a future that woke itself since a
+    /// thread last made progress, see `Set::progress`.
+    pub polls_since_progress: usize,
+
+    /// True if the operations of the thread are recorded, see
+    /// `model::Builder::trace_thread`.
+    pub traced: bool,
+
+    /// True if the thread's `JoinHandle` was dropped without being joined
+    pub detached: bool,
+
     locals: LocalMap,
 
     /// `tracing` span used to associate diagnostics with the current thread.
@@ -95,12 +128,20 @@ impl Thread {
             span: tracing::info_span!(parent: parent_span.id(), "thread", id = id.id),
             state: State::Runnable { unparked: false },
             critical: false,
+            critical_branch: None,
             operation: None,
             causality: VersionVec::new(),
             released: VersionVec::new(),
+            releases: Vec::new(),
+            acquires: Vec::new(),
             dpor_vv: VersionVec::new(),
             last_yield: None,
             yield_count: 0,
+            spinning: false,
+            yields_since_progress: 0,
+            polls_since_progress: 0,
+            traced: false,
+            detached: false,
             locals: HashMap::new(),
         }
     }
@@ -174,12 +215,19 @@ impl fmt::Debug for Thread {
             .field("id", &self.id)
             .field("state", &self.state)
             .field("critical", &self.critical)
+            .field("critical_branch", &self.critical_branch)
             .field("operation", &self.operation)
             .field("causality", &self.causality)
             .field("released", &self.released)
+            .field("releases", &self.releases)
+            .field("acquires", &self.acquires)
             .field("dpor_vv", &self.dpor_vv)
             .field("last_yield", &self.last_yield)
             .field("yield_count", &self.yield_count)
+            .field("spinning", &self.spinning)
+            .field("yields_since_progress", &self.yields_since_progress)
+            .field("polls_since_progress", &self.polls_since_progress)
+            .field("traced", &self.traced)
             .field("locals", &format_args!("[..locals..]"))
             .finish()
     }
@@ -302,6 +350,51 @@ impl Set {
         self.active().causality[id]
     }
 
+    /// Records a `Release` atomic store of the active thread at `location`.
+    pub(crate) fn track_release(&mut self, location: Location) {
+        if location.is_captured() {
+            let version = self.active_atomic_version();
+            self.active_mut().releases.push((version, location));
+        }
+    }
+
+    /// Records the points of other threads acquired by the active thread at
+    /// `location`, given its causality `before` the acquire.
+    pub(crate) fn track_acquire(&mut self, before: &VersionVec, location: Location) {
+        if !location.is_captured() {
+            return;
+        }
+
+        let active = self.active.unwrap();
+        let thread = self.active_mut();
+
+        for i in 0..MAX_THREADS {
+            if i != active && thread.causality[i] > before[i] {
+                let version = thread.causality[i];
+                thread.acquires.push((i, version, location));
+            }
+        }
+    }
+
+    /// Returns the location of the `Release` store at point `version` of
+    /// `thread`, and of the `Acquire` load through which the active thread
+    /// observed it, if both were tracked.
+    pub(crate) fn sync_locations(
+        &self,
+        thread: usize,
+        version: u16,
+    ) -> Option<(Location, Location)> {
+        let released = self.threads.get(thread)?.releases.iter();
+        let (_, release) = released.rev().find(|(v, _)| *v == version)?;
+
+        let acquired = self.active().acquires.iter();
+        let (_, _, acquire) = acquired
+            .rev()
+            .find(|(t, v, _)| *t == thread && *v == version)?;
+
+        Some((*release, *acquire))
+    }
+
     pub(crate) fn unpark(&mut self, id: Id) {
         if id == self.active_id() {
             // The thread is unparking itself. We don't have to join its
@@ -359,6 +452,24 @@ impl Set {
         self.seq_cst_causality = VersionVec::new();
     }
 
+    /// Records that the active thread made progress, by modifying an atomic
+    /// or terminating, so the threads spinning may now stop.
+    pub(crate) fn progress(&mut self) {
+        for thread in &mut self.threads {
+            thread.yields_since_progress = 0;
+            thread.polls_since_progress = 0;
+        }
+    }
+
+    /// Returns `true` if every thread that can run yielded at least `yields`
+    /// times since a thread last made progress.
+    pub(crate) fn is_livelocked(&self, yields: usize) -> bool {
+        self.threads
+            .iter()
+            .filter(|th| !th.is_terminated() && !th.is_blocked())
+            .all(|th| th.yields_since_progress >= yields)
+    }
+
     pub(crate) fn iter(&self) -> impl ExactSizeIterator<Item = (Id, &Thread)> + '_ {
         let execution_id = self.execution_id;
         self.threads
@@ -432,6 +543,11 @@ impl Id {
     pub(crate) fn as_usize(self) -> usize {
         self.id
     }
+
+    /// Returns the execution in which the thread was spawned.
+    pub(crate) fn execution_id(self) -> execution::Id {
+        self.execution_id
+    }
 }
 
 impl From<Id> for usize {
@@ -38,6 +38,16 @@ impl VersionVec {
         }
     }
 
+    /// Reverts the versions equal to those of `since` to the versions of
+    /// `prev`, keeping those that advanced.
+    pub(crate) fn revert_unchanged(&mut self, since: &VersionVec, prev: &VersionVec) {
+        for (i, version) in self.versions.iter_mut().enumerate() {
+            if *version == since.versions[i] {
+                *version = prev.versions[i];
+            }
+        }
+    }
+
     /// Returns the thread ID, if any, that is ahead of the current version.
     pub(crate) fn ahead(&self, other: &VersionVec) -> Option<usize> {
         for (i, &version) in other.versions.iter().enumerate() {
@@ -71,6 +81,14 @@ impl cmp::PartialOrd for VersionVec {
     }
 }
 
+impl ops::Index<usize> for VersionVec {
+    type Output = u16;
+
+    fn index(&self, index: usize) -> &u16 {
+        self.versions.index(index)
+    }
+}
+
 impl ops::Index<thread::Id> for VersionVec {
     type Output = u16;
 
@@ -0,0 +1,133 @@
+use crate::rt::Location;
+
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Duration;
+use std::{process, thread};
+
+/// Detects loom threads blocked on a non-loom operation, see
+/// `model::Builder::blocked_thread_timeout`.
+///
+/// All loom threads run on the same OS thread, so when one of them blocks on a
+/// std primitive or on I/O, the whole model hangs. The watchdog runs on its own
+/// OS thread and aborts the process, reporting the blocked thread, when no
+/// thread switch happened for longer than the timeout. The watchdog stops when
+/// dropped.
+#[derive(Debug)]
+pub(crate) struct Watchdog {
+    shared: Arc<Shared>,
+}
+
+/// Signals the watchdog that the model is making progress.
+#[derive(Debug, Clone)]
+pub(crate) struct Heartbeat {
+    shared: Arc<Shared>,
+}
+
+#[derive(Debug)]
+struct Shared {
+    state: Mutex<State>,
+    condvar: Condvar,
+}
+
+#[derive(Debug)]
+struct State {
+    /// Incremented on each heartbeat
+    beats: u64,
+
+    /// Thread that was scheduled by the last heartbeat
+    thread: usize,
+
+    /// Location of the last loom operation of that thread
+    location: Location,
+
+    /// Set when the watchdog is dropped
+    done: bool,
+}
+
+impl Watchdog {
+    pub(crate) fn start(timeout: Duration) -> Watchdog {
+        let shared = Arc::new(Shared {
+            state: Mutex::new(State {
+                beats: 0,
+                thread: 0,
+                location: Location::disabled(),
+                done: false,
+            }),
+            condvar: Condvar::new(),
+        });
+
+        let watched = shared.clone();
+
+        thread::Builder::new()
+            .name("loom-watchdog".to_string())
+            .spawn(move || watched.watch(timeout))
+            .expect("failed to spawn the loom watchdog");
+
+        Watchdog { shared }
+    }
+
+    pub(crate) fn heartbeat(&self) -> Heartbeat {
+        Heartbeat {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl Drop for Watchdog {
+    fn drop(&mut self) {
+        // Don't panic while unwinding if a loom thread panicked holding the
+        // lock, the watchdog thread can then be left running.
+        if let Ok(mut state) = self.shared.state.lock() {
+            state.done = true;
+            self.shared.condvar.notify_one();
+        }
+    }
+}
+
+impl Heartbeat {
+    /// Records that `thread` was scheduled, resuming after its operation at
+    /// `location`.
+    pub(crate) fn beat(&self, thread: usize, location: Location) {
+        let mut state = self.shared.state.lock().unwrap();
+        state.beats += 1;
+        state.thread = thread;
+        state.location = location;
+    }
+}
+
+impl Shared {
+    fn watch(&self, timeout: Duration) {
+        let mut state = self.state.lock().unwrap();
+
+        loop {
+            let beats = state.beats;
+
+            let (next, res) = self
+                .condvar
+                .wait_timeout_while(state, timeout, |state| !state.done && state.beats == beats)
+                .unwrap();
+            state = next;
+
+            if state.done {
+                return;
+            }
+
+            if res.timed_out() {
+                let location = if state.location.is_captured() {
+                    state.location.to_string()
+                } else {
+                    "<unknown> (set `LOOM_LOCATION` to capture it)".to_string()
+                };
+
+                eprintln!(
+                    "loom: thread {} appears blocked on a non-loom operation at {} \
+                     (no thread switch for {:?}); loom threads can only block on \
+                     loom primitives",
+                    state.thread, location, timeout
+                );
+
+                process::abort();
+            }
+        }
+    }
+}
@@ -2,7 +2,7 @@ use crate::rt;
 
 use std::borrow::Borrow;
 use std::pin::Pin;
-use std::{mem, ops, ptr};
+use std::{fmt, mem, ops, ptr};
 
 /// Mock implementation of `std::sync::Arc`.
 #[derive(Debug)]
@@ -11,6 +11,12 @@ pub struct Arc<T: ?Sized> {
     value: std::sync::Arc<T>,
 }
 
+/// Mock implementation of `std::sync::Weak`.
+pub struct Weak<T: ?Sized> {
+    obj: std::sync::Arc<rt::Arc>,
+    value: std::sync::Weak<T>,
+}
+
 impl<T> Arc<T> {
     /// Constructs a new `Arc<T>`.
     #[track_caller]
@@ -20,6 +26,40 @@ impl<T> Arc<T> {
         Arc::from_std(std)
     }
 
+    /// Constructs a new `Arc<T>` while giving you a [`Weak`] to the
+    /// allocation, to allow you to construct a `T` which holds a weak pointer
+    /// to itself.
+    ///
+    /// Calling [`upgrade`](Weak::upgrade) on the weak reference inside the
+    /// closure returns `None`, as the `Arc` is not constructed yet. The weak
+    /// reference may be cloned and stored, to be upgraded once the `Arc` is
+    /// constructed.
+    #[track_caller]
+    pub fn new_cyclic<F>(data_fn: F) -> Arc<T>
+    where
+        F: FnOnce(&Weak<T>) -> T,
+    {
+        let obj = std::sync::Arc::new(rt::Arc::new_cyclic(location!()));
+
+        let value = std::sync::Arc::new_cyclic(|weak| {
+            let weak = Weak {
+                obj: obj.clone(),
+                value: weak.clone(),
+            };
+
+            data_fn(&weak)
+        });
+
+        obj.init_cyclic();
+
+        rt::execution(|e| {
+            e.arc_objs
+                .insert(std::sync::Arc::as_ptr(&value) as *const (), obj.clone());
+        });
+
+        Arc { obj, value }
+    }
+
     /// Constructs a new `Pin<Arc<T>>`.
     pub fn pin(data: T) -> Pin<Arc<T>> {
         unsafe { Pin::new_unchecked(Arc::new(data)) }
@@ -107,22 +147,40 @@ impl<T: ?Sized> Arc<T> {
         );
 
         let obj = std::sync::Arc::new(rt::Arc::new(location!()));
-        let objc = std::sync::Arc::clone(&obj);
 
-        rt::execution(|e| {
-            e.arc_objs
-                .insert(std::sync::Arc::as_ptr(&std) as *const (), objc);
-        });
-
-        Arc { obj, value: std }
+        Arc::register(obj, std)
     }
 
     /// Gets the number of strong (`Arc`) pointers to this value.
-    #[track_caller]
+    ///
+    /// The returned value is a snapshot of the reference count in the
+    /// interleaving currently being explored. Depending on the schedule, a
+    /// racing `clone` or `drop` on another thread may or may not be reflected.
+    /// Reading the count is not a branch point and does not synchronize with
+    /// other threads.
     pub fn strong_count(this: &Self) -> usize {
         this.obj.strong_count()
     }
 
+    /// Gets the number of [`Weak`] pointers to this value.
+    ///
+    /// Like [`strong_count`](Arc::strong_count), this is a schedule-dependent
+    /// snapshot and is not a branch point.
+    pub fn weak_count(this: &Self) -> usize {
+        this.obj.counts().1
+    }
+
+    /// Creates a new [`Weak`] pointer to this value.
+    #[track_caller]
+    pub fn downgrade(this: &Self) -> Weak<T> {
+        this.obj.weak_inc(location!());
+
+        Weak {
+            obj: this.obj.clone(),
+            value: std::sync::Arc::downgrade(&this.value),
+        }
+    }
+
     /// Increments the strong reference count on the `Arc<T>` associated with the
     /// provided pointer by one.
     ///
@@ -166,12 +224,69 @@ impl<T: ?Sized> Arc<T> {
         }
     }
 
+    /// Makes a mutable reference into the given `Arc`.
+    ///
+    /// If there are other `Arc` pointers to the same value, the inner value is
+    /// cloned into a new `Arc`, which replaces `this`. If only [`Weak`]
+    /// pointers remain, the value is moved into a new `Arc` instead, and the
+    /// `Weak` pointers can no longer be upgraded. Like
+    /// [`get_mut`](Arc::get_mut), the uniqueness check is a branch point, so
+    /// loom explores another thread dropping its clone right before it.
+    #[track_caller]
+    pub fn make_mut(this: &mut Self) -> &mut T
+    where
+        T: Clone,
+    {
+        match this.obj.make_mut(location!()) {
+            rt::MakeMut::Shared => {
+                // Copy-on-write: the previous value is released when `this`
+                // is overwritten.
+                *this = Arc::new(T::clone(this));
+            }
+            rt::MakeMut::Unique => {}
+            rt::MakeMut::Released => {
+                // Create the object of the new `Arc` first: nothing may panic
+                // while the value is moved out of `this`, which would drop
+                // it twice.
+                let obj = std::sync::Arc::new(rt::Arc::new(location!()));
+
+                this.unregister();
+
+                // Same as `try_unwrap`, `this` was already released.
+                unsafe {
+                    let _arc_obj = ptr::read(&this.obj);
+                    let arc_value = ptr::read(&this.value);
+
+                    let value = match std::sync::Arc::try_unwrap(arc_value) {
+                        Ok(value) => value,
+                        Err(_) => unreachable!(),
+                    };
+
+                    ptr::write(this, Arc::register(obj, std::sync::Arc::new(value)));
+                }
+            }
+        }
+
+        assert_eq!(1, std::sync::Arc::strong_count(&this.value));
+        std::sync::Arc::get_mut(&mut this.value).unwrap()
+    }
+
     /// Returns `true` if the two `Arc`s point to the same value (not
     /// just values that compare as equal).
+    ///
+    /// This only compares pointers and is not a branch point.
     pub fn ptr_eq(this: &Self, other: &Self) -> bool {
         std::sync::Arc::ptr_eq(&this.value, &other.value)
     }
 
+    /// Returns `true` if the two `Arc`s point to the same address, ignoring
+    /// any metadata of `?Sized` values such as slice lengths or vtables.
+    ///
+    /// Like [`ptr_eq`](Arc::ptr_eq), this is not a branch point.
+    pub fn addr_eq<U: ?Sized>(this: &Self, other: &Arc<U>) -> bool {
+        Self::as_ptr(this) as *const () == Arc::as_ptr(other) as *const ()
+    }
+
     /// Consumes the `Arc`, returning the wrapped pointer.
     pub fn into_raw(this: Self) -> *const T {
         let ptr = Self::as_ptr(&this);
@@ -211,6 +326,19 @@ impl<T: ?Sized> Arc<T> {
         Arc { value: inner, obj }
     }
 
+    /// Creates an `Arc` from its object and value, registering the object so
+    /// that pointers to the value can find it.
+    fn register(obj: std::sync::Arc<rt::Arc>, value: std::sync::Arc<T>) -> Arc<T> {
+        let objc = std::sync::Arc::clone(&obj);
+
+        rt::execution(|e| {
+            e.arc_objs
+                .insert(std::sync::Arc::as_ptr(&value) as *const (), objc);
+        });
+
+        Arc { obj, value }
+    }
+
     /// Unregister this object before it's gone.
     fn unregister(&self) {
         rt::execution(|e| {
@@ -221,6 +349,56 @@ impl<T: ?Sized> Arc<T> {
     }
 }
 
+impl<T: ?Sized> Weak<T> {
+    /// Attempts to upgrade the `Weak` pointer to an [`Arc`], returning `None`
+    /// if the value has since been dropped.
+    ///
+    /// This is a branch point racing with dropping the last `Arc`, so loom
+    /// explores both outcomes when they are concurrent.
+    #[track_caller]
+    pub fn upgrade(&self) -> Option<Arc<T>> {
+        if !self.obj.upgrade(location!()) {
+            return None;
+        }
+
+        let value = self
+            .value
+            .upgrade()
+            .expect("value was dropped while an `Arc` remains");
+
+        Some(Arc {
+            obj: self.obj.clone(),
+            value,
+        })
+    }
+
+    /// Gets the number of strong (`Arc`) pointers to this value, zero if it
+    /// was dropped.
+    ///
+    /// Like [`Arc::strong_count`], this is a schedule-dependent snapshot and is
+    /// not a branch point.
+    pub fn strong_count(&self) -> usize {
+        self.obj.counts().0
+    }
+
+    /// Gets the number of `Weak` pointers to this value, zero if there are
+    /// no remaining strong pointers.
+    ///
+    /// Like [`Arc::weak_count`], this is a schedule-dependent snapshot and is
+    /// not a branch point.
+    pub fn weak_count(&self) -> usize {
+        match self.obj.counts() {
+            (0, _) => 0,
+            (_, weak) => weak,
+        }
+    }
+
+    /// Returns `true` if the two `Weak`s point to the same allocation.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        self.value.ptr_eq(&other.value)
+    }
+}
+
 impl<T: ?Sized> ops::Deref for Arc<T> {
     type Target = T;
 
@@ -255,6 +433,31 @@ impl<T: ?Sized> Drop for Arc<T> {
     }
 }
 
+impl<T: ?Sized> Clone for Weak<T> {
+    #[track_caller]
+    fn clone(&self) -> Weak<T> {
+        self.obj.weak_inc(location!());
+
+        Weak {
+            obj: self.obj.clone(),
+            value: self.value.clone(),
+        }
+    }
+}
+
+impl<T: ?Sized> Drop for Weak<T> {
+    #[track_caller]
+    fn drop(&mut self) {
+        self.obj.weak_dec(location!());
+    }
+}
+
+impl<T: ?Sized> fmt::Debug for Weak<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "(Weak)")
+    }
+}
+
 impl<T: Default> Default for Arc<T> {
     #[track_caller]
     fn default() -> Arc<T> {
@@ -269,6 +472,20 @@ impl<T> From<T> for Arc<T> {
     }
 }
 
+impl<T> From<Vec<T>> for Arc<[T]> {
+    #[track_caller]
+    fn from(v: Vec<T>) -> Self {
+        Arc::from_std(std::sync::Arc::from(v))
+    }
+}
+
+impl<T> FromIterator<T> for Arc<[T]> {
+    #[track_caller]
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Arc::from_std(iter.into_iter().collect())
+    }
+}
+
 impl<T: ?Sized> AsRef<T> for Arc<T> {
     fn as_ref(&self) -> &T {
         self
@@ -1,8 +1,9 @@
 use crate::rt;
+use crate::rt::orderings::Kind;
 
+use std::fmt;
 use std::sync::atomic::Ordering;
 
-#[derive(Debug)]
 pub(crate) struct Atomic<T> {
     /// Atomic object
     state: rt::Atomic<T>,
@@ -12,30 +13,69 @@ impl<T> Atomic<T>
 where
     T: rt::Numeric,
 {
+    #[track_caller]
     pub(crate) fn new(value: T, location: rt::Location) -> Atomic<T> {
-        let state = rt::Atomic::new(value, location);
+        let state = rt::Atomic::new(value, location, std::panic::Location::caller());
+        state.track_sharing();
 
         Atomic { state }
     }
 
     #[track_caller]
     pub(crate) unsafe fn unsync_load(&self) -> T {
-        self.state.unsync_load(location!())
+        self.state.unsync_load(self.location())
     }
 
     #[track_caller]
     pub(crate) fn load(&self, order: Ordering) -> T {
-        self.state.load(location!(), order)
+        check_load_ordering(order);
+
+        let (order, _) = orderings(Kind::Load, order, order);
+        self.state.load(self.location(), order)
+    }
+
+    #[track_caller]
+    pub(crate) fn load_consume<R>(&self, f: impl FnOnce(T) -> R) -> R {
+        self.state.load_consume(self.location(), f)
     }
 
     #[track_caller]
     pub(crate) fn store(&self, value: T, order: Ordering) {
-        self.state.store(location!(), value, order)
+        check_store_ordering(order);
+
+        let (order, _) = orderings(Kind::Store, order, order);
+        self.state.store(self.location(), value, order)
+    }
+
+    pub(crate) fn track_locations(&self) {
+        self.state.track_locations()
+    }
+
+    pub(crate) fn assert_monotonic(&self)
+    where
+        T: Ord + fmt::Debug,
+    {
+        self.state.assert_monotonic()
+    }
+
+    pub(crate) fn is_visible_to_all(&self, value: T) -> bool {
+        self.state.is_visible_to_all(value)
+    }
+
+    pub(crate) fn modification_count(&self, thread: crate::thread::ThreadId) -> usize {
+        self.state.modification_count(thread.rt_id())
     }
 
     #[track_caller]
     pub(crate) fn with_mut<R>(&mut self, f: impl FnOnce(&mut T) -> R) -> R {
-        self.state.with_mut(location!(), f)
+        self.state.with_mut(self.location(), f)
+    }
+
+    /// Location of the caller, captured if tracking is enabled for the
+    /// execution or for this cell.
+    #[track_caller]
+    fn location(&self) -> rt::Location {
+        self.state.location(std::panic::Location::caller())
     }
 
     /// Read-modify-write
@@ -54,7 +94,33 @@ where
     where
         F: FnOnce(T) -> Result<T, E>,
     {
-        self.state.rmw(location!(), success, failure, f)
+        let (success, failure) = orderings(Kind::Rmw, success, failure);
+        self.state.rmw(self.location(), success, failure, f)
+    }
+
+    /// Like `try_rmw`, but the update may fail spuriously.
+    #[track_caller]
+    fn try_rmw_weak<F>(&self, success: Ordering, failure: Ordering, f: F) -> Result<T, T>
+    where
+        F: FnOnce(T) -> Result<T, T>,
+    {
+        let (success, failure) = orderings(Kind::Rmw, success, failure);
+        self.state.rmw_weak(self.location(), success, failure, f)
+    }
+
+    /// Records a decrement to zero by `fetch_sub` without `Release` ordering,
+    /// see `model::Builder::refcount_lint`.
+    #[track_caller]
+    pub(crate) fn decremented_to_zero(&self, order: Ordering) {
+        if let Ordering::Relaxed | Ordering::Acquire = order {
+            let site = std::panic::Location::caller();
+
+            rt::execution(|execution| {
+                if let Some(sites) = &mut execution.unreleased_decrements {
+                    sites.insert(site);
+                }
+            });
+        }
     }
 
     #[track_caller]
@@ -86,6 +152,8 @@ where
         success: Ordering,
         failure: Ordering,
     ) -> Result<T, T> {
+        check_failure_ordering(failure);
+
         self.try_rmw(success, failure, |actual| {
             if actual == current {
                 Ok(new)
@@ -95,6 +163,34 @@ where
         })
     }
 
+    /// Like `compare_exchange`, but loom also explores the exchange failing
+    /// spuriously when the comparison succeeds.
+    #[track_caller]
+    pub(crate) fn compare_exchange_weak(
+        &self,
+        current: T,
+        new: T,
+        success: Ordering,
+        failure: Ordering,
+    ) -> Result<T, T> {
+        check_failure_ordering(failure);
+
+        self.try_rmw_weak(success, failure, |actual| {
+            if actual == current {
+                Ok(new)
+            } else {
+                Err(actual)
+            }
+        })
+    }
+
+    /// Loads the value, then tries to update it with `compare_exchange` until
+    /// it succeeds or `f` returns `None`.
+    ///
+    /// Each attempt may read a stale value, calling `f` again with the value
+    /// read, so these are explored. Unlike the `compare_exchange_weak` loop
+    /// of `std`, an attempt never fails spuriously, which avoids exploring
+    /// the spurious failures of every `fetch_update`.
     #[track_caller]
     pub(crate) fn fetch_update<F>(
         &self,
@@ -105,13 +201,94 @@ where
     where
         F: FnMut(T) -> Option<T>,
     {
+        check_failure_ordering(fetch_order);
+
         let mut prev = self.load(fetch_order);
-        while let Some(next) = f(prev) {
+
+        while let Some(next) = self.call_update(&mut f, prev) {
             match self.compare_exchange(prev, next, set_order, fetch_order) {
                 Ok(x) => return Ok(x),
                 Err(next_prev) => prev = next_prev,
             }
         }
+
         Err(prev)
     }
+
+    /// Calls the function passed to `fetch_update`, calling it twice to record
+    /// the caller if it is not pure, see `model::Builder::check_update_purity`.
+    #[track_caller]
+    fn call_update<F>(&self, f: &mut F, prev: T) -> Option<T>
+    where
+        F: FnMut(T) -> Option<T>,
+    {
+        let next = f(prev);
+
+        let checked = rt::execution(|execution| execution.impure_updates.is_some());
+
+        if checked && f(prev) != next {
+            let site = std::panic::Location::caller();
+
+            rt::execution(|execution| {
+                if let Some(sites) = &mut execution.impure_updates {
+                    sites.insert(site);
+                }
+            });
+        }
+
+        next
+    }
+}
+
+/// Formats the current value, like the `std` atomics.
+impl<T> fmt::Debug for Atomic<T>
+where
+    T: rt::Numeric + fmt::Debug,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.state.debug_value() {
+            Some(value) => value.fmt(fmt),
+            None => fmt.debug_struct("Atomic").finish_non_exhaustive(),
+        }
+    }
+}
+
+/// Orderings to use for an operation at the caller's location, see
+/// `model::Builder::suggest_orderings`.
+#[track_caller]
+fn orderings(kind: Kind, success: Ordering, failure: Ordering) -> (Ordering, Ordering) {
+    let site = std::panic::Location::caller();
+    rt::execution(|execution| execution.orderings.apply(site, kind, success, failure))
+}
+
+/// Same validation as `std`
+#[track_caller]
+fn check_load_ordering(order: Ordering) {
+    match order {
+        Ordering::Release => panic!("there is no such thing as a release load"),
+        Ordering::AcqRel => panic!("there is no such thing as an acquire-release load"),
+        _ => {}
+    }
+}
+
+/// Same validation as `std`
+#[track_caller]
+fn check_store_ordering(order: Ordering) {
+    match order {
+        Ordering::Acquire => panic!("there is no such thing as an acquire store"),
+        Ordering::AcqRel => panic!("there is no such thing as an acquire-release store"),
+        _ => {}
+    }
+}
+
+/// Same validation as `std`
+#[track_caller]
+fn check_failure_ordering(failure: Ordering) {
+    match failure {
+        Ordering::Release => panic!("there is no such thing as a release failure ordering"),
+        Ordering::AcqRel => {
+            panic!("there is no such thing as an acquire-release failure ordering")
+        }
+        _ => {}
+    }
 }
@@ -5,10 +5,17 @@ use std::sync::atomic::Ordering;
 /// Mock implementation of `std::sync::atomic::AtomicBool`.
 ///
 /// NOTE: Unlike `std::sync::atomic::AtomicBool`, this type has a different
-/// in-memory representation than `bool`.
-#[derive(Debug)]
+/// in-memory representation than `bool`. A pointer to it is not a valid pointer
+/// to an atomic value and must not be passed through FFI, see
+/// [Pointers to atomics](crate::sync::atomic#pointers-to-atomics).
 pub struct AtomicBool(Atomic<bool>);
 
+impl std::fmt::Debug for AtomicBool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
 impl AtomicBool {
     /// Creates a new instance of `AtomicBool`.
     #[track_caller]
@@ -16,6 +23,15 @@ impl AtomicBool {
         AtomicBool(Atomic::new(v, location!()))
     }
 
+    /// Captures locations for accesses to this atomic, even when location
+    /// tracking is disabled for the model.
+    ///
+    /// See [`AtomicUsize::track_locations`](super::AtomicUsize::track_locations).
+    pub fn track_locations(self) -> Self {
+        self.0.track_locations();
+        self
+    }
+
     /// Load the value without any synchronization.
     ///
     /// # Safety
@@ -74,6 +90,10 @@ impl AtomicBool {
     }
 
     /// Stores a value into the atomic if the current value is the same as the current value.
+    ///
+    /// Unlike `compare_exchange`, this function may fail even when the
+    /// comparison succeeds. Loom explores such a spurious failure once per
+    /// atomic and execution.
     #[track_caller]
     pub fn compare_exchange_weak(
         &self,
@@ -82,7 +102,7 @@ impl AtomicBool {
         success: Ordering,
         failure: Ordering,
     ) -> Result<bool, bool> {
-        self.compare_exchange(current, new, success, failure)
+        self.0.compare_exchange_weak(current, new, success, failure)
     }
 
     /// Logical "and" with the current value.
@@ -109,9 +129,18 @@ impl AtomicBool {
         self.0.rmw(|v| v ^ val, order)
     }
 
+    /// Logical "not" with the current value, flipping it.
+    #[track_caller]
+    pub fn fetch_not(&self, order: Ordering) -> bool {
+        self.0.rmw(|v| !v, order)
+    }
+
     /// Fetches the value, and applies a function to it that returns an optional new value. Returns
     /// a [`Result`] of [`Ok`]`(previous_value)` if the function returned [`Some`]`(_)`, else
     /// [`Err`]`(previous_value)`.
+    ///
+    /// Checked as a load followed by a `compare_exchange` loop, see
+    /// [`AtomicUsize::fetch_update`](super::AtomicUsize::fetch_update).
     #[track_caller]
     pub fn fetch_update<F>(
         &self,
@@ -127,12 +156,14 @@ impl AtomicBool {
 }
 
 impl Default for AtomicBool {
+    #[track_caller]
     fn default() -> AtomicBool {
         AtomicBool::new(Default::default())
     }
 }
 
 impl From<bool> for AtomicBool {
+    #[track_caller]
     fn from(b: bool) -> Self {
         Self::new(b)
     }
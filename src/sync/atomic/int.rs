@@ -9,11 +9,28 @@ macro_rules! atomic_int {
             " Mock implementation of `std::sync::atomic::", stringify!($name), "`.\n\n\
              NOTE: Unlike `std::sync::atomic::", stringify!($name), "`, \
              this type has a different in-memory representation than `",
-             stringify!($int_type), "`.",
+             stringify!($int_type), "`. A pointer to it is not a valid pointer \
+             to an atomic value and must not be passed through FFI, see \
+             [Pointers to atomics](crate::sync::atomic#pointers-to-atomics).",
         )]
-        #[derive(Debug)]
         pub struct $name(Atomic<$int_type>);
 
+        impl crate::debug::Atomic for $name {}
+
+        impl crate::debug::sealed::Sealed for $name {
+            type Value = $int_type;
+
+            fn is_visible_to_all(&self, value: $int_type) -> bool {
+                self.0.is_visible_to_all(value)
+            }
+        }
+
+        impl std::fmt::Debug for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+
         impl $name {
             #[doc = concat!(" Creates a new instance of `", stringify!($name), "`.")]
             #[track_caller]
@@ -21,6 +38,30 @@ macro_rules! atomic_int {
                 Self(Atomic::new(v, location!()))
             }
 
+            /// Captures locations for accesses to this atomic, even when
+            /// location tracking is disabled for the model.
+            ///
+            /// Tracking locations for every operation with `LOOM_LOCATION` is
+            /// expensive. This enables it for just the atomic being debugged,
+            /// so that causality violations involving it report where each
+            /// conflicting access happened.
+            pub fn track_locations(self) -> Self {
+                self.0.track_locations();
+                self
+            }
+
+            /// Asserts that the value of this atomic never decreases, e.g. for a
+            /// monotonic counter or a logical clock.
+            ///
+            /// Any later store or read-modify-write operation that stores a
+            /// value smaller than the one it follows in modification order
+            /// panics, reporting where the offending operation happened. This
+            /// catches stale values winding the counter backward.
+            pub fn assert_monotonic(self) -> Self {
+                self.0.assert_monotonic();
+                self
+            }
+
             /// Get access to a mutable reference to the inner value.
             #[track_caller]
             pub fn with_mut<R>(&mut self, f: impl FnOnce(&mut $int_type) -> R) -> R {
@@ -40,6 +81,16 @@ macro_rules! atomic_int {
                 self.0.unsync_load()
             }
 
+            /// Returns the number of stores and successful read-modify-write
+            /// operations performed by `thread` on this atomic in the current
+            /// iteration.
+            ///
+            /// This is not a branch point. It can be used to assert how
+            /// operations are distributed between threads, e.g. for fairness.
+            pub fn modification_count(&self, thread: crate::thread::ThreadId) -> usize {
+                self.0.modification_count(thread)
+            }
+
             /// Consumes the atomic and returns the contained value.
             #[track_caller]
             pub fn into_inner(self) -> $int_type {
@@ -90,6 +141,10 @@ macro_rules! atomic_int {
             }
 
             /// Stores a value into the atomic if the current value is the same as the current value.
+            ///
+            /// Unlike `compare_exchange`, this function may fail even when the
+            /// comparison succeeds. Loom explores such a spurious failure once per
+            /// atomic and execution.
             #[track_caller]
             pub fn compare_exchange_weak(
                 &self,
@@ -98,19 +153,29 @@ macro_rules! atomic_int {
                 success: Ordering,
                 failure: Ordering,
             ) -> Result<$int_type, $int_type> {
-                self.compare_exchange(current, new, success, failure)
+                self.0.compare_exchange_weak(current, new, success, failure)
             }
 
             /// Adds to the current value, returning the previous value.
+            ///
+            /// This operation wraps around on overflow.
             #[track_caller]
             pub fn fetch_add(&self, val: $int_type, order: Ordering) -> $int_type {
                 self.0.rmw(|v| v.wrapping_add(val), order)
             }
 
             /// Subtracts from the current value, returning the previous value.
+            ///
+            /// This operation wraps around on overflow.
             #[track_caller]
             pub fn fetch_sub(&self, val: $int_type, order: Ordering) -> $int_type {
-                self.0.rmw(|v| v.wrapping_sub(val), order)
+                let prev = self.0.rmw(|v| v.wrapping_sub(val), order);
+
+                if prev == val {
+                    self.0.decremented_to_zero(order);
+                }
+
+                prev
             }
 
             /// Bitwise "and" with the current value.
@@ -152,6 +217,21 @@ macro_rules! atomic_int {
             /// Fetches the value, and applies a function to it that returns an optional new value.
             /// Returns a [`Result`] of [`Ok`]`(previous_value)` if the function returned
             /// [`Some`]`(_)`, else [`Err`]`(previous_value)`.
+            ///
+            /// Loom checks this as a load followed by a `compare_exchange`
+            /// loop: the load may read a stale value, and each
+            /// compare-exchange fails if the value changed, in which case the
+            /// function is called again with the value read. Both are
+            /// explored. Unlike the `compare_exchange_weak` loop of `std`,
+            /// `fetch_update` never fails spuriously, so it explores fewer
+            /// interleavings than the equivalent hand-written weak loop.
+            ///
+            /// The function must be pure: loom reruns the model for every
+            /// permutation, so side effects, such as incrementing a counter
+            /// shared with the rest of the model, make the runs
+            /// nondeterministic. See
+            /// [`Builder::check_update_purity`](crate::model::Builder::check_update_purity)
+            /// to detect functions that are not.
             #[track_caller]
             pub fn fetch_update<F>(
                 &self,
@@ -167,12 +247,14 @@ macro_rules! atomic_int {
         }
 
         impl Default for $name {
+            #[track_caller]
             fn default() -> Self {
                 Self::new(Default::default())
             }
         }
 
         impl From<$int_type> for $name {
+            #[track_caller]
             fn from(v: $int_type) -> Self {
                 Self::new(v)
             }
@@ -190,8 +272,5 @@ atomic_int!(AtomicI16, i16);
 atomic_int!(AtomicI32, i32);
 atomic_int!(AtomicIsize, isize);
 
-#[cfg(target_has_atomic = "64")]
 atomic_int!(AtomicU64, u64);
-
-#[cfg(target_has_atomic = "64")]
 atomic_int!(AtomicI64, i64);
@@ -1,4 +1,75 @@
 //! Mock implementation of `std::sync::atomic`.
+//!
+//! # Pointers to atomics
+//!
+//! Loom atomics track the history of stores made to them, so their in-memory
+//! representation differs from the native types. A pointer to a loom atomic,
+//! e.g. `&atomic as *const _`, is **not** a valid pointer to an atomic value:
+//! it must not be passed to C through FFI, nor cast to a pointer to the value
+//! or to a `std` atomic. For the same reason, the `as_ptr` and `from_ptr`
+//! methods of the `std` atomics are not provided. Code that needs the address
+//! of an atomic should be excluded from the model with `#[cfg(not(loom))]`.
+//!
+//! # 64-bit atomics
+//!
+//! Loom models every atomic with a `u64`, so [`AtomicU64`] and [`AtomicI64`]
+//! are provided on all targets, including those without native 64-bit
+//! atomics, such as most 32-bit targets. However, `std` only provides these
+//! types on targets with `target_has_atomic = "64"`: when compiling *without*
+//! loom for such a target, the code under test must provide its own 64-bit
+//! atomic, e.g. one backed by a mutex.
+//!
+//! # Examples
+//!
+//! Using an atomic as a set of flags, where each bit guards its own data. A
+//! `Release` read-modify-write that sets a bit synchronizes with an `Acquire`
+//! load that observes it, even if other bits were set in between.
+//!
+//! ```
+//! use loom::cell::UnsafeCell;
+//! use loom::sync::atomic::AtomicUsize;
+//! use loom::sync::Arc;
+//! use loom::thread;
+//!
+//! use std::sync::atomic::Ordering::{Acquire, Release};
+//!
+//! const READY: usize = 0b01;
+//! const OTHER: usize = 0b10;
+//!
+//! struct Shared {
+//!     flags: AtomicUsize,
+//!     data: UnsafeCell<usize>,
+//! }
+//!
+//! loom::model(|| {
+//!     let shared = Arc::new(Shared {
+//!         flags: AtomicUsize::new(0),
+//!         data: UnsafeCell::new(0),
+//!     });
+//!
+//!     let producer = {
+//!         let shared = shared.clone();
+//!         thread::spawn(move || {
+//!             shared.data.with_mut(|v| unsafe { *v = 42 });
+//!             shared.flags.fetch_or(READY, Release);
+//!         })
+//!     };
+//!
+//!     let other = {
+//!         let shared = shared.clone();
+//!         thread::spawn(move || {
+//!             shared.flags.fetch_or(OTHER, Release);
+//!         })
+//!     };
+//!
+//!     if shared.flags.load(Acquire) & READY != 0 {
+//!         shared.data.with(|v| assert_eq!(42, unsafe { *v }));
+//!     }
+//!
+//!     producer.join().unwrap();
+//!     other.join().unwrap();
+//! });
+//! ```
 
 #[allow(clippy::module_inception)]
 mod atomic;
@@ -9,10 +80,11 @@ pub use self::bool::AtomicBool;
 
 mod int;
 pub use self::int::{AtomicI16, AtomicI32, AtomicI8, AtomicIsize};
+pub use self::int::{AtomicI64, AtomicU64};
 pub use self::int::{AtomicU16, AtomicU32, AtomicU8, AtomicUsize};
 
-#[cfg(target_has_atomic = "64")]
-pub use self::int::{AtomicI64, AtomicU64};
+mod pair;
+pub use self::pair::AtomicU64Pair;
 
 mod ptr;
 pub use self::ptr::AtomicPtr;
@@ -22,17 +94,26 @@ pub use std::sync::atomic::Ordering;
 
 /// Signals the processor that it is entering a busy-wait spin-loop.
 ///
-/// For loom, this is an alias of [`yield_now`] but is provided as a reflection
-/// of the deprecated [`core::sync::atomic::spin_loop_hint`] function. See the
-/// [`yield_now`] documentation for more information on what effect using this
-/// has on loom.
+/// For loom, this is like [`yield_now`] but is provided as a reflection of the
+/// deprecated [`core::sync::atomic::spin_loop_hint`] function. See
+/// [`hint::spin_loop`] for how it differs from [`yield_now`].
 ///
 /// [`yield_now`]: crate::thread::yield_now
+/// [`hint::spin_loop`]: crate::hint::spin_loop
 pub fn spin_loop_hint() {
-    crate::thread::yield_now();
+    crate::rt::spin_loop();
 }
 
 /// An atomic fence.
+///
+/// # Panics
+///
+/// Panics if `order` is [`Relaxed`](Ordering::Relaxed), like `std`.
+#[track_caller]
 pub fn fence(order: Ordering) {
+    if let Ordering::Relaxed = order {
+        panic!("there is no such thing as a relaxed fence");
+    }
+
     crate::rt::fence(order);
 }
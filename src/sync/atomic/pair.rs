@@ -0,0 +1,98 @@
+use super::AtomicU64;
+
+use std::sync::atomic::Ordering;
+
+/// Two `u32` values packed into a single [`AtomicU64`].
+///
+/// The low and high halves are updated independently using read-modify-write
+/// operations on the whole word, so a concurrent update to one half can never
+/// be lost by an update to the other. Code that instead loads the word and
+/// stores it back with one half modified will be caught by loom, as the store
+/// may overwrite a racing update.
+#[derive(Debug, Default)]
+pub struct AtomicU64Pair(AtomicU64);
+
+impl AtomicU64Pair {
+    /// Creates a new pair from its low and high halves.
+    #[track_caller]
+    pub fn new(lo: u32, hi: u32) -> AtomicU64Pair {
+        AtomicU64Pair(AtomicU64::new(join(lo, hi)))
+    }
+
+    /// Consumes the pair and returns its low and high halves.
+    #[track_caller]
+    pub fn into_inner(self) -> (u32, u32) {
+        split(self.0.into_inner())
+    }
+
+    /// Loads both halves, returning `(lo, hi)`.
+    #[track_caller]
+    pub fn load(&self, order: Ordering) -> (u32, u32) {
+        split(self.0.load(order))
+    }
+
+    /// Stores both halves.
+    #[track_caller]
+    pub fn store(&self, lo: u32, hi: u32, order: Ordering) {
+        self.0.store(join(lo, hi), order)
+    }
+
+    /// Applies `f` to the low half, leaving the high half untouched.
+    ///
+    /// Returns `Ok(previous_lo)` if `f` returned `Some(_)`, else
+    /// `Err(previous_lo)`, like [`AtomicU64::fetch_update`].
+    #[track_caller]
+    pub fn fetch_update_lo<F>(
+        &self,
+        set_order: Ordering,
+        fetch_order: Ordering,
+        mut f: F,
+    ) -> Result<u32, u32>
+    where
+        F: FnMut(u32) -> Option<u32>,
+    {
+        self.0
+            .fetch_update(set_order, fetch_order, |v| {
+                let (lo, hi) = split(v);
+                f(lo).map(|lo| join(lo, hi))
+            })
+            .map(|v| split(v).0)
+            .map_err(|v| split(v).0)
+    }
+
+    /// Applies `f` to the high half, leaving the low half untouched.
+    ///
+    /// Returns `Ok(previous_hi)` if `f` returned `Some(_)`, else
+    /// `Err(previous_hi)`, like [`AtomicU64::fetch_update`].
+    #[track_caller]
+    pub fn fetch_update_hi<F>(
+        &self,
+        set_order: Ordering,
+        fetch_order: Ordering,
+        mut f: F,
+    ) -> Result<u32, u32>
+    where
+        F: FnMut(u32) -> Option<u32>,
+    {
+        self.0
+            .fetch_update(set_order, fetch_order, |v| {
+                let (lo, hi) = split(v);
+                f(hi).map(|hi| join(lo, hi))
+            })
+            .map(|v| split(v).1)
+            .map_err(|v| split(v).1)
+    }
+
+    /// Returns the underlying [`AtomicU64`].
+    pub fn as_u64(&self) -> &AtomicU64 {
+        &self.0
+    }
+}
+
+fn join(lo: u32, hi: u32) -> u64 {
+    (hi as u64) << 32 | lo as u64
+}
+
+fn split(v: u64) -> (u32, u32) {
+    (v as u32, (v >> 32) as u32)
+}
@@ -5,7 +5,9 @@ use std::sync::atomic::Ordering;
 /// Mock implementation of `std::sync::atomic::AtomicPtr`.
 ///
 /// NOTE: Unlike `std::sync::atomic::AtomicPtr`, this type has a different
-/// in-memory representation than `*mut T`.
+/// in-memory representation than `*mut T`. A pointer to it is not a valid pointer
+/// to an atomic value and must not be passed through FFI, see
+/// [Pointers to atomics](crate::sync::atomic#pointers-to-atomics).
 pub struct AtomicPtr<T>(Atomic<*mut T>);
 
 impl<T> std::fmt::Debug for AtomicPtr<T> {
@@ -21,6 +23,15 @@ impl<T> AtomicPtr<T> {
         AtomicPtr(Atomic::new(v, location!()))
     }
 
+    /// Captures locations for accesses to this atomic, even when location
+    /// tracking is disabled for the model.
+    ///
+    /// See [`AtomicUsize::track_locations`](super::AtomicUsize::track_locations).
+    pub fn track_locations(self) -> Self {
+        self.0.track_locations();
+        self
+    }
+
     /// Load the value without any synchronization.
     ///
     /// # Safety
@@ -53,6 +64,56 @@ impl<T> AtomicPtr<T> {
         self.0.load(order)
     }
 
+    /// Loads a value from the pointer with `consume` ordering, calling `f`
+    /// with it.
+    ///
+    /// Rust has no `consume` ordering: it is usually approximated by relying
+    /// on the data dependency of the accesses through the loaded pointer,
+    /// which is weaker than `Acquire`. This models it, experimentally: the
+    /// accesses performed by `f`, assumed to depend on the pointer, happen
+    /// after the store it was read from, while the accesses following the
+    /// call are only ordered as if the load was `Relaxed`. Accessing data
+    /// that is not reachable through the pointer after the call, assuming it
+    /// was published with it, is reported as a causality violation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use loom::cell::UnsafeCell;
+    /// use loom::sync::atomic::AtomicPtr;
+    /// use loom::sync::Arc;
+    /// use loom::thread;
+    ///
+    /// use std::ptr;
+    /// use std::sync::atomic::Ordering::Release;
+    ///
+    /// loom::model(|| {
+    ///     let data = Arc::new(UnsafeCell::new(0usize));
+    ///     let ptr = Arc::new(AtomicPtr::<UnsafeCell<usize>>::new(ptr::null_mut()));
+    ///
+    ///     let th = {
+    ///         let (data, ptr) = (data.clone(), ptr.clone());
+    ///         thread::spawn(move || {
+    ///             data.with_mut(|v| unsafe { *v = 1 });
+    ///             ptr.store(Arc::as_ptr(&data) as *mut _, Release);
+    ///         })
+    ///     };
+    ///
+    ///     ptr.load_consume(|p| {
+    ///         // Reading the data through the pointer is ordered
+    ///         if let Some(data) = unsafe { p.as_ref() } {
+    ///             assert_eq!(1, data.with(|v| unsafe { *v }));
+    ///         }
+    ///     });
+    ///
+    ///     th.join().unwrap();
+    /// });
+    /// ```
+    #[track_caller]
+    pub fn load_consume<R>(&self, f: impl FnOnce(*mut T) -> R) -> R {
+        self.0.load_consume(f)
+    }
+
     /// Stores a value into the pointer.
     #[track_caller]
     pub fn store(&self, val: *mut T, order: Ordering) {
@@ -84,6 +145,10 @@ impl<T> AtomicPtr<T> {
     }
 
     /// Stores a value into the atomic if the current value is the same as the current value.
+    ///
+    /// Unlike `compare_exchange`, this function may fail even when the
+    /// comparison succeeds. Loom explores such a spurious failure once per
+    /// atomic and execution.
     #[track_caller]
     pub fn compare_exchange_weak(
         &self,
@@ -92,12 +157,15 @@ impl<T> AtomicPtr<T> {
         success: Ordering,
         failure: Ordering,
     ) -> Result<*mut T, *mut T> {
-        self.compare_exchange(current, new, success, failure)
+        self.0.compare_exchange_weak(current, new, success, failure)
     }
 
     /// Fetches the value, and applies a function to it that returns an optional new value. Returns
     /// a [`Result`] of [`Ok`]`(previous_value)` if the function returned [`Some`]`(_)`, else
     /// [`Err`]`(previous_value)`.
+    ///
+    /// Checked as a load followed by a `compare_exchange` loop, see
+    /// [`AtomicUsize::fetch_update`](super::AtomicUsize::fetch_update).
     #[track_caller]
     pub fn fetch_update<F>(
         &self,
@@ -110,9 +178,39 @@ impl<T> AtomicPtr<T> {
     {
         self.0.fetch_update(set_order, fetch_order, f)
     }
+
+    /// Offsets the pointer by `val` elements of `T`, returning the previous
+    /// pointer.
+    #[track_caller]
+    pub fn fetch_ptr_add(&self, val: usize, order: Ordering) -> *mut T {
+        self.0.rmw(|p| p.wrapping_add(val), order)
+    }
+
+    /// Offsets the pointer back by `val` elements of `T`, returning the
+    /// previous pointer.
+    #[track_caller]
+    pub fn fetch_ptr_sub(&self, val: usize, order: Ordering) -> *mut T {
+        self.0.rmw(|p| p.wrapping_sub(val), order)
+    }
+
+    /// Offsets the pointer by `val` bytes, returning the previous pointer.
+    #[track_caller]
+    pub fn fetch_byte_add(&self, val: usize, order: Ordering) -> *mut T {
+        self.0
+            .rmw(|p| p.cast::<u8>().wrapping_add(val).cast(), order)
+    }
+
+    /// Offsets the pointer back by `val` bytes, returning the previous
+    /// pointer.
+    #[track_caller]
+    pub fn fetch_byte_sub(&self, val: usize, order: Ordering) -> *mut T {
+        self.0
+            .rmw(|p| p.cast::<u8>().wrapping_sub(val).cast(), order)
+    }
 }
 
 impl<T> Default for AtomicPtr<T> {
+    #[track_caller]
     fn default() -> AtomicPtr<T> {
         use std::ptr;
         AtomicPtr::new(ptr::null_mut())
@@ -120,6 +218,7 @@ impl<T> Default for AtomicPtr<T> {
 }
 
 impl<T> From<*mut T> for AtomicPtr<T> {
+    #[track_caller]
     fn from(p: *mut T) -> Self {
         Self::new(p)
     }
@@ -0,0 +1,80 @@
+use crate::sync::atomic::AtomicUsize;
+
+use std::sync::atomic::Ordering;
+
+/// An `Option<usize>` stored in an [`AtomicUsize`].
+///
+/// `None` is represented as `0` and `Some(n)` as `n + 1`, so `usize::MAX`
+/// cannot be stored. Taking the value is done with a single `swap`, so when
+/// multiple threads race to [`take`] it, only one of them gets `Some`.
+///
+/// [`take`]: AtomicOptionUsize::take
+#[derive(Debug, Default)]
+pub struct AtomicOptionUsize(AtomicUsize);
+
+impl AtomicOptionUsize {
+    /// Creates a new `AtomicOptionUsize`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` is `Some(usize::MAX)`.
+    #[track_caller]
+    pub fn new(value: Option<usize>) -> AtomicOptionUsize {
+        AtomicOptionUsize(AtomicUsize::new(encode(value)))
+    }
+
+    /// Consumes the atomic and returns the contained value.
+    #[track_caller]
+    pub fn into_inner(self) -> Option<usize> {
+        decode(self.0.into_inner())
+    }
+
+    /// Loads the value.
+    #[track_caller]
+    pub fn load(&self, order: Ordering) -> Option<usize> {
+        decode(self.0.load(order))
+    }
+
+    /// Stores a value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` is `Some(usize::MAX)`.
+    #[track_caller]
+    pub fn store(&self, value: Option<usize>, order: Ordering) {
+        self.0.store(encode(value), order)
+    }
+
+    /// Takes the value out, leaving `None` in its place.
+    #[track_caller]
+    pub fn take(&self, order: Ordering) -> Option<usize> {
+        decode(self.0.swap(0, order))
+    }
+
+    /// Replaces the value, returning the previous one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` is `Some(usize::MAX)`.
+    #[track_caller]
+    pub fn replace(&self, value: Option<usize>, order: Ordering) -> Option<usize> {
+        decode(self.0.swap(encode(value), order))
+    }
+}
+
+impl From<Option<usize>> for AtomicOptionUsize {
+    fn from(value: Option<usize>) -> Self {
+        Self::new(value)
+    }
+}
+
+pub(super) fn encode(value: Option<usize>) -> usize {
+    match value {
+        Some(n) => n.checked_add(1).expect("`usize::MAX` cannot be stored"),
+        None => 0,
+    }
+}
+
+pub(super) fn decode(value: usize) -> Option<usize> {
+    value.checked_sub(1)
+}
@@ -1,19 +1,88 @@
-//! A stub for `std::sync::Barrier`.
+use super::{Condvar, Mutex};
 
-#[derive(Debug)]
-/// `std::sync::Barrier` is not supported yet in Loom. This stub is provided just
-/// to make the code to compile.
-pub struct Barrier {}
+use std::fmt;
+
+/// Mock implementation of `std::sync::Barrier`.
+///
+/// Like with `std`, the last thread to call [`wait`] in a generation of the
+/// barrier is its leader.
+///
+/// [`wait`]: Barrier::wait
+pub struct Barrier {
+    state: Mutex<State>,
+    cvar: Condvar,
+    num_threads: usize,
+}
+
+/// Returned by [`Barrier::wait`] when all the threads reached the barrier.
+pub struct BarrierWaitResult(bool);
+
+struct State {
+    /// Number of threads waiting in the current generation
+    count: usize,
+    generation: usize,
+}
 
 impl Barrier {
-    /// `std::sync::Barrier` is not supported yet in Loom. This stub is provided just
-    /// to make the code to compile.
-    pub fn new(_n: usize) -> Self {
-        unimplemented!("std::sync::Barrier is not supported yet in Loom.")
+    /// Creates a new barrier that can block a given number of threads.
+    pub fn new(n: usize) -> Barrier {
+        Barrier {
+            state: Mutex::new(State {
+                count: 0,
+                generation: 0,
+            }),
+            cvar: Condvar::new(),
+            num_threads: n,
+        }
     }
-    /// `std::sync::Barrier` is not supported yet in Loom. This stub is provided just
-    /// to make the code to compile.
-    pub fn wait(&self) -> std::sync::BarrierWaitResult {
-        unimplemented!("std::sync::Barrier is not supported yet in Loom.")
+
+    /// Blocks the current thread until all threads have rendezvoused here.
+    ///
+    /// The barrier is reusable: once all threads rendezvoused, a new
+    /// generation begins.
+    #[track_caller]
+    pub fn wait(&self) -> BarrierWaitResult {
+        let mut state = self.state.lock().unwrap();
+        let generation = state.generation;
+
+        state.count += 1;
+
+        if state.count < self.num_threads {
+            // The condvar may wake up spuriously
+            while generation == state.generation {
+                state = self.cvar.wait(state).unwrap();
+            }
+
+            BarrierWaitResult(false)
+        } else {
+            state.count = 0;
+            state.generation = state.generation.wrapping_add(1);
+            self.cvar.notify_all();
+
+            BarrierWaitResult(true)
+        }
+    }
+}
+
+impl fmt::Debug for Barrier {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Barrier").finish_non_exhaustive()
+    }
+}
+
+impl BarrierWaitResult {
+    /// Returns `true` if this thread is the leader of its generation of the
+    /// barrier, i.e. the last one to reach it. Exactly one thread per
+    /// generation is the leader.
+    pub fn is_leader(&self) -> bool {
+        self.0
+    }
+}
+
+impl fmt::Debug for BarrierWaitResult {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("BarrierWaitResult")
+            .field("is_leader", &self.0)
+            .finish()
     }
 }
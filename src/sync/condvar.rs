@@ -24,6 +24,11 @@ impl Condvar {
     }
 
     /// Blocks the current thread until this condition variable receives a notification.
+    ///
+    /// Like with `std`, the wait may return without a notification, so the
+    /// condition must be checked in a loop. Loom explores these spurious
+    /// wakeups when enabled with
+    /// [`Builder::spurious_wakeups`](crate::model::Builder::spurious_wakeups).
     #[track_caller]
     pub fn wait<'a, T>(&self, mut guard: MutexGuard<'a, T>) -> LockResult<MutexGuard<'a, T>> {
         // Release the RefCell borrow guard allowing another thread to lock the
@@ -36,7 +41,11 @@ impl Condvar {
         // Borrow the mutex guarded data again
         guard.reborrow();
 
-        Ok(guard)
+        if guard.rt().is_poisoned() {
+            Err(PoisonError::new(guard))
+        } else {
+            Ok(guard)
+        }
     }
 
     /// Waits on this condition variable for a notification, timing out after a
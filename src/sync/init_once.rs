@@ -0,0 +1,78 @@
+use super::atomic_option::{decode, encode};
+use crate::sync::atomic::AtomicUsize;
+
+use std::sync::atomic::Ordering::{AcqRel, Acquire};
+
+/// A `usize` initialized once, by the first thread to write it.
+///
+/// [`init_or_get`] is the "first write wins" idiom: a `compare_exchange` from
+/// the uninitialized state, where only the first thread succeeds, and the
+/// other threads observe the value it wrote. Loom explores which thread wins,
+/// so a model can check that all threads agree on the value.
+///
+/// Like [`AtomicOptionUsize`](super::AtomicOptionUsize), the value is stored
+/// in an [`AtomicUsize`] offset by one, so `usize::MAX` cannot be stored.
+///
+/// [`init_or_get`]: InitOnce::init_or_get
+///
+/// # Examples
+///
+/// ```
+/// use loom::sync::{Arc, InitOnce};
+/// use loom::thread;
+///
+/// loom::model(|| {
+///     let init = Arc::new(InitOnce::new());
+///
+///     let th = {
+///         let init = init.clone();
+///         thread::spawn(move || init.init_or_get(1))
+///     };
+///
+///     let value = init.init_or_get(2);
+///     assert_eq!(value, th.join().unwrap());
+/// });
+/// ```
+#[derive(Debug, Default)]
+pub struct InitOnce(AtomicUsize);
+
+impl InitOnce {
+    /// Creates a new, uninitialized `InitOnce`.
+    #[track_caller]
+    pub fn new() -> InitOnce {
+        InitOnce(AtomicUsize::new(encode(None)))
+    }
+
+    /// Initializes the value with `value` if it is not initialized yet,
+    /// returning the value that was established: `value` if this thread won,
+    /// otherwise the value of the thread that did.
+    ///
+    /// Initializing the value releases the writes of the winning thread, and
+    /// returning the value acquires them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` is `usize::MAX`.
+    #[track_caller]
+    pub fn init_or_get(&self, value: usize) -> usize {
+        match self
+            .0
+            .compare_exchange(encode(None), encode(Some(value)), AcqRel, Acquire)
+        {
+            Ok(_) => value,
+            Err(actual) => decode(actual).unwrap(),
+        }
+    }
+
+    /// Returns the value, or `None` if it is not initialized yet.
+    #[track_caller]
+    pub fn get(&self) -> Option<usize> {
+        decode(self.0.load(Acquire))
+    }
+
+    /// Consumes the `InitOnce` and returns the value, if initialized.
+    #[track_caller]
+    pub fn into_inner(self) -> Option<usize> {
+        decode(self.0.into_inner())
+    }
+}
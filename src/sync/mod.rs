@@ -2,19 +2,33 @@
 
 mod arc;
 pub mod atomic;
+mod atomic_option;
 mod barrier;
 mod condvar;
+mod init_once;
 pub mod mpsc;
 mod mutex;
 mod notify;
+mod once;
+mod once_lock;
+mod publish;
+mod ref_count;
 mod rwlock;
+mod spin_lock;
 
-pub use self::arc::Arc;
-pub use self::barrier::Barrier;
+pub use self::arc::{Arc, Weak};
+pub use self::atomic_option::AtomicOptionUsize;
+pub use self::barrier::{Barrier, BarrierWaitResult};
 pub use self::condvar::{Condvar, WaitTimeoutResult};
+pub use self::init_once::InitOnce;
 pub use self::mutex::{Mutex, MutexGuard};
 pub use self::notify::Notify;
+pub use self::once::Once;
+pub use self::once_lock::OnceLock;
+pub use self::publish::{Publisher, Subscriber};
+pub use self::ref_count::RefCount;
 pub use self::rwlock::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+pub use self::spin_lock::{SpinLock, SpinLockGuard};
 
 #[doc(no_inline)]
 pub use std::sync::{LockResult, TryLockResult};
@@ -19,6 +19,27 @@ pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
     (sender, receiver)
 }
 
+/// Mock implementation of `std::sync::mpsc::sync_channel`.
+///
+/// The channel buffers at most `bound` messages, sends block while the buffer
+/// is full. With a `bound` of zero, each send blocks until the receiver is
+/// waiting for the message and hands it off.
+#[track_caller]
+pub fn sync_channel<T>(bound: usize) -> (SyncSender<T>, Receiver<T>) {
+    let location = location!();
+    let (sender_channel, receiver_channel) = std::sync::mpsc::channel();
+    let channel = std::sync::Arc::new(rt::Channel::new_bounded(bound, location));
+    let sender = SyncSender {
+        object: std::sync::Arc::clone(&channel),
+        sender: sender_channel,
+    };
+    let receiver = Receiver {
+        object: std::sync::Arc::clone(&channel),
+        receiver: receiver_channel,
+    };
+    (sender, receiver)
+}
+
 #[derive(Debug)]
 /// Mock implementation of `std::sync::mpsc::Sender`.
 pub struct Sender<T> {
@@ -31,13 +52,17 @@ impl<T> Sender<T> {
     /// not be sent.
     #[track_caller]
     pub fn send(&self, msg: T) -> Result<(), std::sync::mpsc::SendError<T>> {
-        self.object.send(location!());
-        self.sender.send(msg)
+        if self.object.send(location!()) {
+            self.sender.send(msg)
+        } else {
+            Err(std::sync::mpsc::SendError(msg))
+        }
     }
 }
 
 impl<T> Clone for Sender<T> {
     fn clone(&self) -> Sender<T> {
+        self.object.clone_sender();
         Sender {
             object: std::sync::Arc::clone(&self.object),
             sender: self.sender.clone(),
@@ -45,6 +70,77 @@ impl<T> Clone for Sender<T> {
     }
 }
 
+impl<T> Drop for Sender<T> {
+    #[track_caller]
+    fn drop(&mut self) {
+        // The sender may outlive the execution, which then has nothing to
+        // drop.
+        if rt::try_execution(|_| ()).is_some() {
+            self.object.drop_sender(location!());
+        }
+    }
+}
+
+#[derive(Debug)]
+/// Mock implementation of `std::sync::mpsc::SyncSender`.
+pub struct SyncSender<T> {
+    object: std::sync::Arc<rt::Channel>,
+    // The bound is enforced by the model, the std channel only stores the
+    // messages.
+    sender: std::sync::mpsc::Sender<T>,
+}
+
+impl<T> SyncSender<T> {
+    /// Sends a value on this channel, blocking until there is room in the
+    /// buffer, or until the receiver takes it for rendezvous channels.
+    /// Returns the value back if the receiver has been dropped.
+    #[track_caller]
+    pub fn send(&self, msg: T) -> Result<(), std::sync::mpsc::SendError<T>> {
+        if self.object.send(location!()) {
+            self.sender.send(msg)
+        } else {
+            Err(std::sync::mpsc::SendError(msg))
+        }
+    }
+
+    /// Attempts to send a value on this channel without blocking, returning
+    /// it back if the buffer is full or the receiver has been dropped.
+    #[track_caller]
+    pub fn try_send(&self, msg: T) -> Result<(), std::sync::mpsc::TrySendError<T>> {
+        use std::sync::mpsc::TrySendError;
+
+        match self.object.try_send(location!()) {
+            Ok(()) => self
+                .sender
+                .send(msg)
+                .map_err(|e| TrySendError::Disconnected(e.0)),
+            Err(TrySendError::Full(())) => Err(TrySendError::Full(msg)),
+            Err(TrySendError::Disconnected(())) => Err(TrySendError::Disconnected(msg)),
+        }
+    }
+}
+
+impl<T> Clone for SyncSender<T> {
+    fn clone(&self) -> SyncSender<T> {
+        self.object.clone_sender();
+        SyncSender {
+            object: std::sync::Arc::clone(&self.object),
+            sender: self.sender.clone(),
+        }
+    }
+}
+
+impl<T> Drop for SyncSender<T> {
+    #[track_caller]
+    fn drop(&mut self) {
+        // The sender may outlive the execution, which then has nothing to
+        // drop.
+        if rt::try_execution(|_| ()).is_some() {
+            self.object.drop_sender(location!());
+        }
+    }
+}
+
 #[derive(Debug)]
 /// Mock implementation of `std::sync::mpsc::Receiver`.
 pub struct Receiver<T> {
@@ -57,33 +153,48 @@ impl<T> Receiver<T> {
     /// corresponding channel has hung up.
     #[track_caller]
     pub fn recv(&self) -> Result<T, std::sync::mpsc::RecvError> {
-        self.object.recv(location!());
-        self.receiver.recv()
+        if self.object.recv(location!()) {
+            self.receiver.recv()
+        } else {
+            Err(std::sync::mpsc::RecvError)
+        }
     }
+
     /// Attempts to wait for a value on this receiver, returning an error if the
     /// corresponding channel has hung up, or if it waits more than `timeout`.
+    ///
+    /// Loom has no clock, the duration is ignored. When no value is available,
+    /// the model explores both the receive timing out and the receive blocking
+    /// until a value is sent. Timeouts are explored once per channel and
+    /// execution, so retrying after a timeout eventually blocks.
+    #[track_caller]
     pub fn recv_timeout(
         &self,
         _timeout: std::time::Duration,
     ) -> Result<T, std::sync::mpsc::RecvTimeoutError> {
-        unimplemented!("std::sync::mpsc::Receiver::recv_timeout is not supported yet in Loom.")
+        use std::sync::mpsc::RecvTimeoutError;
+
+        self.object.recv_timeout(location!())?;
+        self.receiver
+            .recv()
+            .map_err(|_| RecvTimeoutError::Disconnected)
     }
 
     /// Attempts to return a pending value on this receiver without blocking.
+    #[track_caller]
     pub fn try_recv(&self) -> Result<T, std::sync::mpsc::TryRecvError> {
-        if self.object.is_empty() {
-            return Err(std::sync::mpsc::TryRecvError::Empty);
-        } else {
-            self.recv().map_err(|e| e.into())
-        }
+        self.object.try_recv(location!())?;
+        self.receiver.try_recv()
     }
 }
 
 impl<T> Drop for Receiver<T> {
+    #[track_caller]
     fn drop(&mut self) {
-        // Drain the channel.
-        while !self.object.is_empty() {
-            self.recv().unwrap();
+        // The receiver may outlive the execution, which then has nothing to
+        // drop.
+        if rt::try_execution(|_| ()).is_some() {
+            self.object.drop_receiver(location!());
         }
     }
 }
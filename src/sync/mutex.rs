@@ -1,9 +1,12 @@
 use crate::rt;
 
 use std::ops;
-use std::sync::{LockResult, TryLockError, TryLockResult};
+use std::sync::{LockResult, PoisonError, TryLockError, TryLockResult};
 
 /// Mock implementation of `std::sync::Mutex`.
+///
+/// Like with `std`, the mutex is poisoned when a thread panics while holding
+/// the lock. Locking a poisoned mutex returns an error holding the guard.
 #[derive(Debug)]
 pub struct Mutex<T: ?Sized> {
     object: rt::Mutex,
@@ -15,20 +18,43 @@ pub struct Mutex<T: ?Sized> {
 pub struct MutexGuard<'a, T: ?Sized> {
     lock: &'a Mutex<T>,
     data: Option<std::sync::MutexGuard<'a, T>>,
+    /// True if the thread was already panicking when the lock was acquired,
+    /// in which case dropping the guard does not poison the mutex.
+    panicking: bool,
 }
 
 impl<T> Mutex<T> {
     /// Creates a new mutex in an unlocked state ready for use.
+    #[track_caller]
     pub fn new(data: T) -> Mutex<T> {
+        let object = rt::Mutex::new(true);
+        object.track_sharing(std::panic::Location::caller());
+
         Mutex {
             data: std::sync::Mutex::new(data),
-            object: rt::Mutex::new(true),
+            object,
         }
     }
 
     /// Consumes this mutex, returning the underlying data.
+    ///
+    /// This is not a branch point, as no other thread can hold the lock, but
+    /// the access must happen-after the mutex was last unlocked.
+    #[track_caller]
     pub fn into_inner(self) -> LockResult<T> {
-        Ok(self.data.into_inner().unwrap())
+        self.object.exclusive_access(location!());
+
+        // Poisoning is tracked by `object`.
+        let data = self
+            .data
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        if self.object.is_poisoned() {
+            Err(PoisonError::new(data))
+        } else {
+            Ok(data)
+        }
     }
 }
 
@@ -38,10 +64,17 @@ impl<T: ?Sized> Mutex<T> {
     pub fn lock(&self) -> LockResult<MutexGuard<'_, T>> {
         self.object.acquire_lock(location!());
 
-        Ok(MutexGuard {
+        let guard = MutexGuard {
             lock: self,
-            data: Some(self.data.lock().unwrap()),
-        })
+            data: Some(self.lock_data()),
+            panicking: std::thread::panicking(),
+        };
+
+        if self.object.is_poisoned() {
+            Err(PoisonError::new(guard))
+        } else {
+            Ok(guard)
+        }
     }
 
     /// Attempts to acquire this lock.
@@ -53,24 +86,74 @@ impl<T: ?Sized> Mutex<T> {
     /// This function does not block.
     #[track_caller]
     pub fn try_lock(&self) -> TryLockResult<MutexGuard<'_, T>> {
-        if self.object.try_acquire_lock(location!()) {
-            Ok(MutexGuard {
-                lock: self,
-                data: Some(self.data.lock().unwrap()),
-            })
+        if !self.object.try_acquire_lock(location!()) {
+            return Err(TryLockError::WouldBlock);
+        }
+
+        let guard = MutexGuard {
+            lock: self,
+            data: Some(self.lock_data()),
+            panicking: std::thread::panicking(),
+        };
+
+        if self.object.is_poisoned() {
+            Err(TryLockError::Poisoned(PoisonError::new(guard)))
         } else {
-            Err(TryLockError::WouldBlock)
+            Ok(guard)
         }
     }
 
+    /// Determines whether the mutex is poisoned.
+    ///
+    /// Like with `std`, another thread may poison the mutex at any time, so
+    /// this is only meant for assertions. This is not a branch point and does
+    /// not synchronize with other threads.
+    pub fn is_poisoned(&self) -> bool {
+        self.object.is_poisoned()
+    }
+
+    /// Clears the poisoned state of the mutex.
+    ///
+    /// This is typically called while holding the guard returned in the
+    /// `PoisonError`, after restoring the data to a consistent state. This
+    /// is not a branch point.
+    pub fn clear_poison(&self) {
+        self.object.clear_poison();
+    }
+
     /// Returns a mutable reference to the underlying data.
+    ///
+    /// This is not a branch point, as no other thread can hold the lock, but
+    /// the access must happen-after the mutex was last unlocked, and
+    /// happen-before it is locked again.
+    #[track_caller]
     pub fn get_mut(&mut self) -> LockResult<&mut T> {
-        Ok(self.data.get_mut().unwrap())
+        self.object.exclusive_access(location!());
+
+        // Poisoning is tracked by `object`.
+        let data = self.data.get_mut().unwrap_or_else(PoisonError::into_inner);
+
+        if self.object.is_poisoned() {
+            Err(PoisonError::new(data))
+        } else {
+            Ok(data)
+        }
+    }
+
+    /// Locks the std mutex holding the data. Only the thread holding the loom
+    /// lock calls this, so it never blocks.
+    ///
+    /// The std mutex is poisoned along with `object`, which tracks poisoning
+    /// instead.
+    fn lock_data(&self) -> std::sync::MutexGuard<'_, T> {
+        self.data.lock().unwrap_or_else(PoisonError::into_inner)
     }
 }
 
+#[allow(clippy::needless_maybe_sized)]
 impl<T: ?Sized + Default> Default for Mutex<T> {
     /// Creates a `Mutex<T>`, with the `Default` value for T.
+    #[track_caller]
     fn default() -> Self {
         Self::new(Default::default())
     }
@@ -79,6 +162,7 @@ impl<T: ?Sized + Default> Default for Mutex<T> {
 impl<T> From<T> for Mutex<T> {
     /// Creates a new mutex in an unlocked state ready for use.
     /// This is equivalent to [`Mutex::new`].
+    #[track_caller]
     fn from(t: T) -> Self {
         Self::new(t)
     }
@@ -90,7 +174,7 @@ impl<'a, T: ?Sized + 'a> MutexGuard<'a, T> {
     }
 
     pub(super) fn reborrow(&mut self) {
-        self.data = Some(self.lock.data.lock().unwrap());
+        self.data = Some(self.lock.lock_data());
     }
 
     pub(super) fn rt(&self) -> &rt::Mutex {
@@ -114,6 +198,10 @@ impl<'a, T: ?Sized> ops::DerefMut for MutexGuard<'a, T> {
 
 impl<'a, T: ?Sized + 'a> Drop for MutexGuard<'a, T> {
     fn drop(&mut self) {
+        if !self.panicking && std::thread::panicking() {
+            self.lock.object.poison();
+        }
+
         self.data = None;
         self.lock.object.release_lock();
     }
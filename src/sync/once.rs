@@ -0,0 +1,87 @@
+use crate::rt;
+use crate::rt::once::Begin;
+
+use std::fmt;
+
+/// Mock implementation of `std::sync::Once`.
+///
+/// Concurrent calls to [`call_once`] block until the thread running the
+/// initialization completes it, and synchronize with that thread.
+///
+/// [`call_once`]: Once::call_once
+pub struct Once {
+    object: rt::Once,
+}
+
+impl Once {
+    /// Creates a new `Once` value.
+    pub fn new() -> Once {
+        Once {
+            object: rt::Once::new(),
+        }
+    }
+
+    /// Performs an initialization routine once and only once. The given
+    /// closure will be executed if this is the first time `call_once` has
+    /// been called, and otherwise the routine will *not* be invoked.
+    ///
+    /// This method will block the calling thread if another initialization
+    /// routine is currently running.
+    ///
+    /// # Panics
+    ///
+    /// If the closure panics, the `Once` is poisoned and all future calls to
+    /// `call_once` panic as well.
+    #[track_caller]
+    pub fn call_once<F>(&self, f: F)
+    where
+        F: FnOnce(),
+    {
+        match self.object.begin(location!()) {
+            Begin::Run => {}
+            Begin::Complete => return,
+            Begin::Poisoned => panic!("Once instance has previously been poisoned"),
+        }
+
+        let end = End {
+            object: &self.object,
+        };
+
+        f();
+        drop(end);
+    }
+
+    /// Returns `true` if some [`call_once`](Once::call_once) call has
+    /// completed successfully, synchronizing with it in that case.
+    #[track_caller]
+    pub fn is_completed(&self) -> bool {
+        self.object.is_complete(location!())
+    }
+}
+
+/// Ends the initialization, poisoning the `Once` if the closure panicked.
+struct End<'a> {
+    object: &'a rt::Once,
+}
+
+impl Drop for End<'_> {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            self.object.abort(true);
+        } else {
+            self.object.end();
+        }
+    }
+}
+
+impl Default for Once {
+    fn default() -> Once {
+        Once::new()
+    }
+}
+
+impl fmt::Debug for Once {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Once").finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,148 @@
+use crate::rt;
+use crate::rt::once::Begin;
+
+use std::cell::UnsafeCell;
+use std::fmt;
+use std::mem;
+
+/// Mock implementation of `std::sync::OnceLock`.
+///
+/// Reading the value with [`get`], [`wait`] or [`get_or_init`] synchronizes
+/// with the thread that initialized it. Threads racing to initialize the cell
+/// block until the winner publishes the value.
+///
+/// [`get`]: OnceLock::get
+/// [`wait`]: OnceLock::wait
+/// [`get_or_init`]: OnceLock::get_or_init
+pub struct OnceLock<T> {
+    object: rt::Once,
+    value: UnsafeCell<Option<T>>,
+}
+
+unsafe impl<T: Send> Send for OnceLock<T> {}
+unsafe impl<T: Send + Sync> Sync for OnceLock<T> {}
+
+impl<T> OnceLock<T> {
+    /// Creates a new empty cell.
+    pub fn new() -> OnceLock<T> {
+        OnceLock {
+            object: rt::Once::new(),
+            value: UnsafeCell::new(None),
+        }
+    }
+
+    /// Gets the reference to the underlying value.
+    ///
+    /// Returns `None` if the cell is empty, or being initialized.
+    #[track_caller]
+    pub fn get(&self) -> Option<&T> {
+        if self.object.is_complete(location!()) {
+            Some(self.value())
+        } else {
+            None
+        }
+    }
+
+    /// Gets the mutable reference to the underlying value.
+    pub fn get_mut(&mut self) -> Option<&mut T> {
+        self.value.get_mut().as_mut()
+    }
+
+    /// Blocks the current thread until the cell is initialized, then returns
+    /// the value.
+    ///
+    /// The model deadlocks if the cell is never initialized.
+    #[track_caller]
+    pub fn wait(&self) -> &T {
+        self.object.wait(location!());
+        self.value()
+    }
+
+    /// Initializes the contents of the cell to `value`.
+    ///
+    /// Blocks while another thread initializes the cell. Returns `Err(value)`
+    /// if the cell was already initialized.
+    #[track_caller]
+    pub fn set(&self, value: T) -> Result<(), T> {
+        if self.object.begin(location!()) != Begin::Run {
+            return Err(value);
+        }
+
+        self.publish(value);
+        Ok(())
+    }
+
+    /// Gets the contents of the cell, initializing it with `f` if the cell
+    /// was empty.
+    ///
+    /// Only one of the threads racing to initialize the cell runs its
+    /// closure, the others block until the value is published. If `f` panics,
+    /// the panic is propagated and the cell remains uninitialized, so that
+    /// another thread may initialize it.
+    #[track_caller]
+    pub fn get_or_init<F>(&self, f: F) -> &T
+    where
+        F: FnOnce() -> T,
+    {
+        if self.object.begin(location!()) == Begin::Run {
+            let abort = Abort {
+                object: &self.object,
+            };
+
+            let value = f();
+            mem::forget(abort);
+            self.publish(value);
+        }
+
+        self.value()
+    }
+
+    /// Consumes the cell, returning the wrapped value.
+    pub fn into_inner(self) -> Option<T> {
+        self.value.into_inner()
+    }
+
+    fn publish(&self, value: T) {
+        // Safety: other threads only read the value once the initialization
+        // completed, and no thread can run before the next branch point.
+        unsafe { *self.value.get() = Some(value) };
+        self.object.end();
+    }
+
+    fn value(&self) -> &T {
+        // Safety: the value is not written again once initialized.
+        unsafe { (*self.value.get()).as_ref() }
+            .expect("[loom internal bug] initialized cell has no value")
+    }
+}
+
+/// Aborts the initialization if the closure panics.
+struct Abort<'a> {
+    object: &'a rt::Once,
+}
+
+impl Drop for Abort<'_> {
+    fn drop(&mut self) {
+        self.object.abort(false);
+    }
+}
+
+impl<T> Default for OnceLock<T> {
+    fn default() -> OnceLock<T> {
+        OnceLock::new()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for OnceLock<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut d = f.debug_tuple("OnceLock");
+
+        if self.object.debug_is_complete() {
+            d.field(self.value());
+        } else {
+            d.field(&format_args!("<uninit>"));
+        }
+
+        d.finish()
+    }
+}
@@ -0,0 +1,137 @@
+use crate::cell::UnsafeCell;
+use crate::sync::atomic::AtomicBool;
+use crate::sync::Arc;
+
+use std::fmt;
+use std::sync::atomic::Ordering::{Acquire, Release};
+
+/// Publishes a value once to any number of [`Subscriber`]s.
+///
+/// This is the "build the data, then publish it with a `Release` store" idiom:
+/// the value is written to an [`UnsafeCell`] before a flag is set with
+/// `Release` ordering, and subscribers only read the value once they observe
+/// the flag with `Acquire` ordering. Loom checks that no subscriber reads the
+/// value before it is fully written.
+///
+/// # Examples
+///
+/// ```
+/// use loom::sync::Publisher;
+/// use loom::thread;
+///
+/// loom::model(|| {
+///     let publisher = Publisher::new();
+///     let subscriber = publisher.subscribe();
+///
+///     let th = thread::spawn(move || {
+///         if let Some(sum) = subscriber.with(|&(a, b)| a + b) {
+///             assert_eq!(3, sum);
+///         }
+///     });
+///
+///     publisher.publish((1, 2));
+///     th.join().unwrap();
+/// });
+/// ```
+pub struct Publisher<T> {
+    shared: Arc<Shared<T>>,
+}
+
+/// Reads the value published by a [`Publisher`].
+pub struct Subscriber<T> {
+    shared: Arc<Shared<T>>,
+}
+
+struct Shared<T> {
+    published: AtomicBool,
+    value: UnsafeCell<Option<T>>,
+}
+
+// The value is written once, before `published` is set, and only read after.
+unsafe impl<T: Send> Send for Shared<T> {}
+unsafe impl<T: Send + Sync> Sync for Shared<T> {}
+
+impl<T> Publisher<T> {
+    /// Creates a new `Publisher`, with no value published.
+    #[track_caller]
+    pub fn new() -> Publisher<T> {
+        Publisher {
+            shared: Arc::new(Shared {
+                published: AtomicBool::new(false),
+                value: UnsafeCell::new(None),
+            }),
+        }
+    }
+
+    /// Returns a new subscriber to the value.
+    pub fn subscribe(&self) -> Subscriber<T> {
+        Subscriber {
+            shared: self.shared.clone(),
+        }
+    }
+
+    /// Publishes `value` to the subscribers.
+    #[track_caller]
+    pub fn publish(self, value: T) {
+        // Safety: subscribers don't read the value until `published` is set,
+        // and it can only be set once as `publish` consumes the publisher.
+        self.shared
+            .value
+            .with_mut(|ptr| unsafe { *ptr = Some(value) });
+
+        self.shared.published.store(true, Release);
+    }
+}
+
+impl<T> Subscriber<T> {
+    /// Returns `true` if the value was published.
+    ///
+    /// When this returns `true`, the value is visible to the current thread.
+    #[track_caller]
+    pub fn is_published(&self) -> bool {
+        self.shared.published.load(Acquire)
+    }
+
+    /// Calls `f` with the value if it was published, returning its result.
+    #[track_caller]
+    pub fn with<F, R>(&self, f: F) -> Option<R>
+    where
+        F: FnOnce(&T) -> R,
+    {
+        if !self.is_published() {
+            return None;
+        }
+
+        // Safety: the value was written before `published` was set, and is
+        // never written again.
+        self.shared
+            .value
+            .with(|ptr| unsafe { (*ptr).as_ref().map(f) })
+    }
+}
+
+impl<T> Clone for Subscriber<T> {
+    fn clone(&self) -> Subscriber<T> {
+        Subscriber {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> Default for Publisher<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> fmt::Debug for Publisher<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Publisher").finish_non_exhaustive()
+    }
+}
+
+impl<T> fmt::Debug for Subscriber<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Subscriber").finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,113 @@
+use crate::sync::atomic::{fence, AtomicUsize};
+
+use std::sync::atomic::Ordering::{Acquire, Relaxed, Release};
+
+/// A reference count for intrusive data structures, using the same orderings
+/// as `std::sync::Arc`.
+///
+/// Acquiring a reference is `Relaxed`, as a new reference can only be created
+/// from an existing one. Releasing a reference is `Release`, so that all
+/// accesses through it happen-before the data is dropped, and the thread
+/// releasing the last reference issues an `Acquire` fence before dropping the
+/// data. Loom reports a causality violation if the data is dropped without
+/// that fence while another thread accessed it.
+///
+/// # Examples
+///
+/// ```
+/// use loom::cell::UnsafeCell;
+/// use loom::sync::RefCount;
+/// use loom::thread;
+///
+/// struct Node {
+///     refs: RefCount,
+///     value: UnsafeCell<usize>,
+/// }
+///
+/// loom::model(|| {
+///     let node = Box::into_raw(Box::new(Node {
+///         refs: RefCount::new(),
+///         value: UnsafeCell::new(0),
+///     }));
+///     let ptr = node as usize;
+///
+///     unsafe { (*node).refs.increment() };
+///
+///     let th = thread::spawn(move || {
+///         let node = ptr as *mut Node;
+///
+///         unsafe {
+///             (*node).value.with_mut(|v| *v = 1);
+///
+///             if (*node).refs.decrement() {
+///                 drop(Box::from_raw(node));
+///             }
+///         }
+///     });
+///
+///     unsafe {
+///         if (*node).refs.decrement() {
+///             drop(Box::from_raw(node));
+///         }
+///     }
+///
+///     th.join().unwrap();
+/// });
+/// ```
+#[derive(Debug)]
+pub struct RefCount(AtomicUsize);
+
+impl RefCount {
+    /// Creates a new count, holding a single reference.
+    #[track_caller]
+    pub fn new() -> RefCount {
+        RefCount(AtomicUsize::new(1))
+    }
+
+    /// Acquires a new reference.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the count overflows `isize::MAX`, like `Arc::clone` aborts.
+    #[track_caller]
+    pub fn increment(&self) {
+        let prev = self.0.fetch_add(1, Relaxed);
+        assert!(prev <= isize::MAX as usize, "reference count overflow");
+    }
+
+    /// Releases a reference, returning `true` if it was the last one, in which
+    /// case the caller must drop the data.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no reference to release.
+    #[track_caller]
+    pub fn decrement(&self) -> bool {
+        let prev = self.0.fetch_sub(1, Release);
+        assert_ne!(prev, 0, "released a reference that was not held");
+
+        if prev != 1 {
+            return false;
+        }
+
+        // Synchronize with the release of the other references, so that
+        // their accesses happen-before the data is dropped.
+        fence(Acquire);
+        true
+    }
+
+    /// Returns the number of references.
+    ///
+    /// As with `Arc::strong_count`, the count may change concurrently unless
+    /// the caller holds the only reference.
+    #[track_caller]
+    pub fn count(&self) -> usize {
+        self.0.load(Acquire)
+    }
+}
+
+impl Default for RefCount {
+    fn default() -> RefCount {
+        RefCount::new()
+    }
+}
@@ -28,10 +28,14 @@ pub struct RwLockWriteGuard<'a, T> {
 
 impl<T> RwLock<T> {
     /// Creates a new rwlock in an unlocked state ready for use.
+    #[track_caller]
     pub fn new(data: T) -> RwLock<T> {
+        let object = rt::RwLock::new();
+        object.track_sharing(std::panic::Location::caller());
+
         RwLock {
             data: std::sync::RwLock::new(data),
-            object: rt::RwLock::new(),
+            object,
         }
     }
 
@@ -89,7 +93,8 @@ impl<T> RwLock<T> {
 
     /// Attempts to lock this rwlock with exclusive write access.
     ///
-    /// If the lock could not be acquired at this time, then Err is returned.
+    /// If the lock could not be acquired at this time, including when any
+    /// reader holds it, then Err is returned.
     /// Otherwise, an RAII guard is returned which will release the lock when
     /// it is dropped.
     ///
@@ -106,19 +111,49 @@ impl<T> RwLock<T> {
         }
     }
 
+    /// Returns the number of read guards currently held on this rwlock.
+    ///
+    /// This is only meant for assertions and debugging: it does not create a
+    /// branch point or synchronize with other threads, so the value may be
+    /// outdated as soon as another thread runs. It is accurate while the
+    /// calling thread holds the guards being counted, or a write guard.
+    pub fn reader_count(&self) -> usize {
+        self.object.reader_count()
+    }
+
+    /// Returns `true` if this rwlock is currently write locked.
+    ///
+    /// Like [`reader_count`](RwLock::reader_count), this is advisory and
+    /// does not synchronize with other threads.
+    pub fn is_write_locked(&self) -> bool {
+        self.object.is_write_locked()
+    }
+
     /// Returns a mutable reference to the underlying data.
+    ///
+    /// This is not a branch point, as no other thread can hold the lock, but
+    /// the access must happen-after the rwlock was last unlocked, and
+    /// happen-before it is locked again.
+    #[track_caller]
     pub fn get_mut(&mut self) -> LockResult<&mut T> {
+        self.object.exclusive_access(location!());
         Ok(self.data.get_mut().expect("loom::RwLock state corrupt"))
     }
 
     /// Consumes this `RwLock`, returning the underlying data.
+    ///
+    /// This is not a branch point, as no other thread can hold the lock, but
+    /// the access must happen-after the rwlock was last unlocked.
+    #[track_caller]
     pub fn into_inner(self) -> LockResult<T> {
+        self.object.exclusive_access(location!());
         Ok(self.data.into_inner().expect("loom::RwLock state corrupt"))
     }
 }
 
 impl<T: Default> Default for RwLock<T> {
     /// Creates a `RwLock<T>`, with the `Default` value for T.
+    #[track_caller]
     fn default() -> Self {
         Self::new(Default::default())
     }
@@ -127,6 +162,7 @@ impl<T: Default> Default for RwLock<T> {
 impl<T> From<T> for RwLock<T> {
     /// Creates a new rwlock in an unlocked state ready for use.
     /// This is equivalent to [`RwLock::new`].
+    #[track_caller]
     fn from(t: T) -> Self {
         Self::new(t)
     }
@@ -0,0 +1,142 @@
+use crate::cell::{MutPtr, UnsafeCell};
+use crate::sync::atomic::AtomicBool;
+use crate::thread;
+
+use std::fmt;
+use std::ops;
+use std::sync::atomic::Ordering::{Acquire, Relaxed, Release};
+
+/// A spin lock, built on an [`AtomicBool`] and an [`UnsafeCell`].
+///
+/// This is a reference implementation of a spin lock checked by loom: the
+/// lock is acquired with an `Acquire` compare-and-swap and released with a
+/// `Release` store, so the accesses to the data in a critical section
+/// happen-before those of the next one. While the lock is held by another
+/// thread, the waiting thread spins on a `Relaxed` load, yielding so that loom
+/// explores a single spin per attempt instead of every number of spins. With
+/// more than two threads contending for the lock, the waiting threads may
+/// still spin in turns, so the number of branches grows quickly.
+///
+/// It can be used as a building block in models, or as a starting point for
+/// modeling another spin lock.
+///
+/// # Examples
+///
+/// ```
+/// use loom::sync::{Arc, SpinLock};
+/// use loom::thread;
+///
+/// loom::model(|| {
+///     let lock = Arc::new(SpinLock::new(0));
+///
+///     let th = {
+///         let lock = lock.clone();
+///         thread::spawn(move || *lock.lock() += 1)
+///     };
+///
+///     *lock.lock() += 1;
+///     th.join().unwrap();
+///
+///     assert_eq!(2, *lock.lock());
+/// });
+/// ```
+pub struct SpinLock<T> {
+    locked: AtomicBool,
+    data: UnsafeCell<T>,
+}
+
+/// Guard of a [`SpinLock`], releasing the lock when dropped.
+pub struct SpinLockGuard<'a, T> {
+    lock: &'a SpinLock<T>,
+    data: Option<MutPtr<T>>,
+}
+
+// The data is only accessed while holding the lock.
+unsafe impl<T: Send> Send for SpinLock<T> {}
+unsafe impl<T: Send> Sync for SpinLock<T> {}
+
+impl<T> SpinLock<T> {
+    /// Creates a new spin lock in an unlocked state.
+    #[track_caller]
+    pub fn new(data: T) -> SpinLock<T> {
+        SpinLock {
+            locked: AtomicBool::new(false),
+            data: UnsafeCell::new(data),
+        }
+    }
+
+    /// Acquires the lock, spinning until it is available.
+    #[track_caller]
+    pub fn lock(&self) -> SpinLockGuard<'_, T> {
+        loop {
+            if let Some(guard) = self.try_lock() {
+                return guard;
+            }
+
+            while self.locked.load(Relaxed) {
+                thread::yield_now();
+            }
+        }
+    }
+
+    /// Attempts to acquire the lock, returning `None` if it is held by another
+    /// thread.
+    #[track_caller]
+    pub fn try_lock(&self) -> Option<SpinLockGuard<'_, T>> {
+        if self
+            .locked
+            .compare_exchange(false, true, Acquire, Relaxed)
+            .is_err()
+        {
+            return None;
+        }
+
+        Some(SpinLockGuard {
+            lock: self,
+            data: Some(self.data.get_mut()),
+        })
+    }
+
+    /// Consumes the lock, returning the underlying data.
+    pub fn into_inner(self) -> T {
+        self.data.into_inner()
+    }
+}
+
+impl<T> fmt::Debug for SpinLock<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("SpinLock").finish_non_exhaustive()
+    }
+}
+
+impl<T> ops::Deref for SpinLockGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // Safety: the lock is held, so the access is exclusive.
+        unsafe { self.data.as_ref().unwrap().deref() }
+    }
+}
+
+impl<T> ops::DerefMut for SpinLockGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        // Safety: the lock is held, so the access is exclusive.
+        unsafe { self.data.as_ref().unwrap().deref() }
+    }
+}
+
+impl<T> Drop for SpinLockGuard<'_, T> {
+    fn drop(&mut self) {
+        // End the access to the data before releasing the lock
+        self.data = None;
+        self.lock.locked.store(false, Release);
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for SpinLockGuard<'_, T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("SpinLockGuard")
+            .field("data", &**self)
+            .finish()
+    }
+}
@@ -1,20 +1,53 @@
 //! Mock implementation of `std::thread`.
 
 pub use crate::rt::thread::AccessError;
-pub use crate::rt::yield_now;
 use crate::rt::{self, Execution, Location};
+pub use crate::rt::{yield_now, yield_now_checked};
 
 #[doc(no_inline)]
 pub use std::thread::panicking;
 
+use std::any::Any;
 use std::marker::PhantomData;
+use std::num::NonZeroUsize;
+use std::panic::{self, AssertUnwindSafe};
 use std::sync::{Arc, Mutex};
-use std::{fmt, io};
+use std::time::Duration;
+use std::{fmt, io, mem};
 
 use tracing::trace;
 
 /// Mock implementation of `std::thread::JoinHandle`.
 pub struct JoinHandle<T> {
+    inner: JoinHandleInner<T>,
+    joined: bool,
+}
+
+/// Mock implementation of `std::thread::ScopedJoinHandle`.
+pub struct ScopedJoinHandle<'scope, T> {
+    /// `None` if the thread panicked
+    inner: JoinHandleInner<Option<T>>,
+    scope: &'scope ScopeData,
+}
+
+/// Mock implementation of `std::thread::Scope`.
+pub struct Scope<'scope, 'env: 'scope> {
+    data: ScopeData,
+    scope: PhantomData<&'scope mut &'scope ()>,
+    env: PhantomData<&'env mut &'env ()>,
+}
+
+/// Threads spawned in a scope that were not joined yet, waited for when the
+/// scope ends.
+struct ScopeData {
+    running: Mutex<Vec<(rt::thread::Id, rt::Notify)>>,
+
+    /// Panics of the threads that were not joined yet, propagated when the
+    /// scope ends.
+    panics: Mutex<Vec<(rt::thread::Id, Box<dyn Any + Send>)>>,
+}
+
+struct JoinHandleInner<T> {
     result: Arc<Mutex<Option<std::thread::Result<T>>>>,
     notify: rt::Notify,
     thread: Thread,
@@ -53,11 +86,22 @@ impl Thread {
 }
 
 /// Mock implementation of `std::thread::ThreadId`.
+///
+/// Within an iteration of the model, each thread has a distinct id that does
+/// not change, so it can be used as a `HashMap` key, e.g. to accumulate
+/// per-thread state. Ids are not stable across iterations: keys of a map kept
+/// from a previous iteration won't match any thread of the current one.
 #[derive(Clone, Copy, Eq, Hash, PartialEq)]
 pub struct ThreadId {
     id: crate::rt::thread::Id,
 }
 
+impl ThreadId {
+    pub(crate) fn rt_id(self) -> crate::rt::thread::Id {
+        self.id
+    }
+}
+
 impl std::fmt::Debug for ThreadId {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "ThreadId({})", self.id.public_id())
@@ -97,6 +141,10 @@ fn init_current(execution: &mut Execution, name: Option<String>) -> Thread {
         name,
     };
 
+    if let Some(trace_thread) = &execution.trace_thread {
+        execution.threads.active_mut().traced = trace_thread(&thread);
+    }
+
     execution
         .threads
         .local_init(&CURRENT_THREAD_KEY, thread.clone());
@@ -129,7 +177,54 @@ where
     F: 'static,
     T: 'static,
 {
-    spawn_internal(f, None, None, location!())
+    // Safety: the closure and its result are `'static`.
+    let inner = unsafe { spawn_internal(f, None, None, location!()) };
+
+    JoinHandle {
+        inner,
+        joined: false,
+    }
+}
+
+/// Mock implementation of `std::thread::scope`.
+///
+/// All threads spawned in the scope that were not joined are joined before
+/// this function returns. If one of them panicked, the panic is propagated
+/// once all of them finished, like with `std`.
+#[track_caller]
+pub fn scope<'env, F, T>(f: F) -> T
+where
+    F: for<'scope> FnOnce(&'scope Scope<'scope, 'env>) -> T,
+{
+    let location = location!();
+
+    let scope = Scope {
+        data: ScopeData {
+            running: Mutex::new(Vec::new()),
+            panics: Mutex::new(Vec::new()),
+        },
+        scope: PhantomData,
+        env: PhantomData,
+    };
+
+    let ret = f(&scope);
+
+    // Threads still running may spawn more threads in the scope, so take them
+    // one at a time.
+    loop {
+        let next = scope.data.running.lock().unwrap().pop();
+
+        match next {
+            Some((_, notify)) => notify.wait(location),
+            None => break,
+        }
+    }
+
+    if let Some((_, payload)) = scope.data.panics.lock().unwrap().pop() {
+        panic::resume_unwind(payload);
+    }
+
+    ret
 }
 
 /// Mock implementation of `std::thread::park`.
@@ -143,34 +238,87 @@ pub fn park() {
     rt::park(location!());
 }
 
-fn spawn_internal<F, T>(
+/// Mock implementation of `std::thread::available_parallelism`.
+///
+/// Returns the number of threads the model can spawn in addition to the main
+/// thread, that is the model's [`max_threads`] minus one, or 1 if it only
+/// allows the main thread. Sizing a thread pool with it keeps it within the
+/// threads loom can handle.
+///
+/// [`max_threads`]: crate::model::Builder::max_threads
+pub fn available_parallelism() -> io::Result<NonZeroUsize> {
+    let max_threads = rt::execution(|execution| execution.max_threads);
+    Ok(NonZeroUsize::new(max_threads - 1).unwrap_or(NonZeroUsize::MIN))
+}
+
+/// Mock implementation of `std::thread::sleep`.
+///
+/// Loom has no clock: the duration is ignored, and the thread [yields]
+/// instead, letting other threads run. Sleeping does not make any time
+/// elapse, so code waiting for a timeout to expire is not modeled.
+///
+/// [yields]: yield_now
+#[track_caller]
+pub fn sleep(dur: Duration) {
+    let location = location!();
+    trace!(?dur, ?location, "sleep");
+
+    rt::yield_now();
+}
+
+/// Mock implementation of `std::thread::sleep_ms`.
+///
+/// Like [`sleep`], the duration is ignored and the thread yields.
+#[deprecated(note = "replaced by `loom::thread::sleep`")]
+#[track_caller]
+pub fn sleep_ms(ms: u32) {
+    sleep(Duration::from_millis(ms as u64))
+}
+
+/// Spawns a thread running `f`.
+///
+/// # Safety
+///
+/// `f` and its result may borrow data for `'a`, the caller must ensure that
+/// the thread completes before `'a` ends.
+unsafe fn spawn_internal<'a, F, T>(
     f: F,
     name: Option<String>,
     stack_size: Option<usize>,
     location: Location,
-) -> JoinHandle<T>
+) -> JoinHandleInner<T>
 where
     F: FnOnce() -> T,
-    F: 'static,
-    T: 'static,
+    F: 'a,
+    T: 'a,
 {
     let result = Arc::new(Mutex::new(None));
     let notify = rt::Notify::new(true, false);
 
-    let id = {
+    let body: Box<dyn FnOnce() + 'a> = {
         let name = name.clone();
         let result = result.clone();
-        rt::spawn(stack_size, move || {
+
+        Box::new(move || {
             rt::execution(|execution| {
                 init_current(execution, name);
             });
 
-            *result.lock().unwrap() = Some(Ok(f()));
+            let res = f();
+
+            // Thread-local destructors must happen-before `join` returns.
+            rt::drop_locals();
+
+            *result.lock().unwrap() = Some(Ok(res));
             notify.notify(location);
         })
     };
 
-    JoinHandle {
+    // Erase the lifetime of the borrowed data, see the safety section.
+    let body = mem::transmute::<Box<dyn FnOnce() + 'a>, Box<dyn FnOnce() + 'static>>(body);
+    let id = rt::spawn(stack_size, body);
+
+    JoinHandleInner {
         result,
         notify,
         thread: Thread {
@@ -202,6 +350,10 @@ impl Builder {
     }
 
     /// Sets the size of the stack (in bytes) for the new thread.
+    ///
+    /// Loom threads run on stacks allocated by loom, which honor the requested
+    /// size. Sizes smaller than the default stack size of loom threads, 32KiB
+    /// on 64-bit targets, are rounded up to it.
     pub fn stack_size(mut self, size: usize) -> Builder {
         self.stack_size = Some(size);
 
@@ -217,21 +369,163 @@ impl Builder {
         F: Send + 'static,
         T: Send + 'static,
     {
-        Ok(spawn_internal(f, self.name, self.stack_size, location!()))
+        // Safety: the closure and its result are `'static`.
+        let inner = unsafe { spawn_internal(f, self.name, self.stack_size, location!()) };
+
+        Ok(JoinHandle {
+            inner,
+            joined: false,
+        })
+    }
+
+    /// Spawns a new scoped thread by taking ownership of the `Builder`, and
+    /// returns an `io::Result` to its `ScopedJoinHandle`.
+    #[track_caller]
+    pub fn spawn_scoped<'scope, 'env, F, T>(
+        self,
+        scope: &'scope Scope<'scope, 'env>,
+        f: F,
+    ) -> io::Result<ScopedJoinHandle<'scope, T>>
+    where
+        F: FnOnce() -> T + Send + 'scope,
+        T: Send + 'scope,
+    {
+        Ok(scope.spawn_internal(f, self.name, self.stack_size, location!()))
+    }
+}
+
+impl<'scope, 'env> Scope<'scope, 'env> {
+    /// Spawns a new thread within the scope, returning a
+    /// [`ScopedJoinHandle`] for it.
+    ///
+    /// Unlike [`spawn`], the thread may borrow non-`'static` data from outside
+    /// the scope, and return borrowed data. It is joined at the end of the
+    /// scope if it was not joined before.
+    #[track_caller]
+    pub fn spawn<F, T>(&'scope self, f: F) -> ScopedJoinHandle<'scope, T>
+    where
+        F: FnOnce() -> T + Send + 'scope,
+        T: Send + 'scope,
+    {
+        self.spawn_internal(f, None, None, location!())
+    }
+
+    fn spawn_internal<F, T>(
+        &'scope self,
+        f: F,
+        name: Option<String>,
+        stack_size: Option<usize>,
+        location: Location,
+    ) -> ScopedJoinHandle<'scope, T>
+    where
+        F: FnOnce() -> T + Send + 'scope,
+        T: Send + 'scope,
+    {
+        let data = &self.data;
+
+        // A panic is recorded instead of failing the model right away, so the
+        // scope still waits for the other threads before propagating it.
+        let f = move || match panic::catch_unwind(AssertUnwindSafe(f)) {
+            Ok(ret) => Some(ret),
+            Err(payload) => {
+                let id = rt::execution(|execution| execution.threads.active_id());
+                data.panics.lock().unwrap().push((id, payload));
+                None
+            }
+        };
+
+        // Safety: `scope` waits for the thread before `'scope` ends.
+        let inner = unsafe { spawn_internal(f, name, stack_size, location) };
+
+        self.data
+            .running
+            .lock()
+            .unwrap()
+            .push((inner.thread.id.rt_id(), inner.notify));
+
+        ScopedJoinHandle {
+            inner,
+            scope: &self.data,
+        }
     }
 }
 
 impl<T> JoinHandle<T> {
     /// Waits for the associated thread to finish.
+    ///
+    /// The value returned by the thread, including a `Result`, is returned
+    /// as-is in `Ok`. Unlike `std`, a panic in the thread is not returned as an
+    /// `Err`, it fails the model instead.
+    #[track_caller]
+    pub fn join(mut self) -> std::thread::Result<T> {
+        self.joined = true;
+        self.inner.join(location!())
+    }
+
+    /// Gets a handle to the underlying [`Thread`]
+    pub fn thread(&self) -> &Thread {
+        &self.inner.thread
+    }
+}
+
+impl<T> ScopedJoinHandle<'_, T> {
+    /// Waits for the associated thread to finish.
+    ///
+    /// The value returned by the thread is returned as-is in `Ok`, as for
+    /// [`JoinHandle::join`]. A panic in the thread is propagated, failing the
+    /// model, instead of being propagated when the scope ends.
     #[track_caller]
     pub fn join(self) -> std::thread::Result<T> {
-        self.notify.wait(location!());
-        self.result.lock().unwrap().take().unwrap()
+        let id = self.inner.thread.id.rt_id();
+
+        // The scope must not wait for the thread again
+        self.scope
+            .running
+            .lock()
+            .unwrap()
+            .retain(|&(running, _)| running != id);
+
+        match self.inner.join(location!())? {
+            Some(ret) => Ok(ret),
+            None => {
+                let mut panics = self.scope.panics.lock().unwrap();
+                let i = panics.iter().position(|&(panicked, _)| panicked == id);
+                let (_, payload) = panics.remove(i.unwrap());
+                drop(panics);
+
+                panic::resume_unwind(payload)
+            }
+        }
     }
 
     /// Gets a handle to the underlying [`Thread`]
     pub fn thread(&self) -> &Thread {
-        &self.thread
+        &self.inner.thread
+    }
+}
+
+impl<T> JoinHandleInner<T> {
+    fn join(&self, location: Location) -> std::thread::Result<T> {
+        self.notify.wait(location);
+        self.result.lock().unwrap().take().unwrap()
+    }
+}
+
+impl<T> Drop for JoinHandle<T> {
+    fn drop(&mut self) {
+        if self.joined || std::thread::panicking() {
+            return;
+        }
+
+        // The thread keeps running; remember that nothing will wait for it.
+        // The handle may outlive the execution, or be dropped by a later one,
+        // which then has nothing to remember.
+        let id = self.inner.thread.id.rt_id();
+        rt::try_execution(|execution| {
+            if id.execution_id() == execution.threads.execution_id() {
+                execution.threads[id].detached = true;
+            }
+        });
     }
 }
 
@@ -241,10 +535,24 @@ impl<T: fmt::Debug> fmt::Debug for JoinHandle<T> {
     }
 }
 
+impl<T> fmt::Debug for ScopedJoinHandle<'_, T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("ScopedJoinHandle").finish_non_exhaustive()
+    }
+}
+
+impl fmt::Debug for Scope<'_, '_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Scope").finish_non_exhaustive()
+    }
+}
+
 fn _assert_traits() {
     fn assert<T: Send + Sync>() {}
 
     assert::<JoinHandle<()>>();
+    assert::<ScopedJoinHandle<'_, ()>>();
+    assert::<Scope<'_, '_>>();
 }
 
 impl<T: 'static> LocalKey<T> {
@@ -128,3 +128,308 @@ fn try_unwrap_multithreaded() {
         let _ = Arc::try_unwrap(num).unwrap();
     });
 }
+
+#[test]
+fn strong_count_racing_clone() {
+    use std::sync::atomic::AtomicBool as StdAtomicBool;
+    use std::sync::atomic::Ordering::Relaxed;
+
+    static SEEN_BEFORE: StdAtomicBool = StdAtomicBool::new(false);
+    static SEEN_AFTER: StdAtomicBool = StdAtomicBool::new(false);
+
+    loom::model(|| {
+        let num = Arc::new(0usize);
+        let num2 = num.clone();
+        let cloned = Arc::new(AtomicBool::new(false));
+        let cloned2 = cloned.clone();
+
+        let th = thread::spawn(move || {
+            let num3 = num2.clone();
+            cloned2.store(true, Release);
+            drop(num3);
+        });
+
+        let _ = cloned.load(Acquire);
+        match Arc::strong_count(&num) {
+            2 => SEEN_BEFORE.store(true, Relaxed),
+            3 => SEEN_AFTER.store(true, Relaxed),
+            // The spawned thread already dropped both of its references.
+            1 => {}
+            n => panic!("unexpected strong count {}", n),
+        }
+
+        th.join().unwrap();
+        assert_eq!(1, Arc::strong_count(&num));
+    });
+
+    assert!(SEEN_BEFORE.load(Relaxed));
+    assert!(SEEN_AFTER.load(Relaxed));
+}
+
+#[test]
+fn weak_count_is_zero() {
+    loom::model(|| {
+        let num = Arc::new(0usize);
+        assert_eq!(0, Arc::weak_count(&num));
+    });
+}
+
+#[test]
+fn slice_from_iter_shared_across_threads() {
+    loom::model(|| {
+        let nums: Arc<[u32]> = (1..=3).collect();
+
+        let th = {
+            let nums = nums.clone();
+            thread::spawn(move || nums.iter().sum::<u32>())
+        };
+
+        assert_eq!(6, th.join().unwrap());
+        assert_eq!([1, 2, 3], *nums);
+        assert_eq!(1, Arc::strong_count(&nums));
+    });
+}
+
+#[test]
+fn slice_from_vec() {
+    loom::model(|| {
+        let nums: Arc<[u32]> = Arc::from(vec![1, 2, 3]);
+        let nums2 = nums.clone();
+
+        assert_eq!(*nums, *nums2);
+        assert_eq!(2, Arc::strong_count(&nums));
+    });
+}
+
+#[test]
+fn downgrade_and_upgrade() {
+    loom::model(|| {
+        let num = Arc::new(1usize);
+        let weak = Arc::downgrade(&num);
+
+        assert_eq!(1, Arc::weak_count(&num));
+        assert_eq!(1, weak.strong_count());
+
+        let weak2 = weak.clone();
+        assert_eq!(2, weak.weak_count());
+        assert!(weak.ptr_eq(&weak2));
+
+        let upgraded = weak.upgrade().unwrap();
+        assert!(Arc::ptr_eq(&num, &upgraded));
+        assert_eq!(2, Arc::strong_count(&num));
+
+        drop((num, upgraded));
+
+        assert!(weak2.upgrade().is_none());
+        assert_eq!(0, weak.strong_count());
+        assert_eq!(0, weak.weak_count());
+    });
+}
+
+#[test]
+fn upgrade_races_with_last_drop() {
+    use std::sync::atomic::AtomicBool as StdAtomicBool;
+    use std::sync::atomic::Ordering::SeqCst;
+
+    static UPGRADED: StdAtomicBool = StdAtomicBool::new(false);
+    static DROPPED: StdAtomicBool = StdAtomicBool::new(false);
+
+    loom::model(|| {
+        let num = Arc::new(UnsafeCell::new(0));
+        let weak = Arc::downgrade(&num);
+
+        let th = thread::spawn(move || match weak.upgrade() {
+            Some(num) => {
+                UPGRADED.store(true, SeqCst);
+                num.with(|v| assert_eq!(0, unsafe { *v }));
+            }
+            None => DROPPED.store(true, SeqCst),
+        });
+
+        drop(num);
+        th.join().unwrap();
+    });
+
+    assert!(UPGRADED.load(SeqCst));
+    assert!(DROPPED.load(SeqCst));
+}
+
+#[test]
+#[should_panic(expected = "Weak leaked")]
+fn detect_weak_leak() {
+    loom::model(|| {
+        let num = Arc::new(0usize);
+        std::mem::forget(Arc::downgrade(&num));
+    });
+}
+
+#[test]
+#[should_panic(expected = "Arc leaked")]
+fn detect_arc_leak_with_weak() {
+    loom::model(|| {
+        let num = Arc::new(0usize);
+        let _weak = Arc::downgrade(&num);
+        std::mem::forget(num);
+    });
+}
+
+#[test]
+fn new_cyclic_two_node_cycle() {
+    use loom::sync::Weak;
+
+    struct Parent {
+        me: Weak<Parent>,
+        child: Arc<Child>,
+    }
+
+    struct Child {
+        parent: Weak<Parent>,
+    }
+
+    loom::model(|| {
+        let parent = Arc::new_cyclic(|me: &Weak<Parent>| {
+            // The `Arc` is not constructed yet
+            assert!(me.upgrade().is_none());
+            assert_eq!(0, me.strong_count());
+
+            Parent {
+                me: me.clone(),
+                child: Arc::new(Child { parent: me.clone() }),
+            }
+        });
+
+        assert_eq!(1, Arc::strong_count(&parent));
+        assert_eq!(2, Arc::weak_count(&parent));
+        assert!(Arc::ptr_eq(&parent, &parent.me.upgrade().unwrap()));
+
+        let child = parent.child.clone();
+
+        let th = thread::spawn(move || {
+            if let Some(parent) = child.parent.upgrade() {
+                assert!(Arc::ptr_eq(&parent.child, &child));
+            }
+        });
+
+        drop(parent);
+        th.join().unwrap();
+    });
+}
+
+#[test]
+fn make_mut_unique_does_not_clone() {
+    loom::model(|| {
+        let mut num = Arc::new(1);
+        let ptr = Arc::as_ptr(&num);
+
+        *Arc::make_mut(&mut num) += 1;
+
+        assert_eq!(2, *num);
+        assert_eq!(ptr, Arc::as_ptr(&num));
+    });
+}
+
+#[test]
+fn make_mut_races_with_drop() {
+    use std::sync::atomic::AtomicBool as StdAtomicBool;
+    use std::sync::atomic::Ordering::SeqCst;
+
+    static CLONED: StdAtomicBool = StdAtomicBool::new(false);
+    static UNIQUE: StdAtomicBool = StdAtomicBool::new(false);
+
+    loom::model(|| {
+        let mut num = Arc::new(1);
+        let num2 = num.clone();
+        let ptr = Arc::as_ptr(&num);
+
+        let th = thread::spawn(move || assert_eq!(1, *num2));
+
+        *Arc::make_mut(&mut num) += 1;
+
+        if Arc::as_ptr(&num) == ptr {
+            UNIQUE.store(true, SeqCst);
+        } else {
+            CLONED.store(true, SeqCst);
+        }
+
+        th.join().unwrap();
+        assert_eq!(2, *num);
+    });
+
+    assert!(CLONED.load(SeqCst));
+    assert!(UNIQUE.load(SeqCst));
+}
+
+#[test]
+fn make_mut_with_weak_moves() {
+    #[derive(Debug)]
+    struct NoClone(usize);
+
+    impl Clone for NoClone {
+        fn clone(&self) -> NoClone {
+            panic!("the value is moved, not cloned");
+        }
+    }
+
+    loom::model(|| {
+        let mut num = Arc::new(NoClone(1));
+        let weak = Arc::downgrade(&num);
+
+        Arc::make_mut(&mut num).0 += 1;
+
+        // The weak pointer refers to the previous allocation, which was released
+        assert!(weak.upgrade().is_none());
+        assert_eq!(0, weak.strong_count());
+        assert_eq!(0, Arc::weak_count(&num));
+        assert_eq!(2, num.0);
+    });
+}
+
+#[test]
+fn addr_eq_unsized() {
+    loom::model(|| {
+        let a: Arc<[u8]> = Arc::from_std(std::sync::Arc::from(&[1, 2, 3][..]));
+        let b: Arc<[u8]> = Arc::from_std(std::sync::Arc::from(&[1, 2, 3][..]));
+        let a2 = a.clone();
+
+        assert!(Arc::addr_eq(&a, &a2));
+        assert!(Arc::ptr_eq(&a, &a2));
+        assert!(!Arc::addr_eq(&a, &b));
+        assert!(!Arc::ptr_eq(&a, &b));
+    });
+}
+
+#[test]
+fn ptr_comparisons_are_not_branch_points() {
+    use std::sync::atomic::AtomicUsize as StdAtomicUsize;
+    use std::sync::atomic::Ordering::SeqCst;
+
+    fn executions(compare: bool) -> usize {
+        let count = std::sync::Arc::new(StdAtomicUsize::new(0));
+        let c = count.clone();
+
+        loom::model(move || {
+            c.fetch_add(1, SeqCst);
+
+            let a = Arc::new(1);
+            let a2 = a.clone();
+            let b = a.clone();
+            let b2 = a.clone();
+
+            let th = thread::spawn(move || {
+                if compare {
+                    assert!(Arc::ptr_eq(&b, &b2));
+                }
+            });
+
+            if compare {
+                assert!(Arc::addr_eq(&a, &a2));
+            }
+
+            th.join().unwrap();
+        });
+
+        count.load(SeqCst)
+    }
+
+    assert_eq!(executions(false), executions(true));
+}
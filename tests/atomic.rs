@@ -1,9 +1,12 @@
 #![deny(warnings, rust_2018_idioms)]
 
+use loom::cell::UnsafeCell;
 use loom::sync::atomic::AtomicUsize;
 use loom::thread;
 
-use std::sync::atomic::Ordering::{AcqRel, Acquire, Relaxed, Release};
+use std::sync::atomic::AtomicBool as StdAtomicBool;
+use std::sync::atomic::AtomicUsize as StdAtomicUsize;
+use std::sync::atomic::Ordering::{self, AcqRel, Acquire, Relaxed, Release, SeqCst};
 use std::sync::Arc;
 
 loom::lazy_static! {
@@ -79,6 +82,85 @@ fn invalid_unsync_load_relaxed() {
     });
 }
 
+#[test]
+#[should_panic(
+    expected = "no happens-before path exists between the atomic store of thread #1 \
+                           and the `unsync_load` of thread #0:\n    \
+                           the atomic store follows point 3 of thread #1, \
+                           but thread #0 only synchronized with thread #1 up to point 2"
+)]
+fn invalid_unsync_load_explains_missing_happens_before() {
+    loom::model(unsync_load_after_relaxed_flag);
+}
+
+#[test]
+fn invalid_unsync_load_names_synchronizing_operations() {
+    let res = std::panic::catch_unwind(|| {
+        let mut builder = loom::model::Builder::new();
+        builder.location = true;
+        builder.check(unsync_load_after_relaxed_flag);
+    });
+
+    let payload = res.expect_err("causality violation was not detected");
+    let msg = payload.downcast_ref::<String>().unwrap();
+
+    let expected = format!(
+        "but thread #0 only synchronized with thread #1 up to point 2,\n      \
+         released by the store at {}:",
+        file!()
+    );
+    assert!(msg.contains(&expected), "{}", msg);
+
+    let expected = format!("\n      and acquired by the load at {}:", file!());
+    assert!(msg.contains(&expected), "{}", msg);
+}
+
+/// The main thread synchronizes with the spawned thread through `ready`, but
+/// only observes the store to `a` through a `Relaxed` load of `flag`.
+fn unsync_load_after_relaxed_flag() {
+    let a = Arc::new(AtomicUsize::new(0));
+    let ready = Arc::new(AtomicUsize::new(0));
+    let flag = Arc::new(AtomicUsize::new(0));
+
+    let th = {
+        let a = a.clone();
+        let ready = ready.clone();
+        let flag = flag.clone();
+
+        thread::spawn(move || {
+            ready.store(1, Release);
+            a.store(1, Relaxed);
+            flag.store(1, Relaxed);
+        })
+    };
+
+    // Synchronizes with the thread, but only up to the `ready` store:
+    // reading `flag` with `Relaxed` does not synchronize with the store
+    // to `a`.
+    if ready.load(Acquire) == 1 && flag.load(Relaxed) == 1 {
+        unsafe { a.unsync_load() };
+    }
+
+    th.join().unwrap();
+}
+
+#[test]
+#[should_panic(expected = "atomic store: thread #0 @ tests/atomic.rs")]
+fn invalid_unsync_load_tracked_locations() {
+    loom::model(|| {
+        let a = Arc::new(AtomicUsize::new(0).track_locations());
+        let b = a.clone();
+
+        let thread = thread::spawn(move || {
+            unsafe { a.unsync_load() };
+        });
+
+        b.store(1, Relaxed);
+
+        thread.join().unwrap();
+    });
+}
+
 #[test]
 #[ignore]
 #[should_panic]
@@ -123,3 +205,1057 @@ fn fetch_add_atomic() {
         assert_ne!(v1, v2);
     });
 }
+
+fn count_counter_iterations(load: Ordering, rmw: Ordering, relaxed_init: bool) -> usize {
+    let iterations: &'static StdAtomicUsize = Box::leak(Box::new(StdAtomicUsize::new(0)));
+
+    loom::model(move || {
+        iterations.fetch_add(1, Relaxed);
+
+        let counter = Arc::new(AtomicUsize::new(0));
+
+        if relaxed_init {
+            // Happens-before every other access, so it does not change the
+            // possible outcomes, but the cell is no longer `SeqCst`-only.
+            counter.load(Relaxed);
+        }
+
+        let ths: Vec<_> = (0..2)
+            .map(|_| {
+                let counter = counter.clone();
+                thread::spawn(move || {
+                    counter.fetch_add(1, rmw);
+                })
+            })
+            .collect();
+
+        let first = counter.load(load);
+        let second = counter.load(load);
+        assert!(first <= second);
+
+        for th in ths {
+            th.join().unwrap();
+        }
+
+        assert_eq!(2, counter.load(load));
+    });
+
+    iterations.load(Relaxed)
+}
+
+#[test]
+fn seq_cst_only_loads_read_latest_store() {
+    let seq_cst = count_counter_iterations(SeqCst, SeqCst, false);
+    let mixed = count_counter_iterations(SeqCst, SeqCst, true);
+    let acq_rel = count_counter_iterations(Acquire, AcqRel, false);
+
+    assert!(seq_cst < mixed, "seq_cst = {seq_cst}; mixed = {mixed}");
+    assert!(mixed <= acq_rel, "mixed = {mixed}; acq_rel = {acq_rel}");
+}
+
+fn count_double_iterations(cas_loop: bool) -> usize {
+    let iterations: &'static StdAtomicUsize = Box::leak(Box::new(StdAtomicUsize::new(0)));
+
+    loom::model(move || {
+        iterations.fetch_add(1, Relaxed);
+
+        let num = Arc::new(AtomicUsize::new(1));
+
+        let ths: Vec<_> = (0..2)
+            .map(|_| {
+                let num = num.clone();
+                thread::spawn(move || {
+                    if cas_loop {
+                        let mut curr = num.load(Acquire);
+                        while let Err(actual) =
+                            num.compare_exchange_weak(curr, curr * 2, AcqRel, Acquire)
+                        {
+                            curr = actual;
+                        }
+                    } else {
+                        num.fetch_update(AcqRel, Acquire, |n| Some(n * 2)).unwrap();
+                    }
+                })
+            })
+            .collect();
+
+        for th in ths {
+            th.join().unwrap();
+        }
+
+        assert_eq!(4, num.load(Relaxed));
+    });
+
+    iterations.load(Relaxed)
+}
+
+#[test]
+fn fetch_update_does_not_fail_spuriously() {
+    let cas_loop = count_double_iterations(true);
+    let fetch_update = count_double_iterations(false);
+
+    // The weak loop also explores spurious failures
+    assert!(fetch_update < cas_loop, "{} >= {}", fetch_update, cas_loop);
+}
+
+#[test]
+fn fetch_update_retries_function() {
+    static MAX_CALLS: StdAtomicUsize = StdAtomicUsize::new(0);
+
+    loom::model(|| {
+        let num = Arc::new(AtomicUsize::new(1));
+        let calls = std::sync::Arc::new(StdAtomicUsize::new(0));
+
+        let ths: Vec<_> = (0..2)
+            .map(|_| {
+                let (num, calls) = (num.clone(), calls.clone());
+                thread::spawn(move || {
+                    num.fetch_update(AcqRel, Acquire, |n| {
+                        calls.fetch_add(1, Relaxed);
+                        Some(n * 2)
+                    })
+                    .unwrap();
+                })
+            })
+            .collect();
+
+        for th in ths {
+            th.join().unwrap();
+        }
+
+        assert_eq!(4, num.load(Relaxed));
+        MAX_CALLS.fetch_max(calls.load(Relaxed), Relaxed);
+    });
+
+    // Stale reads call the function again
+    assert!(MAX_CALLS.load(Relaxed) > 2);
+}
+
+const BIT_A: usize = 0b01;
+const BIT_B: usize = 0b10;
+
+struct Flags {
+    bits: AtomicUsize,
+    data_a: UnsafeCell<usize>,
+    data_b: UnsafeCell<usize>,
+}
+
+impl Flags {
+    fn publish(&self, bit: usize, order: Ordering) {
+        let data = if bit == BIT_A {
+            &self.data_a
+        } else {
+            &self.data_b
+        };
+        data.with_mut(|v| unsafe { *v = bit });
+        self.bits.fetch_or(bit, order);
+    }
+
+    fn observe(&self) {
+        let bits = self.bits.load(Acquire);
+
+        if bits & BIT_A != 0 {
+            self.data_a.with(|v| assert_eq!(BIT_A, unsafe { *v }));
+        }
+
+        if bits & BIT_B != 0 {
+            self.data_b.with(|v| assert_eq!(BIT_B, unsafe { *v }));
+        }
+    }
+}
+
+fn bitflags_model(order: Ordering) {
+    loom::model(move || {
+        let flags = loom::sync::Arc::new(Flags {
+            bits: AtomicUsize::new(0),
+            data_a: UnsafeCell::new(0),
+            data_b: UnsafeCell::new(0),
+        });
+
+        let ths: Vec<_> = [BIT_A, BIT_B]
+            .iter()
+            .map(|&bit| {
+                let flags = flags.clone();
+                thread::spawn(move || flags.publish(bit, order))
+            })
+            .collect();
+
+        flags.observe();
+
+        for th in ths {
+            th.join().unwrap();
+        }
+    });
+}
+
+#[test]
+fn fetch_or_bitflags_release_acquire() {
+    bitflags_model(Release);
+}
+
+#[test]
+#[should_panic]
+fn fetch_or_bitflags_relaxed() {
+    bitflags_model(Relaxed);
+}
+
+#[test]
+fn atomic_ptr_default_is_null() {
+    use loom::sync::atomic::{AtomicBool, AtomicPtr};
+
+    #[derive(Default)]
+    struct Node {
+        next: AtomicPtr<Node>,
+        flag: AtomicBool,
+        count: AtomicUsize,
+    }
+
+    loom::model(|| {
+        let node = Node::default();
+
+        assert!(node.next.load(Acquire).is_null());
+        assert!(!node.flag.load(Acquire));
+        assert_eq!(0, node.count.load(Acquire));
+    });
+}
+
+#[test]
+fn lazy_static_drop_performs_atomic_store() {
+    use std::sync::atomic::AtomicBool as StdAtomicBool;
+
+    static SEEN_BEFORE: StdAtomicBool = StdAtomicBool::new(false);
+    static SEEN_AFTER: StdAtomicBool = StdAtomicBool::new(false);
+
+    struct Holder {
+        dropped: loom::sync::Arc<AtomicUsize>,
+        data: loom::sync::Arc<UnsafeCell<usize>>,
+    }
+
+    impl Drop for Holder {
+        fn drop(&mut self) {
+            self.data.with_mut(|v| unsafe { *v = 1 });
+            self.dropped.store(1, Release);
+        }
+    }
+
+    loom::lazy_static! {
+        static ref HOLDER: Holder = Holder {
+            dropped: loom::sync::Arc::new(AtomicUsize::new(0)),
+            data: loom::sync::Arc::new(UnsafeCell::new(0)),
+        };
+    }
+
+    loom::model(|| {
+        let dropped = HOLDER.dropped.clone();
+        let data = HOLDER.data.clone();
+
+        // Not joined, so it may observe the drop of the lazy static.
+        thread::spawn(move || {
+            if dropped.load(Acquire) == 1 {
+                data.with(|v| assert_eq!(1, unsafe { *v }));
+                SEEN_AFTER.store(true, Relaxed);
+            } else {
+                SEEN_BEFORE.store(true, Relaxed);
+            }
+        });
+    });
+
+    assert!(SEEN_BEFORE.load(Relaxed));
+    assert!(SEEN_AFTER.load(Relaxed));
+}
+
+#[test]
+fn compare_and_swap_release_derives_relaxed_failure() {
+    loom::model(|| {
+        let num = AtomicUsize::new(1);
+
+        // Fails, which must not use `Release` as the failure ordering.
+        let prev = num.compare_and_swap(0, 2, Release);
+        assert_eq!(1, prev);
+        assert_eq!(Err(1), num.compare_exchange(0, 2, Release, Relaxed));
+
+        let prev = num.compare_and_swap(1, 2, Release);
+        assert_eq!(1, prev);
+        assert_eq!(2, num.load(Relaxed));
+    });
+}
+
+#[test]
+fn compare_and_swap_release_publishes() {
+    loom::model(|| {
+        let data = loom::sync::Arc::new(UnsafeCell::new(0));
+        let num = loom::sync::Arc::new(AtomicUsize::new(0));
+
+        let th = {
+            let data = data.clone();
+            let num = num.clone();
+            thread::spawn(move || {
+                data.with_mut(|v| unsafe { *v = 1 });
+                num.compare_and_swap(0, 1, Release);
+            })
+        };
+
+        let prev = num.compare_and_swap(1, 2, AcqRel);
+        if prev == 1 {
+            data.with(|v| assert_eq!(1, unsafe { *v }));
+        }
+
+        th.join().unwrap();
+    });
+}
+
+#[test]
+#[should_panic(expected = "there is no such thing as a release failure ordering")]
+fn compare_exchange_release_failure_ordering() {
+    loom::model(|| {
+        let num = AtomicUsize::new(0);
+        let _ = num.compare_exchange(0, 1, Release, Release);
+    });
+}
+
+#[test]
+fn compare_exchange_returns_prev_or_actual() {
+    loom::model(|| {
+        let num = AtomicUsize::new(1);
+
+        assert_eq!(Ok(1), num.compare_exchange(1, 2, AcqRel, Acquire));
+        assert_eq!(Err(2), num.compare_exchange(1, 3, AcqRel, Acquire));
+        assert_eq!(Err(2), num.compare_exchange_weak(1, 3, AcqRel, Acquire));
+
+        // A weak exchange either succeeds, or fails spuriously with the
+        // expected value.
+        match num.compare_exchange_weak(2, 3, AcqRel, Acquire) {
+            Ok(prev) => {
+                assert_eq!(2, prev);
+                assert_eq!(3, num.load(Relaxed));
+            }
+            Err(actual) => {
+                assert_eq!(2, actual);
+                assert_eq!(2, num.load(Relaxed));
+            }
+        }
+    });
+}
+
+#[test]
+fn compare_exchange_never_fails_spuriously() {
+    loom::model(|| {
+        let num = Arc::new(AtomicUsize::new(0));
+
+        let ths: Vec<_> = (1..3)
+            .map(|i| {
+                let num = num.clone();
+                thread::spawn(move || num.compare_exchange(0, i, AcqRel, Acquire))
+            })
+            .collect();
+
+        let res: Vec<_> = ths.into_iter().map(|th| th.join().unwrap()).collect();
+
+        // Exactly one exchange succeeds, the other one fails with the value
+        // stored by the first.
+        let winner = num.load(Acquire);
+        assert_eq!(1, res.iter().filter(|res| res.is_ok()).count());
+        assert!(res.contains(&Err(winner)));
+    });
+}
+
+#[test]
+fn compare_exchange_weak_fails_spuriously() {
+    static SPURIOUS: StdAtomicBool = StdAtomicBool::new(false);
+
+    loom::model(|| {
+        let num = AtomicUsize::new(0);
+
+        match num.compare_exchange_weak(0, 1, AcqRel, Acquire) {
+            Ok(prev) => assert_eq!(0, prev),
+            Err(actual) => {
+                assert_eq!(0, actual);
+                SPURIOUS.store(true, Relaxed);
+            }
+        }
+    });
+
+    assert!(SPURIOUS.load(Relaxed));
+}
+
+#[test]
+fn compare_exchange_weak_loop_converges() {
+    loom::model(|| {
+        let num = Arc::new(AtomicUsize::new(0));
+
+        let ths: Vec<_> = (0..2)
+            .map(|_| {
+                let num = num.clone();
+                thread::spawn(move || {
+                    let mut curr = num.load(Relaxed);
+                    while let Err(actual) =
+                        num.compare_exchange_weak(curr, curr + 1, AcqRel, Relaxed)
+                    {
+                        curr = actual;
+                    }
+                })
+            })
+            .collect();
+
+        for th in ths {
+            th.join().unwrap();
+        }
+
+        assert_eq!(2, num.load(Relaxed));
+    });
+}
+
+#[test]
+fn modification_count_per_thread() {
+    use std::collections::HashSet;
+    use std::sync::Mutex;
+
+    const ITEMS: usize = 3;
+
+    let seen: &'static Mutex<HashSet<(usize, usize)>> =
+        Box::leak(Box::new(Mutex::new(HashSet::new())));
+
+    loom::model(move || {
+        let next = loom::sync::Arc::new(AtomicUsize::new(0));
+
+        let ths: Vec<_> = (0..2)
+            .map(|_| {
+                let next = next.clone();
+                thread::spawn(move || {
+                    while next
+                        .fetch_update(
+                            AcqRel,
+                            Acquire,
+                            |n| if n < ITEMS { Some(n + 1) } else { None },
+                        )
+                        .is_ok()
+                    {}
+                })
+            })
+            .collect();
+
+        let ids: Vec<_> = ths.iter().map(|th| th.thread().id()).collect();
+
+        for th in ths {
+            th.join().unwrap();
+        }
+
+        let a = next.modification_count(ids[0]);
+        let b = next.modification_count(ids[1]);
+
+        assert_eq!(ITEMS, a + b);
+        assert_eq!(0, next.modification_count(thread::current().id()));
+
+        seen.lock().unwrap().insert((a, b));
+    });
+
+    let seen = seen.lock().unwrap();
+    // Both a fair and a fully unfair distribution are possible.
+    assert!(seen.contains(&(2, 1)) || seen.contains(&(1, 2)));
+    assert!(seen.contains(&(ITEMS, 0)));
+    assert!(seen.contains(&(0, ITEMS)));
+}
+
+fn release_chain(hop: Ordering) {
+    loom::model(move || {
+        let data = loom::sync::Arc::new(UnsafeCell::new(0));
+        let a = loom::sync::Arc::new(AtomicUsize::new(0));
+        let b = loom::sync::Arc::new(AtomicUsize::new(0));
+
+        let th1 = {
+            let (data, a) = (data.clone(), a.clone());
+            thread::spawn(move || {
+                data.with_mut(|v| unsafe { *v = 1 });
+                a.store(1, Release);
+            })
+        };
+
+        let th2 = {
+            let (a, b) = (a.clone(), b.clone());
+            thread::spawn(move || {
+                if a.load(Acquire) == 1 {
+                    b.fetch_add(1, hop);
+                }
+            })
+        };
+
+        let th3 = thread::spawn(move || {
+            if b.load(Acquire) == 1 {
+                data.with(|v| assert_eq!(1, unsafe { *v }));
+            }
+        });
+
+        th1.join().unwrap();
+        th2.join().unwrap();
+        th3.join().unwrap();
+    });
+}
+
+#[test]
+fn release_acquire_chain_across_atomics() {
+    release_chain(Release);
+}
+
+#[test]
+#[should_panic]
+fn relaxed_hop_breaks_release_acquire_chain() {
+    release_chain(Relaxed);
+}
+
+#[test]
+fn debug_formats_current_value_like_std() {
+    use loom::sync::atomic::AtomicBool;
+    use std::sync::atomic::AtomicBool as StdAtomicBool;
+
+    #[derive(Debug)]
+    struct Counters {
+        hits: AtomicUsize,
+        closed: AtomicBool,
+    }
+
+    loom::model(|| {
+        let counters = Counters {
+            hits: AtomicUsize::new(0),
+            closed: AtomicBool::new(false),
+        };
+
+        counters.hits.fetch_add(7, Relaxed);
+        counters.closed.store(true, Relaxed);
+
+        assert_eq!(
+            format!("{:?}", StdAtomicUsize::new(7)),
+            format!("{:?}", counters.hits)
+        );
+        assert_eq!(
+            format!("{:?}", StdAtomicBool::new(true)),
+            format!("{:?}", counters.closed)
+        );
+        assert_eq!(
+            "Counters { hits: 7, closed: true }",
+            format!("{:?}", counters)
+        );
+    });
+}
+
+/// A `Release` store of `10` to `flag` publishes `data`, then two threads
+/// modify `flag` with relaxed RMWs, or relaxed stores when `rmw` is `false`.
+/// The main thread reads `data` if it observes a value written after the
+/// `Release` store.
+fn release_sequence_model(rmw: bool) {
+    static READ_LATER_VALUE: StdAtomicBool = StdAtomicBool::new(false);
+
+    let mut builder = loom::model::Builder::new();
+    // Bounded to keep the four threads fast enough to check
+    builder.preemption_bound = Some(2);
+
+    builder.check(move || {
+        let flag = loom::sync::Arc::new(AtomicUsize::new(0));
+        let data = loom::sync::Arc::new(UnsafeCell::new(0));
+
+        let publisher = {
+            let (flag, data) = (flag.clone(), data.clone());
+
+            thread::spawn(move || {
+                data.with_mut(|ptr| unsafe { *ptr = 1 });
+                flag.store(10, Release);
+            })
+        };
+
+        let ths: Vec<_> = (0..2)
+            .map(|_| {
+                let flag = flag.clone();
+
+                thread::spawn(move || {
+                    if rmw {
+                        flag.fetch_add(1, Relaxed);
+                    } else if flag.load(Relaxed) >= 10 {
+                        flag.store(20, Relaxed);
+                    }
+                })
+            })
+            .collect();
+
+        let value = flag.load(Acquire);
+
+        if value >= 10 {
+            data.with(|ptr| assert_eq!(1, unsafe { *ptr }));
+        }
+
+        if value > 10 {
+            READ_LATER_VALUE.store(true, SeqCst);
+        }
+
+        publisher.join().unwrap();
+
+        for th in ths {
+            th.join().unwrap();
+        }
+    });
+
+    // The main thread read a value written after the `Release` store
+    assert!(READ_LATER_VALUE.load(SeqCst));
+}
+
+#[test]
+fn release_sequence_extends_through_relaxed_rmw() {
+    release_sequence_model(true);
+}
+
+#[test]
+#[should_panic(expected = "Causality violation")]
+fn relaxed_store_breaks_release_sequence() {
+    release_sequence_model(false);
+}
+
+/// Returns `order`, hiding it from the compiler as if it was chosen at runtime.
+fn runtime(order: Ordering) -> Ordering {
+    std::hint::black_box(order)
+}
+
+#[test]
+fn runtime_orderings() {
+    loom::model(|| {
+        let num = AtomicUsize::new(0);
+
+        for order in [Relaxed, Release, SeqCst] {
+            num.store(1, runtime(order));
+        }
+
+        for order in [Relaxed, Acquire, SeqCst] {
+            assert_eq!(1, num.load(runtime(order)));
+        }
+
+        for order in [Acquire, Release, AcqRel, SeqCst] {
+            loom::sync::atomic::fence(runtime(order));
+        }
+    });
+}
+
+#[test]
+#[should_panic(expected = "there is no such thing as a release load")]
+fn runtime_release_load() {
+    loom::model(|| {
+        AtomicUsize::new(0).load(runtime(Release));
+    });
+}
+
+#[test]
+#[should_panic(expected = "there is no such thing as an acquire-release store")]
+fn runtime_acq_rel_store() {
+    loom::model(|| {
+        AtomicUsize::new(0).store(1, runtime(AcqRel));
+    });
+}
+
+#[test]
+#[should_panic(expected = "there is no such thing as a release failure ordering")]
+fn runtime_release_failure_ordering() {
+    loom::model(|| {
+        let _ = AtomicUsize::new(0).compare_exchange(0, 1, SeqCst, runtime(Release));
+    });
+}
+
+#[test]
+#[should_panic(expected = "there is no such thing as a relaxed fence")]
+fn runtime_relaxed_fence() {
+    loom::model(|| {
+        loom::sync::atomic::fence(runtime(Relaxed));
+    });
+}
+
+/// Advances `clock` to `time`, unless it is already past it. The value loaded
+/// may be stale when it is stored, winding the clock backward.
+fn advance_racy(clock: &AtomicUsize, time: usize) {
+    if clock.load(SeqCst) < time {
+        clock.store(time, SeqCst);
+    }
+}
+
+fn advance(clock: &AtomicUsize, time: usize) {
+    let mut curr = clock.load(SeqCst);
+
+    while curr < time {
+        match clock.compare_exchange(curr, time, SeqCst, SeqCst) {
+            Ok(_) => return,
+            Err(actual) => curr = actual,
+        }
+    }
+}
+
+fn advance_concurrently(advance: fn(&AtomicUsize, usize)) {
+    let clock = Arc::new(AtomicUsize::new(0).assert_monotonic());
+
+    let ths: Vec<_> = [1, 2]
+        .iter()
+        .map(|&time| {
+            let clock = clock.clone();
+            thread::spawn(move || advance(&clock, time))
+        })
+        .collect();
+
+    for th in ths {
+        th.join().unwrap();
+    }
+
+    assert_eq!(2, clock.load(SeqCst));
+}
+
+#[test]
+fn monotonic_compare_exchange() {
+    loom::model(|| advance_concurrently(advance));
+}
+
+#[test]
+fn monotonic_stale_store_reports_location() {
+    let res = std::panic::catch_unwind(|| {
+        loom::model(|| advance_concurrently(advance_racy));
+    });
+
+    let payload = res.expect_err("stale store was not detected");
+    let msg = payload.downcast_ref::<String>().unwrap();
+
+    assert!(
+        msg.contains("Monotonicity violation: atomic value decreased from 2 to 1."),
+        "{}",
+        msg
+    );
+    assert!(msg.contains("store: thread #"), "{}", msg);
+    assert!(msg.contains(file!()), "{}", msg);
+}
+
+#[test]
+#[should_panic(expected = "Monotonicity violation: atomic value decreased from 1 to 0.")]
+fn monotonic_fetch_sub() {
+    loom::model(|| {
+        let num = AtomicUsize::new(0).assert_monotonic();
+        num.fetch_add(1, Relaxed);
+        num.fetch_sub(1, Relaxed);
+    });
+}
+
+/// Stores each of `values` to `num`, checking that the following load never
+/// returns an older store of the current thread.
+fn store_then_load(num: &AtomicUsize, values: &[usize], order: Ordering) {
+    for (i, &value) in values.iter().enumerate() {
+        num.store(value, order);
+
+        let loaded = num.load(order);
+        assert!(
+            loaded == value || !values[..i].contains(&loaded) && loaded != 0,
+            "stored {} then loaded {}",
+            value,
+            loaded
+        );
+    }
+}
+
+#[test]
+fn load_reads_own_store() {
+    loom::model(|| {
+        let num = Arc::new(AtomicUsize::new(0));
+
+        let th = {
+            let num = num.clone();
+            thread::spawn(move || store_then_load(&num, &[5], Relaxed))
+        };
+
+        store_then_load(&num, &[6], Relaxed);
+        th.join().unwrap();
+    });
+}
+
+#[test]
+fn load_reads_own_stores_sequence() {
+    static READ_OTHER: StdAtomicBool = StdAtomicBool::new(false);
+
+    for order in [Relaxed, SeqCst] {
+        READ_OTHER.store(false, SeqCst);
+
+        loom::model(move || {
+            let num = Arc::new(AtomicUsize::new(0));
+
+            let th = {
+                let num = num.clone();
+                thread::spawn(move || store_then_load(&num, &[1, 2, 3], order))
+            };
+
+            num.store(10, order);
+            num.store(20, order);
+
+            // The load reads this thread's latest store, or a store of the
+            // other thread ordered after it
+            let loaded = num.load(order);
+            assert!(loaded == 20 || [1, 2, 3].contains(&loaded), "{}", loaded);
+
+            if loaded != 20 {
+                READ_OTHER.store(true, SeqCst);
+            }
+
+            th.join().unwrap();
+        });
+
+        // Loads do observe the later stores of the other thread
+        assert!(READ_OTHER.load(SeqCst));
+    }
+}
+
+#[test]
+fn rmw_reads_own_store() {
+    loom::model(|| {
+        let num = Arc::new(AtomicUsize::new(0));
+
+        let th = {
+            let num = num.clone();
+            thread::spawn(move || num.store(5, Relaxed))
+        };
+
+        num.store(1, Relaxed);
+
+        // A read-modify-write reads the newest store, never an older one
+        let prev = num.fetch_add(1, Relaxed);
+        assert!(prev == 1 || prev == 5, "{}", prev);
+
+        th.join().unwrap();
+    });
+}
+
+#[test]
+fn bool_fetch_not() {
+    use loom::sync::atomic::AtomicBool;
+
+    loom::model(|| {
+        let flag = Arc::new(AtomicBool::new(false));
+
+        let th = {
+            let flag = flag.clone();
+            thread::spawn(move || flag.fetch_not(Relaxed))
+        };
+
+        let prev = flag.fetch_not(Relaxed);
+        let th_prev = th.join().unwrap();
+
+        // Both flips read the newest value, so they never read the same one
+        assert_ne!(prev, th_prev);
+        assert!(!flag.load(Relaxed));
+    });
+}
+
+#[test]
+fn bool_fetch_update_claims_once() {
+    use loom::sync::atomic::AtomicBool;
+
+    static FIRST_CLAIMED: StdAtomicBool = StdAtomicBool::new(false);
+    static SECOND_CLAIMED: StdAtomicBool = StdAtomicBool::new(false);
+
+    loom::model(|| {
+        let flag = Arc::new(AtomicBool::new(false));
+
+        let ths: Vec<_> = (0..2)
+            .map(|_| {
+                let flag = flag.clone();
+
+                thread::spawn(move || {
+                    // Claims the flag, unless it is already set
+                    flag.fetch_update(AcqRel, Acquire, |set| if set { None } else { Some(true) })
+                })
+            })
+            .collect();
+
+        let results: Vec<_> = ths.into_iter().map(|th| th.join().unwrap()).collect();
+
+        // Exactly one thread claims the flag
+        match results[..] {
+            [Ok(false), Err(true)] => FIRST_CLAIMED.store(true, SeqCst),
+            [Err(true), Ok(false)] => SECOND_CLAIMED.store(true, SeqCst),
+            _ => panic!("unexpected results {:?}", results),
+        }
+    });
+
+    assert!(FIRST_CLAIMED.load(SeqCst));
+    assert!(SECOND_CLAIMED.load(SeqCst));
+}
+
+#[test]
+fn atomic_ptr_treiber_stack() {
+    use loom::sync::atomic::AtomicPtr;
+    use std::ptr;
+
+    struct Node {
+        value: usize,
+        next: *mut Node,
+    }
+
+    struct Stack {
+        head: AtomicPtr<Node>,
+    }
+
+    // Nodes are only freed once no thread uses the stack anymore
+    unsafe impl Send for Stack {}
+    unsafe impl Sync for Stack {}
+
+    impl Stack {
+        fn push(&self, value: usize) {
+            let node = Box::into_raw(Box::new(Node {
+                value,
+                next: ptr::null_mut(),
+            }));
+
+            self.head
+                .fetch_update(Release, Relaxed, |head| {
+                    unsafe { (*node).next = head };
+                    Some(node)
+                })
+                .unwrap();
+        }
+
+        fn pop(&self) -> Option<*mut Node> {
+            self.head
+                .fetch_update(Acquire, Acquire, |head| {
+                    if head.is_null() {
+                        None
+                    } else {
+                        Some(unsafe { (*head).next })
+                    }
+                })
+                .ok()
+        }
+    }
+
+    loom::model(|| {
+        let stack = Arc::new(Stack {
+            head: AtomicPtr::new(ptr::null_mut()),
+        });
+
+        let ths: Vec<_> = (1..=2)
+            .map(|value| {
+                let stack = stack.clone();
+                thread::spawn(move || stack.push(value))
+            })
+            .collect();
+
+        let mut popped = vec![];
+        popped.extend(stack.pop());
+
+        for th in ths {
+            th.join().unwrap();
+        }
+
+        while let Some(node) = stack.pop() {
+            popped.push(node);
+        }
+
+        let mut values: Vec<_> = popped
+            .into_iter()
+            .map(|node| unsafe { Box::from_raw(node) }.value)
+            .collect();
+        values.sort();
+
+        assert_eq!(vec![1, 2], values);
+    });
+}
+
+#[test]
+fn atomic_ptr_fetch_add_and_sub() {
+    use loom::sync::atomic::AtomicPtr;
+
+    loom::model(|| {
+        let slots = Arc::new([0u32; 4]);
+        let base = slots.as_ptr() as *mut u32;
+        let cursor = Arc::new(AtomicPtr::new(base));
+
+        let th = {
+            let cursor = cursor.clone();
+            thread::spawn(move || cursor.fetch_ptr_add(1, Relaxed) as usize)
+        };
+
+        let slot = cursor.fetch_ptr_add(1, Relaxed) as usize;
+        let th_slot = th.join().unwrap();
+
+        // Each thread claims a different slot
+        let mut claimed = [slot, th_slot];
+        claimed.sort();
+        assert_eq!([base as usize, base.wrapping_add(1) as usize], claimed);
+
+        // Tag the pointer in its low bit, then clear the tag
+        assert_eq!(base.wrapping_add(2), cursor.fetch_byte_add(1, Relaxed));
+        assert_eq!(
+            base as usize + 2 * 4 + 1,
+            cursor.fetch_byte_sub(1, Relaxed) as usize
+        );
+
+        assert_eq!(base.wrapping_add(2), cursor.fetch_ptr_sub(0, Relaxed));
+        assert_eq!(base.wrapping_add(2), cursor.fetch_ptr_sub(2, Relaxed));
+        assert_eq!(base, cursor.load(Relaxed));
+    });
+}
+
+/// Publishes `data` through a pointer, after writing to both `data` and
+/// `flag`. The reader only reads `flag` if `read_flag`, assuming it was
+/// published with the pointer.
+fn consume_model(consume: bool, read_flag: bool) {
+    use loom::sync::atomic::AtomicPtr;
+
+    struct Shared {
+        data: UnsafeCell<usize>,
+        flag: UnsafeCell<usize>,
+        ptr: AtomicPtr<UnsafeCell<usize>>,
+    }
+
+    // The cells are synchronized by the model
+    unsafe impl Sync for Shared {}
+
+    loom::model(move || {
+        let shared = Arc::new(Shared {
+            data: UnsafeCell::new(0),
+            flag: UnsafeCell::new(0),
+            ptr: AtomicPtr::new(std::ptr::null_mut()),
+        });
+
+        let th = {
+            let shared = shared.clone();
+            thread::spawn(move || {
+                shared.flag.with_mut(|v| unsafe { *v = 1 });
+                shared.data.with_mut(|v| unsafe { *v = 1 });
+                shared
+                    .ptr
+                    .store(&shared.data as *const _ as *mut _, Release);
+            })
+        };
+
+        let read = |p: *mut UnsafeCell<usize>| match unsafe { p.as_ref() } {
+            Some(data) => {
+                assert_eq!(1, data.with(|v| unsafe { *v }));
+                true
+            }
+            None => false,
+        };
+
+        let published = if consume {
+            shared.ptr.load_consume(read)
+        } else {
+            read(shared.ptr.load(Acquire))
+        };
+
+        if published && read_flag {
+            assert_eq!(1, shared.flag.with(|v| unsafe { *v }));
+        }
+
+        th.join().unwrap();
+    });
+}
+
+#[test]
+fn load_consume_orders_dependent_accesses() {
+    consume_model(true, false);
+}
+
+#[test]
+fn load_acquire_orders_independent_accesses() {
+    consume_model(false, true);
+}
+
+#[test]
+#[should_panic(expected = "Causality violation")]
+fn load_consume_does_not_order_independent_accesses() {
+    consume_model(true, true);
+}
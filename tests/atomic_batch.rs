@@ -0,0 +1,86 @@
+#![deny(warnings, rust_2018_idioms)]
+
+use loom::sync::atomic::AtomicUsize;
+use loom::sync::{Arc, Mutex};
+use loom::thread;
+
+use std::collections::HashSet;
+use std::sync::atomic::Ordering::SeqCst;
+
+/// Returns the values observed by the main thread while another thread
+/// increments a counter twice, and the number of iterations.
+fn observed(batch: bool) -> (HashSet<usize>, usize) {
+    let seen = std::sync::Arc::new(std::sync::Mutex::new((HashSet::new(), 0)));
+
+    loom::model({
+        let seen = seen.clone();
+
+        move || {
+            let num = Arc::new(AtomicUsize::new(0));
+
+            let th = {
+                let num = num.clone();
+                thread::spawn(move || {
+                    let incr = || {
+                        num.fetch_add(1, SeqCst);
+                        num.fetch_add(1, SeqCst);
+                    };
+
+                    if batch {
+                        loom::atomic_batch(incr);
+                    } else {
+                        incr();
+                    }
+                })
+            };
+
+            let value = num.load(SeqCst);
+            th.join().unwrap();
+
+            let mut seen = seen.lock().unwrap();
+            seen.0.insert(value);
+            seen.1 += 1;
+        }
+    });
+
+    let seen = seen.lock().unwrap();
+    (seen.0.clone(), seen.1)
+}
+
+#[test]
+fn batch_hides_intermediate_values() {
+    let (values, iterations) = observed(false);
+    assert_eq!(HashSet::from([0, 1, 2]), values);
+
+    let (batched_values, batched_iterations) = observed(true);
+    assert_eq!(HashSet::from([0, 2]), batched_values);
+    assert!(batched_iterations < iterations);
+}
+
+#[test]
+fn batch_returns_value() {
+    loom::model(|| {
+        let num = AtomicUsize::new(1);
+        let prev = loom::atomic_batch(|| num.fetch_add(1, SeqCst));
+
+        assert_eq!(1, prev);
+        assert_eq!(2, num.load(SeqCst));
+    });
+}
+
+#[test]
+fn batch_blocking_on_mutex_lets_other_threads_run() {
+    loom::model(|| {
+        let lock = Arc::new(Mutex::new(0));
+
+        let th = {
+            let lock = lock.clone();
+            thread::spawn(move || *lock.lock().unwrap() += 1)
+        };
+
+        loom::atomic_batch(|| *lock.lock().unwrap() += 1);
+        th.join().unwrap();
+
+        assert_eq!(2, *lock.lock().unwrap());
+    });
+}
@@ -92,6 +92,45 @@ macro_rules! test_int {
                     assert_eq!(b, atomic.load(SeqCst));
                 });
             }
+
+            #[test]
+            fn fetch_add_wraps_around() {
+                use loom::sync::Arc;
+                use loom::thread;
+                use std::sync::atomic::AtomicBool;
+
+                // Whether the spawned thread observed the wraparound
+                static SPAWNED_WRAPPED: AtomicBool = AtomicBool::new(false);
+                static MAIN_WRAPPED: AtomicBool = AtomicBool::new(false);
+
+                loom::model(|| {
+                    let atomic = Arc::new(<$atomic>::new(<$int>::MAX - 1));
+
+                    let th = {
+                        let atomic = atomic.clone();
+                        thread::spawn(move || atomic.fetch_add(1, SeqCst))
+                    };
+
+                    let main = atomic.fetch_add(1, SeqCst);
+                    let spawned = th.join().unwrap();
+
+                    // One increment reaches `MAX`, the other wraps around
+                    match (main, spawned) {
+                        (a, b) if a == <$int>::MAX - 1 && b == <$int>::MAX => {
+                            SPAWNED_WRAPPED.store(true, SeqCst)
+                        }
+                        (a, b) if a == <$int>::MAX && b == <$int>::MAX - 1 => {
+                            MAIN_WRAPPED.store(true, SeqCst)
+                        }
+                        res => panic!("unexpected results {:?}", res),
+                    }
+
+                    assert_eq!(<$int>::MIN, atomic.load(SeqCst));
+                });
+
+                assert!(SPAWNED_WRAPPED.load(SeqCst));
+                assert!(MAIN_WRAPPED.load(SeqCst));
+            }
         }
     };
 }
@@ -106,8 +145,69 @@ test_int!(atomic_i16, i16, AtomicI16);
 test_int!(atomic_i32, i32, AtomicI32);
 test_int!(atomic_isize, isize, AtomicIsize);
 
-#[cfg(target_pointer_width = "64")]
 test_int!(atomic_u64, u64, AtomicU64);
-
-#[cfg(target_pointer_width = "64")]
 test_int!(atomic_i64, i64, AtomicI64);
+
+mod u64_pair {
+    use loom::sync::atomic::AtomicU64Pair;
+    use loom::sync::Arc;
+    use loom::thread;
+    use std::sync::atomic::Ordering::{AcqRel, Acquire, Release};
+
+    #[test]
+    fn concurrent_half_updates() {
+        loom::model(|| {
+            let pair = Arc::new(AtomicU64Pair::new(1, 2));
+
+            let th = {
+                let pair = pair.clone();
+                thread::spawn(move || {
+                    pair.fetch_update_hi(AcqRel, Acquire, |hi| Some(hi + 10))
+                        .unwrap();
+                })
+            };
+
+            assert_eq!(
+                pair.fetch_update_lo(AcqRel, Acquire, |lo| Some(lo + 10)),
+                Ok(1)
+            );
+
+            th.join().unwrap();
+
+            assert_eq!(
+                pair.load(Acquire),
+                (11, 12),
+                "the update of the high half was lost"
+            );
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "the update of the high half was lost")]
+    fn non_atomic_half_update_loses_write() {
+        loom::model(|| {
+            let pair = Arc::new(AtomicU64Pair::new(1, 2));
+
+            let th = {
+                let pair = pair.clone();
+                thread::spawn(move || {
+                    pair.fetch_update_hi(AcqRel, Acquire, |hi| Some(hi + 10))
+                        .unwrap();
+                })
+            };
+
+            // Buggy: read the whole word and write it back with the low half
+            // modified.
+            let (lo, hi) = pair.load(Acquire);
+            pair.store(lo + 10, hi, Release);
+
+            th.join().unwrap();
+
+            assert_eq!(
+                pair.load(Acquire),
+                (11, 12),
+                "the update of the high half was lost"
+            );
+        });
+    }
+}
@@ -0,0 +1,52 @@
+#![deny(warnings, rust_2018_idioms)]
+
+use loom::sync::{Arc, AtomicOptionUsize};
+use loom::thread;
+
+use std::sync::atomic::Ordering::{AcqRel, Acquire, Release};
+
+#[test]
+fn basic_usage() {
+    loom::model(|| {
+        let opt = AtomicOptionUsize::new(None);
+        assert_eq!(None, opt.load(Acquire));
+
+        opt.store(Some(0), Release);
+        assert_eq!(Some(0), opt.load(Acquire));
+
+        assert_eq!(Some(0), opt.replace(Some(5), AcqRel));
+        assert_eq!(Some(5), opt.take(AcqRel));
+        assert_eq!(None, opt.take(AcqRel));
+        assert_eq!(None, opt.into_inner());
+    });
+}
+
+#[test]
+#[should_panic]
+fn usize_max_cannot_be_stored() {
+    loom::model(|| {
+        AtomicOptionUsize::new(Some(usize::MAX));
+    });
+}
+
+#[test]
+fn racing_take() {
+    loom::model(|| {
+        let opt = Arc::new(AtomicOptionUsize::new(Some(7)));
+
+        let ths: Vec<_> = (0..2)
+            .map(|_| {
+                let opt = opt.clone();
+                thread::spawn(move || opt.take(AcqRel))
+            })
+            .collect();
+
+        let taken: Vec<_> = ths
+            .into_iter()
+            .filter_map(|th| th.join().unwrap())
+            .collect();
+
+        assert_eq!(vec![7], taken);
+        assert_eq!(None, opt.load(Acquire));
+    });
+}
@@ -0,0 +1,42 @@
+#![deny(warnings, rust_2018_idioms)]
+
+use loom::cell::UnsafeCell;
+use loom::sync::Arc;
+use loom::thread;
+
+fn write_cell(cell: &UnsafeCell<usize>) {
+    cell.with_mut(|v| unsafe { *v += 1 });
+}
+
+#[test]
+fn causality_violation_includes_backtrace() {
+    // Only read once per process, so this must be the only test in this file.
+    // With `RUST_BACKTRACE`, the panic hook prints the backtrace instead.
+    std::env::set_var("RUST_LIB_BACKTRACE", "1");
+    std::env::remove_var("RUST_BACKTRACE");
+
+    let res = std::panic::catch_unwind(|| {
+        loom::model(|| {
+            let cell = Arc::new(UnsafeCell::new(0));
+
+            let th = {
+                let cell = cell.clone();
+                thread::spawn(move || write_cell(&cell))
+            };
+
+            write_cell(&cell);
+
+            th.join().unwrap();
+        });
+    });
+
+    let err = res.unwrap_err();
+    let msg = err
+        .downcast_ref::<String>()
+        .expect("causality violations panic with a `String`");
+
+    // Frames of the model itself may be inlined in release builds, so only
+    // check that the section lists frames.
+    assert_eq!(1, msg.matches("\n\nbacktrace:\n").count(), "{}", msg);
+    assert!(msg.contains("   0: "), "{}", msg);
+}
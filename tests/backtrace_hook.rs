@@ -0,0 +1,46 @@
+#![deny(warnings, rust_2018_idioms)]
+
+use loom::cell::UnsafeCell;
+use loom::sync::Arc;
+use loom::thread;
+
+use std::process::Command;
+
+/// Fails with a causality violation. Run in a child process by
+/// `hook_prints_backtrace_once`, as backtraces are configured once per
+/// process.
+#[test]
+#[ignore]
+fn causality_violation() {
+    loom::model(|| {
+        let cell = Arc::new(UnsafeCell::new(0));
+
+        let th = {
+            let cell = cell.clone();
+            thread::spawn(move || cell.with_mut(|v| unsafe { *v += 1 }))
+        };
+
+        cell.with_mut(|v| unsafe { *v += 1 });
+
+        th.join().unwrap();
+    });
+}
+
+#[test]
+fn hook_prints_backtrace_once() {
+    let output = Command::new(std::env::current_exe().unwrap())
+        .args(["causality_violation", "--exact", "--ignored", "--nocapture"])
+        .env("RUST_BACKTRACE", "1")
+        .env_remove("RUST_LIB_BACKTRACE")
+        .output()
+        .unwrap();
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(!output.status.success(), "{}", stderr);
+    assert!(stderr.contains("Causality violation"), "{}", stderr);
+
+    // Printed by the panic hook, not included in the message
+    assert_eq!(1, stderr.matches("stack backtrace:").count(), "{}", stderr);
+    assert!(!stderr.contains("\nbacktrace:\n"), "{}", stderr);
+}
@@ -0,0 +1,106 @@
+#![deny(warnings, rust_2018_idioms)]
+
+use loom::cell::UnsafeCell;
+use loom::sync::{Arc, Barrier};
+use loom::thread;
+
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::SeqCst;
+
+#[test]
+fn barrier_one_leader() {
+    static SPAWNED_LEADER: AtomicBool = AtomicBool::new(false);
+    static MAIN_LEADER: AtomicBool = AtomicBool::new(false);
+
+    loom::model(|| {
+        let barrier = Arc::new(Barrier::new(2));
+
+        let th = {
+            let barrier = barrier.clone();
+            thread::spawn(move || barrier.wait().is_leader())
+        };
+
+        let leader = barrier.wait().is_leader();
+        let th_leader = th.join().unwrap();
+
+        // Exactly one thread is the leader
+        assert_ne!(leader, th_leader);
+
+        if leader {
+            MAIN_LEADER.store(true, SeqCst);
+        } else {
+            SPAWNED_LEADER.store(true, SeqCst);
+        }
+    });
+
+    // Either thread may be the leader
+    assert!(MAIN_LEADER.load(SeqCst));
+    assert!(SPAWNED_LEADER.load(SeqCst));
+}
+
+#[test]
+fn barrier_one_leader_per_generation() {
+    loom::model(|| {
+        let barrier = Arc::new(Barrier::new(2));
+
+        let th = {
+            let barrier = barrier.clone();
+
+            thread::spawn(move || {
+                let first = barrier.wait().is_leader();
+                let second = barrier.wait().is_leader();
+                (first, second)
+            })
+        };
+
+        let first = barrier.wait().is_leader();
+        let second = barrier.wait().is_leader();
+        let (th_first, th_second) = th.join().unwrap();
+
+        assert_ne!(first, th_first);
+        assert_ne!(second, th_second);
+    });
+}
+
+#[test]
+fn barrier_synchronizes() {
+    loom::model(|| {
+        let barrier = Arc::new(Barrier::new(2));
+        let data = Arc::new(UnsafeCell::new(0));
+
+        let th = {
+            let barrier = barrier.clone();
+            let data = data.clone();
+
+            thread::spawn(move || {
+                data.with_mut(|v| unsafe { *v = 1 });
+                barrier.wait();
+            })
+        };
+
+        barrier.wait();
+
+        // The write happens-before the main thread leaves the barrier
+        data.with(|v| assert_eq!(1, unsafe { *v }));
+
+        th.join().unwrap();
+    });
+}
+
+#[test]
+fn barrier_of_one_does_not_block() {
+    loom::model(|| {
+        let barrier = Barrier::new(1);
+        assert!(barrier.wait().is_leader());
+        assert!(barrier.wait().is_leader());
+    });
+}
+
+#[test]
+#[should_panic(expected = "deadlock")]
+fn barrier_missing_thread_deadlocks() {
+    loom::model(|| {
+        let barrier = Barrier::new(2);
+        barrier.wait();
+    });
+}
@@ -0,0 +1,66 @@
+#![deny(warnings, rust_2018_idioms)]
+
+use loom::sync::atomic::AtomicUsize;
+use loom::sync::Arc;
+use loom::thread;
+
+use std::env;
+use std::process::Command;
+use std::sync::atomic::Ordering::SeqCst;
+use std::sync::mpsc;
+use std::time::Duration;
+
+/// Set when running as the child process that blocks.
+const CHILD: &str = "LOOM_TEST_BLOCKED_THREAD_CHILD";
+
+#[test]
+fn blocked_thread_is_reported() {
+    if env::var(CHILD).is_ok() {
+        block_on_std_channel();
+        return;
+    }
+
+    // The watchdog aborts the process, so check it from another one.
+    let output = Command::new(env::current_exe().unwrap())
+        .args(["blocked_thread_is_reported", "--exact", "--nocapture"])
+        .env(CHILD, "1")
+        .output()
+        .unwrap();
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(!output.status.success(), "stderr: {}", stderr);
+    assert!(
+        stderr.contains(
+            "thread 1 appears blocked on a non-loom operation at tests/blocked_thread.rs:"
+        ),
+        "stderr: {}",
+        stderr
+    );
+}
+
+fn block_on_std_channel() {
+    let mut builder = loom::model::Builder::new();
+    builder.blocked_thread_timeout = Some(Duration::from_millis(500));
+    builder.location = true;
+
+    builder.check(|| {
+        let (tx, rx) = mpsc::channel::<()>();
+        let num = Arc::new(AtomicUsize::new(0));
+
+        let th = {
+            let num = num.clone();
+
+            thread::spawn(move || {
+                num.store(1, SeqCst);
+
+                // Blocks the OS thread shared by all loom threads, as the
+                // sender is never used.
+                let _ = rx.recv();
+            })
+        };
+
+        th.join().unwrap();
+        drop(tx);
+    });
+}
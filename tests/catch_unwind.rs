@@ -0,0 +1,151 @@
+#![deny(warnings, rust_2018_idioms)]
+
+use loom::cell::UnsafeCell;
+use loom::sync::atomic::AtomicUsize;
+use loom::sync::Arc;
+use loom::thread;
+
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::Ordering::{AcqRel, Acquire, Release};
+
+/// Tracks the number of in-progress operations, which must be restored if an
+/// operation panics.
+struct Tracker {
+    active: AtomicUsize,
+    done: AtomicUsize,
+}
+
+impl Tracker {
+    fn run(&self, fail: bool) {
+        self.active.fetch_add(1, AcqRel);
+
+        let res = panic::catch_unwind(AssertUnwindSafe(|| {
+            // Branch point in between the increment and the panic
+            thread::yield_now();
+
+            if fail {
+                panic!("operation failed");
+            }
+
+            self.done.fetch_add(1, AcqRel);
+        }));
+
+        // Recovery happens whether or not the operation panicked
+        self.active.fetch_sub(1, AcqRel);
+
+        assert_eq!(fail, res.is_err());
+    }
+}
+
+#[test]
+fn catch_unwind_keeps_atomics_coherent() {
+    loom::model(|| {
+        let tracker = Arc::new(Tracker {
+            active: AtomicUsize::new(0),
+            done: AtomicUsize::new(0),
+        });
+
+        let th = {
+            let tracker = tracker.clone();
+            thread::spawn(move || tracker.run(true))
+        };
+
+        tracker.run(false);
+
+        th.join().unwrap();
+
+        assert_eq!(0, tracker.active.load(Acquire));
+        assert_eq!(1, tracker.done.load(Acquire));
+    });
+}
+
+#[test]
+#[should_panic(expected = "not recovered")]
+fn panic_after_recovery_fails_model() {
+    loom::model(|| {
+        let num = Arc::new(AtomicUsize::new(0));
+
+        let res = panic::catch_unwind(AssertUnwindSafe(|| {
+            num.fetch_add(1, AcqRel);
+            panic!("recovered");
+        }));
+        assert!(res.is_err());
+
+        if num.load(Acquire) == 1 {
+            panic!("not recovered");
+        }
+    });
+}
+
+#[test]
+#[should_panic(expected = "update failed")]
+fn panic_in_compare_exchange_loop_fails_model() {
+    loom::model(|| {
+        let num = Arc::new(AtomicUsize::new(0));
+
+        let th = {
+            let num = num.clone();
+            thread::spawn(move || num.store(1, Release))
+        };
+
+        // Panics in the middle of the compare-exchange loop, once the racing
+        // store is observed
+        let _ = num.fetch_update(AcqRel, Acquire, |v| {
+            assert_eq!(0, v, "update failed");
+            Some(v + 2)
+        });
+
+        th.join().unwrap();
+    });
+}
+
+#[test]
+fn panic_in_compare_exchange_loop_completes_load() {
+    loom::model(|| {
+        let num = Arc::new(AtomicUsize::new(0));
+        let data = Arc::new(UnsafeCell::new(0));
+
+        let th = {
+            let num = num.clone();
+            let data = data.clone();
+
+            thread::spawn(move || {
+                data.with_mut(|v| unsafe { *v = 1 });
+                num.store(1, Release);
+            })
+        };
+
+        let res = panic::catch_unwind(AssertUnwindSafe(|| {
+            num.fetch_update(AcqRel, Acquire, |v| {
+                if v == 1 {
+                    panic!("stop");
+                }
+
+                None
+            })
+        }));
+
+        // The update's load completed, synchronizing with the store
+        if res.is_err() {
+            data.with(|v| assert_eq!(1, unsafe { *v }));
+        }
+
+        th.join().unwrap();
+    });
+}
+
+#[test]
+#[should_panic(expected = "failed after spawning")]
+fn panic_after_spawn_fails_model() {
+    loom::model(|| {
+        let num = Arc::new(AtomicUsize::new(0));
+
+        // The thread has not started when the model panics
+        let _th = {
+            let num = num.clone();
+            thread::spawn(move || num.store(1, Release))
+        };
+
+        panic!("failed after spawning");
+    });
+}
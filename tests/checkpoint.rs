@@ -0,0 +1,164 @@
+#![cfg(feature = "checkpoint")]
+#![deny(warnings, rust_2018_idioms)]
+
+use loom::model::{Builder, CheckpointFormat};
+use loom::sync::atomic::AtomicUsize;
+use loom::sync::Arc;
+use loom::thread;
+
+use std::ops::ControlFlow;
+use std::path::PathBuf;
+use std::sync::atomic::Ordering::SeqCst;
+
+fn model() {
+    let num = Arc::new(AtomicUsize::new(0));
+
+    let ths: Vec<_> = (0..2)
+        .map(|_| {
+            let num = num.clone();
+            thread::spawn(move || {
+                num.fetch_add(1, SeqCst);
+                num.fetch_add(1, SeqCst);
+            })
+        })
+        .collect();
+
+    for th in ths {
+        th.join().unwrap();
+    }
+
+    assert_eq!(4, num.load(SeqCst));
+}
+
+fn checkpoint_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("loom-{}-{}", std::process::id(), name));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+/// Stops the check after a few permutations, then resumes it from the
+/// checkpoint file, returning the total number of permutations.
+fn resume(path: &PathBuf, format: Option<CheckpointFormat>) -> usize {
+    let mut builder = Builder::new();
+    builder.checkpoint_file = Some(path.clone());
+    builder.checkpoint_format = format;
+    builder.checkpoint_interval = 1;
+    builder.max_permutations = Some(5);
+    builder.check(model);
+
+    assert!(path.exists());
+
+    let mut builder = Builder::new();
+    builder.checkpoint_file = Some(path.clone());
+    builder.checkpoint_format = format;
+    builder.checkpoint_interval = 1;
+    let stats = builder.check(model);
+    assert!(stats.complete);
+
+    let _ = std::fs::remove_file(path);
+    stats.permutations
+}
+
+#[test]
+fn binary_checkpoint_resumes_like_json() {
+    let json = resume(&checkpoint_path("resume.json"), None);
+    let binary = resume(&checkpoint_path("resume.bin"), None);
+
+    let mut builder = Builder::new();
+    builder.checkpoint_file = None;
+    let all = builder.check(model).permutations;
+
+    assert_eq!(all, json);
+    assert_eq!(all, binary);
+}
+
+#[test]
+fn resume_from_checkpointed_iteration() {
+    for name in ["iteration.json", "iteration.bin"] {
+        let path = checkpoint_path(name);
+
+        let mut builder = Builder::new();
+        builder.checkpoint_file = Some(path.clone());
+        builder.checkpoint_interval = 1;
+        builder.max_permutations = Some(5);
+        assert_eq!(4, builder.check(model).permutations);
+
+        let first = std::sync::Arc::new(std::sync::Mutex::new(None));
+
+        let mut builder = Builder::new();
+        builder.checkpoint_file = Some(path.clone());
+        builder.checkpoint_interval = 1;
+        builder.on_iteration({
+            let first = first.clone();
+            move |i| {
+                first.lock().unwrap().get_or_insert(i);
+                ControlFlow::Continue(())
+            }
+        });
+        builder.check(model);
+
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(Some(5), *first.lock().unwrap());
+    }
+}
+
+#[test]
+fn max_permutations_counts_checkpointed_iterations() {
+    let path = checkpoint_path("max_permutations.json");
+
+    let mut builder = Builder::new();
+    builder.checkpoint_file = Some(path.clone());
+    builder.checkpoint_interval = 1;
+    builder.max_permutations = Some(5);
+    builder.check(model);
+
+    // The 5 permutations were already checked
+    let stats = builder.check(model);
+    let _ = std::fs::remove_file(&path);
+
+    assert!(!stats.complete);
+    assert_eq!(4, stats.permutations);
+}
+
+/// Stops the check after a few permutations, returning the contents of the
+/// checkpoint file.
+fn checkpoint(path: &PathBuf, format: Option<CheckpointFormat>) -> Vec<u8> {
+    let mut builder = Builder::new();
+    builder.checkpoint_file = Some(path.clone());
+    builder.checkpoint_format = format;
+    builder.checkpoint_interval = 1;
+    builder.max_permutations = Some(2);
+    builder.check(model);
+
+    let contents = std::fs::read(path).unwrap();
+    let _ = std::fs::remove_file(path);
+    contents
+}
+
+fn is_json(contents: &[u8]) -> bool {
+    std::str::from_utf8(contents).is_ok_and(|s| s.starts_with('{') && s.ends_with('}'))
+}
+
+#[test]
+fn format_selected_by_extension() {
+    assert!(is_json(&checkpoint(&checkpoint_path("ext.json"), None)));
+    assert!(is_json(&checkpoint(&checkpoint_path("ext"), None)));
+    assert!(!is_json(&checkpoint(&checkpoint_path("ext.bin"), None)));
+}
+
+#[test]
+fn format_overrides_extension() {
+    let binary = Some(CheckpointFormat::Binary);
+    assert!(!is_json(&checkpoint(
+        &checkpoint_path("override.json"),
+        binary
+    )));
+
+    let json = Some(CheckpointFormat::Json);
+    assert!(is_json(&checkpoint(&checkpoint_path("override.bin"), json)));
+
+    assert_eq!(
+        resume(&checkpoint_path("resume-override.json"), binary),
+        resume(&checkpoint_path("resume-override.bin"), json),
+    );
+}
@@ -1,5 +1,6 @@
 #![deny(warnings, rust_2018_idioms)]
 
+use loom::model::Builder;
 use loom::sync::atomic::AtomicUsize;
 use loom::sync::{Condvar, Mutex};
 use loom::thread;
@@ -80,3 +81,69 @@ impl Inc {
         self.condvar.notify_all();
     }
 }
+
+/// Waits for the flag set by another thread, checking it only once.
+fn wait_without_predicate_loop() {
+    let pair = Arc::new((Mutex::new(false), Condvar::new()));
+
+    let th = {
+        let pair = pair.clone();
+        thread::spawn(move || {
+            *pair.0.lock().unwrap() = true;
+            pair.1.notify_one();
+        })
+    };
+
+    let mut ready = pair.0.lock().unwrap();
+
+    // BUG: the condition must be re-checked in a loop, as the wait may
+    // return because of a spurious wakeup.
+    if !*ready {
+        ready = pair.1.wait(ready).unwrap();
+    }
+
+    assert!(*ready, "woke up before the flag was set");
+    drop(ready);
+
+    th.join().unwrap();
+}
+
+#[test]
+#[should_panic(expected = "woke up before the flag was set")]
+fn spurious_wakeup_without_predicate_loop() {
+    let mut builder = Builder::new();
+    builder.spurious_wakeups(true);
+    builder.check(wait_without_predicate_loop);
+}
+
+#[test]
+fn no_spurious_wakeup_by_default() {
+    loom::model(wait_without_predicate_loop);
+}
+
+#[test]
+fn spurious_wakeup_with_predicate_loop() {
+    let mut builder = Builder::new();
+    builder.spurious_wakeups(true);
+    builder.check(|| {
+        let pair = Arc::new((Mutex::new(false), Condvar::new()));
+
+        let th = {
+            let pair = pair.clone();
+            thread::spawn(move || {
+                *pair.0.lock().unwrap() = true;
+                pair.1.notify_one();
+            })
+        };
+
+        let mut ready = pair.0.lock().unwrap();
+
+        while !*ready {
+            ready = pair.1.wait(ready).unwrap();
+        }
+
+        drop(ready);
+
+        th.join().unwrap();
+    });
+}
@@ -0,0 +1,81 @@
+#![deny(warnings, rust_2018_idioms)]
+
+mod support;
+
+use loom::sync::atomic::AtomicUsize;
+use loom::sync::{Arc, Mutex};
+use loom::thread;
+
+use std::sync::atomic::AtomicU32;
+use std::sync::atomic::Ordering::{Relaxed, SeqCst};
+
+/// Checks the model with `report_dead_objects` enabled, returning the emitted
+/// warnings.
+fn check_warnings<F>(f: F) -> String
+where
+    F: Fn() + Sync + Send + 'static,
+{
+    support::check_warnings(
+        |builder| {
+            builder.report_dead_objects(true);
+        },
+        f,
+    )
+}
+
+#[test]
+fn reports_unused_atomic() {
+    static CREATED_AT: AtomicU32 = AtomicU32::new(0);
+
+    let warnings = check_warnings(|| {
+        let used = Arc::new(AtomicUsize::new(0));
+        let lock = Arc::new(Mutex::new(0));
+        CREATED_AT.store(line!() + 1, Relaxed);
+        let _unused = AtomicUsize::new(0);
+
+        let th = {
+            let used = used.clone();
+            thread::spawn(move || used.fetch_add(1, SeqCst))
+        };
+
+        used.load(SeqCst);
+        *lock.lock().unwrap() += 1;
+
+        th.join().unwrap();
+    });
+
+    let reported: Vec<_> = warnings.lines().collect();
+
+    assert_eq!(1, reported.len(), "{}", warnings);
+    assert!(
+        reported[0].contains(&format!(
+            "atomic created at {}:{}:",
+            file!(),
+            CREATED_AT.load(Relaxed)
+        )),
+        "{}",
+        warnings
+    );
+    assert!(reported[0].contains("was never accessed"), "{}", warnings);
+}
+
+#[test]
+fn object_accessed_in_some_permutations_is_not_reported() {
+    let warnings = check_warnings(|| {
+        let flag = Arc::new(AtomicUsize::new(0));
+        let rare = Arc::new(AtomicUsize::new(0));
+
+        let th = {
+            let flag = flag.clone();
+            thread::spawn(move || flag.store(1, SeqCst))
+        };
+
+        if flag.load(SeqCst) == 1 {
+            rare.store(1, SeqCst);
+        }
+
+        th.join().unwrap();
+    });
+
+    assert!(warnings.is_empty(), "{}", warnings);
+}
@@ -0,0 +1,74 @@
+#![deny(warnings, rust_2018_idioms)]
+
+use loom::debug::assert_not_yet_visible;
+use loom::sync::atomic::{AtomicBool, AtomicUsize};
+use loom::sync::Arc;
+use loom::thread;
+
+use std::sync::atomic::Ordering::{Relaxed, SeqCst};
+
+#[test]
+fn relaxed_store_is_not_yet_visible() {
+    loom::model(|| {
+        let num = Arc::new(AtomicUsize::new(0));
+        let done = Arc::new(AtomicBool::new(false));
+
+        let th = {
+            let (num, done) = (num.clone(), done.clone());
+
+            thread::spawn(move || {
+                let _ = num.load(Relaxed);
+
+                while !done.load(SeqCst) {
+                    thread::yield_now();
+                }
+            })
+        };
+
+        num.store(1, Relaxed);
+
+        // The spawned thread is still running, and even when its load already
+        // happened it may have returned `0`.
+        assert_not_yet_visible(&*num, 1);
+
+        done.store(true, SeqCst);
+        th.join().unwrap();
+    });
+}
+
+#[test]
+#[should_panic(expected = "the store of 1 is already visible to all other threads")]
+fn observed_store_is_visible() {
+    loom::model(|| {
+        let num = Arc::new(AtomicUsize::new(0));
+        let seen = Arc::new(AtomicBool::new(false));
+        let done = Arc::new(AtomicBool::new(false));
+
+        let th = {
+            let (num, seen, done) = (num.clone(), seen.clone(), done.clone());
+
+            thread::spawn(move || {
+                while num.load(Relaxed) != 1 {
+                    thread::yield_now();
+                }
+
+                seen.store(true, SeqCst);
+
+                while !done.load(SeqCst) {
+                    thread::yield_now();
+                }
+            })
+        };
+
+        num.store(1, Relaxed);
+
+        while !seen.load(SeqCst) {
+            thread::yield_now();
+        }
+
+        assert_not_yet_visible(&*num, 1);
+
+        done.store(true, SeqCst);
+        th.join().unwrap();
+    });
+}
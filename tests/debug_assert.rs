@@ -0,0 +1,47 @@
+#![deny(warnings, rust_2018_idioms)]
+
+use loom::sync::atomic::AtomicUsize;
+use loom::thread;
+
+use std::sync::atomic::Ordering::SeqCst;
+use std::sync::Arc;
+
+#[test]
+#[should_panic(expected = "lost an increment")]
+fn debug_assert_fails_iteration() {
+    loom::model(|| {
+        let num = Arc::new(AtomicUsize::new(0));
+
+        let th = {
+            let num = num.clone();
+            thread::spawn(move || {
+                let curr = num.load(SeqCst);
+                num.store(curr + 1, SeqCst);
+            })
+        };
+
+        let curr = num.load(SeqCst);
+        num.store(curr + 1, SeqCst);
+        th.join().unwrap();
+
+        loom::debug_assert!(num.load(SeqCst) == 2, "lost an increment");
+    });
+}
+
+#[test]
+fn debug_assert_passes() {
+    loom::model(|| {
+        let num = Arc::new(AtomicUsize::new(0));
+
+        let th = {
+            let num = num.clone();
+            thread::spawn(move || num.fetch_add(1, SeqCst))
+        };
+
+        num.fetch_add(1, SeqCst);
+        th.join().unwrap();
+
+        loom::debug_assert!(num.load(SeqCst) == 2);
+        loom::debug_assert!(num.load(SeqCst) == 2, "{} increments", 2);
+    });
+}
@@ -0,0 +1,93 @@
+#![deny(warnings, rust_2018_idioms)]
+
+mod support;
+
+use loom::sync::atomic::AtomicUsize;
+use loom::sync::Arc;
+use loom::thread;
+
+use std::sync::atomic::Ordering::SeqCst;
+use std::sync::Mutex;
+
+/// Checks the model with `warn_detached_threads` enabled, returning the
+/// emitted warnings.
+fn check_warnings<F>(f: F) -> String
+where
+    F: Fn() + Sync + Send + 'static,
+{
+    support::check_warnings(
+        |builder| {
+            builder.warn_detached_threads(true);
+        },
+        f,
+    )
+}
+
+#[test]
+fn warns_running_detached_thread() {
+    let warnings = check_warnings(|| {
+        let num = Arc::new(AtomicUsize::new(0));
+        let num2 = num.clone();
+
+        drop(thread::spawn(move || {
+            num2.store(1, SeqCst);
+        }));
+    });
+
+    assert!(
+        warnings.contains("detached thread 1 still running"),
+        "{}",
+        warnings
+    );
+}
+
+#[test]
+fn joined_thread_does_not_warn() {
+    let warnings = check_warnings(|| {
+        let num = Arc::new(AtomicUsize::new(0));
+        let num2 = num.clone();
+
+        thread::spawn(move || {
+            num2.store(1, SeqCst);
+        })
+        .join()
+        .unwrap();
+    });
+
+    assert!(warnings.is_empty(), "{}", warnings);
+}
+
+#[test]
+fn handle_dropped_after_model() {
+    static HANDLE: Mutex<Option<thread::JoinHandle<()>>> = Mutex::new(None);
+
+    loom::model(|| {
+        let th = thread::spawn(|| {});
+        *HANDLE.lock().unwrap() = Some(th);
+    });
+
+    drop(HANDLE.lock().unwrap().take());
+}
+
+#[test]
+fn handle_dropped_in_later_execution() {
+    static HANDLE: Mutex<Option<thread::JoinHandle<()>>> = Mutex::new(None);
+
+    loom::model(|| {
+        // The handle of the thread spawned by the previous execution is
+        // dropped before this execution spawns its own thread.
+        drop(HANDLE.lock().unwrap().take());
+
+        let num = Arc::new(AtomicUsize::new(0));
+        let num2 = num.clone();
+
+        let th = thread::spawn(move || {
+            num2.store(1, SeqCst);
+        });
+
+        num.load(SeqCst);
+        *HANDLE.lock().unwrap() = Some(th);
+    });
+
+    drop(HANDLE.lock().unwrap().take());
+}
@@ -1,7 +1,8 @@
 #![deny(warnings, rust_2018_idioms)]
+#![allow(clippy::arc_with_non_send_sync)]
 
 use loom::cell::UnsafeCell;
-use loom::sync::atomic::{fence, AtomicBool};
+use loom::sync::atomic::{fence, AtomicBool, AtomicUsize};
 use loom::thread;
 
 use std::sync::atomic::Ordering::{Acquire, Relaxed, Release, SeqCst};
@@ -52,6 +53,50 @@ fn fence_sw_collapsed_store() {
     });
 }
 
+/// Loads `data` after observing `flag`, returning whether the load could ever
+/// return the initial value.
+fn atomic_data_can_be_stale(acquire_fence: bool) -> bool {
+    use std::sync::atomic::AtomicBool as StdAtomicBool;
+
+    let stale: &'static StdAtomicBool = Box::leak(Box::new(StdAtomicBool::new(false)));
+
+    loom::model(move || {
+        let data = Arc::new(AtomicUsize::new(0));
+        let flag = Arc::new(AtomicBool::new(false));
+
+        let th = {
+            let (data, flag) = (data.clone(), flag.clone());
+            thread::spawn(move || {
+                data.store(42, Relaxed);
+                flag.store(true, Release);
+            })
+        };
+
+        if flag.load(Relaxed) {
+            if acquire_fence {
+                fence(Acquire);
+            }
+
+            if data.load(Relaxed) == 0 {
+                stale.store(true, Relaxed);
+            }
+        }
+        th.join().unwrap();
+    });
+
+    stale.load(Relaxed)
+}
+
+#[test]
+fn fence_acquire_prevents_stale_atomic_load() {
+    assert!(!atomic_data_can_be_stale(true));
+}
+
+#[test]
+fn stale_atomic_load_without_fence() {
+    assert!(atomic_data_can_be_stale(false));
+}
+
 #[test]
 fn fence_sw_collapsed_load() {
     loom::model(|| {
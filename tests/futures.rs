@@ -1,14 +1,16 @@
 #![cfg(feature = "futures")]
 #![deny(warnings, rust_2018_idioms)]
 
-use loom::future::{block_on, AtomicWaker};
+use loom::future::{self, block_on, AtomicWaker, LocalSet};
 use loom::sync::atomic::AtomicUsize;
 use loom::thread;
 
 use futures_util::future::poll_fn;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
 use std::sync::atomic::Ordering::Relaxed;
 use std::sync::Arc;
-use std::task::Poll;
+use std::task::{Poll, Waker};
 
 struct Chan {
     num: AtomicUsize,
@@ -90,3 +92,212 @@ fn spurious_poll() {
 
     assert!(actual.load(Acquire));
 }
+
+/// Single slot channel between tasks of a `LocalSet`.
+struct Slot<T> {
+    value: Option<T>,
+    waker: Option<Waker>,
+}
+
+fn send<T>(slot: &RefCell<Slot<T>>, value: T) {
+    let mut slot = slot.borrow_mut();
+    slot.value = Some(value);
+
+    if let Some(waker) = slot.waker.take() {
+        waker.wake();
+    }
+}
+
+async fn recv<T>(slot: &RefCell<Slot<T>>) -> T {
+    poll_fn(|cx| {
+        let mut slot = slot.borrow_mut();
+
+        match slot.value.take() {
+            Some(value) => Poll::Ready(value),
+            None => {
+                slot.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    })
+    .await
+}
+
+#[test]
+fn local_set_ping_pong() {
+    use std::sync::atomic::AtomicBool;
+    use std::sync::atomic::Ordering::SeqCst;
+
+    static PING_FIRST: AtomicBool = AtomicBool::new(false);
+    static PONG_FIRST: AtomicBool = AtomicBool::new(false);
+
+    loom::model(|| {
+        let ping = Rc::new(RefCell::new(Slot {
+            value: None,
+            waker: None,
+        }));
+        let pong = Rc::new(RefCell::new(Slot {
+            value: None,
+            waker: None,
+        }));
+        let polled = Rc::new(Cell::new(false));
+
+        let mut set = LocalSet::new();
+
+        {
+            let (ping, pong, polled) = (ping.clone(), pong.clone(), polled.clone());
+
+            set.spawn(async move {
+                if !polled.replace(true) {
+                    PING_FIRST.store(true, SeqCst);
+                }
+
+                for i in 0..2 {
+                    send(&ping, i);
+                    assert_eq!(i, recv(&pong).await);
+                }
+            });
+        }
+
+        set.spawn(async move {
+            if !polled.replace(true) {
+                PONG_FIRST.store(true, SeqCst);
+            }
+
+            for _ in 0..2 {
+                let i = recv(&ping).await;
+                send(&pong, i);
+            }
+        });
+
+        set.run();
+    });
+
+    assert!(PING_FIRST.load(SeqCst));
+    assert!(PONG_FIRST.load(SeqCst));
+}
+
+#[test]
+fn local_set_woken_from_thread() {
+    use loom::sync::atomic::AtomicBool;
+    use loom::sync::atomic::Ordering::{Acquire, Release};
+
+    loom::model(|| {
+        let flag = Arc::new(AtomicBool::new(false));
+        let mut set = LocalSet::new();
+
+        {
+            let flag = flag.clone();
+
+            set.spawn(async move {
+                let mut spawned = false;
+
+                poll_fn(|cx| {
+                    if !spawned {
+                        spawned = true;
+
+                        let flag = flag.clone();
+                        let waker = cx.waker().clone();
+
+                        thread::spawn(move || {
+                            flag.store(true, Release);
+                            waker.wake();
+                        });
+                    }
+
+                    if flag.load(Acquire) {
+                        Poll::Ready(())
+                    } else {
+                        Poll::Pending
+                    }
+                })
+                .await
+            });
+        }
+
+        set.spawn(async {});
+        set.run();
+
+        assert!(flag.load(Acquire));
+    });
+}
+
+#[test]
+#[should_panic(expected = "deadlock")]
+fn local_set_never_woken_deadlocks() {
+    loom::model(|| {
+        let mut set = LocalSet::new();
+        set.spawn(std::future::pending());
+        set.run();
+    });
+}
+
+#[test]
+#[should_panic(expected = "future polled 250 times without progress; possible livelock")]
+fn self_waking_future_exceeds_poll_budget() {
+    loom::model(|| {
+        block_on(poll_fn(|cx| {
+            cx.waker().wake_by_ref();
+            Poll::<()>::Pending
+        }));
+    });
+}
+
+#[test]
+fn future_yielding_a_few_times_completes() {
+    loom::model(|| {
+        let mut polls = 0;
+
+        let polled = block_on(poll_fn(|cx| {
+            polls += 1;
+
+            if polls < 10 {
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+
+            Poll::Ready(polls)
+        }));
+
+        assert_eq!(10, polled);
+    });
+}
+
+#[test]
+fn spawned_task_woken_by_another_task() {
+    use loom::sync::atomic::AtomicBool;
+    use loom::sync::atomic::Ordering::{Acquire, Release};
+
+    struct Flag {
+        set: AtomicBool,
+        waker: AtomicWaker,
+    }
+
+    loom::model(|| {
+        let flag = Arc::new(Flag {
+            set: AtomicBool::new(false),
+            waker: AtomicWaker::new(),
+        });
+
+        let waiter = {
+            let flag = flag.clone();
+            future::spawn(poll_fn(move |cx| {
+                flag.waker.register_by_ref(cx.waker());
+
+                if flag.set.load(Acquire) {
+                    Poll::Ready("woken")
+                } else {
+                    Poll::Pending
+                }
+            }))
+        };
+
+        let setter = future::spawn(async move {
+            flag.set.store(true, Release);
+            flag.waker.wake();
+        });
+
+        setter.join().unwrap();
+        assert_eq!("woken", waiter.join().unwrap());
+    });
+}
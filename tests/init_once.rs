@@ -0,0 +1,95 @@
+#![deny(warnings, rust_2018_idioms)]
+
+use loom::sync::{Arc, InitOnce};
+use loom::thread;
+
+use std::collections::BTreeSet;
+use std::sync::Mutex;
+
+#[test]
+fn basic_usage() {
+    loom::model(|| {
+        let init = InitOnce::new();
+        assert_eq!(None, init.get());
+
+        assert_eq!(0, init.init_or_get(0));
+        assert_eq!(0, init.init_or_get(5));
+        assert_eq!(Some(0), init.get());
+        assert_eq!(Some(0), init.into_inner());
+    });
+}
+
+#[test]
+#[should_panic]
+fn usize_max_cannot_be_stored() {
+    loom::model(|| {
+        InitOnce::new().init_or_get(usize::MAX);
+    });
+}
+
+#[test]
+fn three_threads_agree_on_winner() {
+    static WINNERS: Mutex<BTreeSet<usize>> = Mutex::new(BTreeSet::new());
+
+    loom::model(|| {
+        let init = Arc::new(InitOnce::new());
+
+        let ths: Vec<_> = (1..=3)
+            .map(|value| {
+                let init = init.clone();
+                thread::spawn(move || init.init_or_get(value))
+            })
+            .collect();
+
+        let values: Vec<_> = ths.into_iter().map(|th| th.join().unwrap()).collect();
+        let winner = values[0];
+
+        assert!(values.iter().all(|&value| value == winner));
+        assert_eq!(Some(winner), init.get());
+
+        WINNERS.lock().unwrap().insert(winner);
+    });
+
+    // Each thread won in some permutation
+    assert_eq!(
+        vec![1, 2, 3],
+        WINNERS.lock().unwrap().iter().copied().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn init_or_get_publishes_winner_writes() {
+    use loom::cell::UnsafeCell;
+
+    struct Slots {
+        init: InitOnce,
+        slots: [UnsafeCell<usize>; 2],
+    }
+
+    // Each slot is only written by the thread initializing the index
+    unsafe impl Sync for Slots {}
+
+    loom::model(|| {
+        let shared = Arc::new(Slots {
+            init: InitOnce::new(),
+            slots: [UnsafeCell::new(0), UnsafeCell::new(0)],
+        });
+
+        let ths: Vec<_> = (0..2)
+            .map(|index| {
+                let shared = shared.clone();
+                thread::spawn(move || {
+                    shared.slots[index].with_mut(|v| unsafe { *v = index + 1 });
+
+                    let winner = shared.init.init_or_get(index);
+                    let value = shared.slots[winner].with(|v| unsafe { *v });
+                    assert_eq!(winner + 1, value);
+                })
+            })
+            .collect();
+
+        for th in ths {
+            th.join().unwrap();
+        }
+    });
+}
@@ -0,0 +1,122 @@
+#![deny(warnings, rust_2018_idioms)]
+
+use loom::linearizability::{self, Spec};
+use loom::model::Builder;
+use loom::sync::atomic::AtomicUsize;
+use loom::sync::Mutex;
+
+use std::collections::VecDeque;
+use std::sync::atomic::Ordering::SeqCst;
+
+#[derive(Clone, Debug)]
+enum Op {
+    Push(usize),
+    Pop,
+}
+
+#[derive(Clone, Default)]
+struct Queue(VecDeque<usize>);
+
+impl Spec for Queue {
+    type Op = Op;
+    type Ret = Option<usize>;
+
+    fn apply(&mut self, op: &Op) -> Option<usize> {
+        match *op {
+            Op::Push(v) => {
+                self.0.push_back(v);
+                None
+            }
+            Op::Pop => self.0.pop_front(),
+        }
+    }
+}
+
+fn ops() -> Vec<Vec<Op>> {
+    vec![vec![Op::Push(1)], vec![Op::Push(2), Op::Pop, Op::Pop]]
+}
+
+#[test]
+fn locked_queue_is_linearizable() {
+    linearizability::check(
+        ops(),
+        Queue::default(),
+        || Mutex::new(VecDeque::new()),
+        |queue, op| match *op {
+            Op::Push(v) => {
+                queue.lock().unwrap().push_back(v);
+                None
+            }
+            Op::Pop => queue.lock().unwrap().pop_front(),
+        },
+    );
+}
+
+#[test]
+#[should_panic(expected = "at most 1 threads of operations can be checked")]
+fn ops_limited_by_builder_max_threads() {
+    let mut builder = Builder::new();
+    builder.max_threads(2);
+
+    linearizability::check_with(
+        &builder,
+        ops(),
+        Queue::default(),
+        || Mutex::new(VecDeque::new()),
+        |queue, op| match *op {
+            Op::Push(v) => {
+                queue.lock().unwrap().push_back(v);
+                None
+            }
+            Op::Pop => queue.lock().unwrap().pop_front(),
+        },
+    );
+}
+
+/// Queue whose `push` reserves a slot with a separate load and store, so two
+/// concurrent pushes can write to the same slot.
+struct RacyQueue {
+    slots: [AtomicUsize; 2],
+    head: AtomicUsize,
+    tail: AtomicUsize,
+}
+
+impl RacyQueue {
+    fn push(&self, v: usize) {
+        let tail = self.tail.load(SeqCst);
+        self.slots[tail].store(v, SeqCst);
+        self.tail.store(tail + 1, SeqCst);
+    }
+
+    fn pop(&self) -> Option<usize> {
+        let head = self.head.load(SeqCst);
+
+        if head == self.tail.load(SeqCst) {
+            return None;
+        }
+
+        self.head.store(head + 1, SeqCst);
+        Some(self.slots[head].load(SeqCst))
+    }
+}
+
+#[test]
+#[should_panic(expected = "history is not linearizable")]
+fn racy_queue_is_not_linearizable() {
+    linearizability::check(
+        ops(),
+        Queue::default(),
+        || RacyQueue {
+            slots: [AtomicUsize::new(0), AtomicUsize::new(0)],
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+        },
+        |queue, op| match *op {
+            Op::Push(v) => {
+                queue.push(v);
+                None
+            }
+            Op::Pop => queue.pop(),
+        },
+    );
+}
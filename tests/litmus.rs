@@ -4,7 +4,7 @@ use loom::sync::atomic::AtomicUsize;
 use loom::thread;
 
 use std::collections::HashSet;
-use std::sync::atomic::Ordering::Relaxed;
+use std::sync::atomic::Ordering::{self, Relaxed, SeqCst};
 use std::sync::{Arc, Mutex};
 
 // Loom currently does not support load buffering.
@@ -57,3 +57,54 @@ fn store_buffering() {
     });
     assert!(values_.lock().unwrap().contains(&(0, 0)));
 }
+
+/// Independent reads of independent writes: two threads store to `x` and `y`,
+/// and two threads read them in opposite orders. Returns the observed
+/// outcomes, as `(r1, r2, r3, r4)`.
+fn iriw(order: Ordering) -> HashSet<(usize, usize, usize, usize)> {
+    let values = Arc::new(Mutex::new(HashSet::new()));
+    let values_ = values.clone();
+    loom::model(move || {
+        let x = Arc::new(AtomicUsize::new(0));
+        let y = Arc::new(AtomicUsize::new(0));
+
+        let writers = [x.clone(), y.clone()].map(|a| thread::spawn(move || a.store(1, order)));
+
+        let reader = {
+            let (x, y) = (x.clone(), y.clone());
+            thread::spawn(move || (x.load(order), y.load(order)))
+        };
+
+        let r3 = y.load(order);
+        let r4 = x.load(order);
+
+        let (r1, r2) = reader.join().unwrap();
+
+        for writer in writers {
+            writer.join().unwrap();
+        }
+
+        values.lock().unwrap().insert((r1, r2, r3, r4));
+    });
+    let values = values_.lock().unwrap().clone();
+    values
+}
+
+#[test]
+fn independent_reads_of_independent_writes() {
+    let values = iriw(SeqCst);
+
+    // All threads agree on a single total order of the `SeqCst` stores, so the
+    // readers cannot observe them in opposite orders.
+    assert!(!values.contains(&(1, 0, 1, 0)));
+    assert!(values.contains(&(1, 1, 1, 1)));
+    assert!(values.contains(&(0, 0, 0, 0)));
+}
+
+#[test]
+fn independent_reads_of_independent_writes_relaxed() {
+    let values = iriw(Relaxed);
+
+    // Without `SeqCst`, the readers may disagree on the order of the stores.
+    assert!(values.contains(&(1, 0, 1, 0)));
+}
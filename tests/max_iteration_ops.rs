@@ -0,0 +1,47 @@
+#![deny(warnings, rust_2018_idioms)]
+
+use loom::sync::atomic::{AtomicBool, AtomicUsize};
+use loom::sync::Arc;
+use loom::thread;
+
+use std::sync::atomic::Ordering::{Relaxed, SeqCst};
+
+/// Only takes the slow path when the spawned thread runs before the main
+/// thread sets `ready`.
+fn slow_path_in_some_schedules() {
+    let ready = Arc::new(AtomicBool::new(false));
+    let num = Arc::new(AtomicUsize::new(0));
+
+    let th = {
+        let (ready, num) = (ready.clone(), num.clone());
+
+        thread::spawn(move || {
+            if !ready.load(SeqCst) {
+                for _ in 0..50 {
+                    num.fetch_add(1, Relaxed);
+                }
+            }
+        })
+    };
+
+    ready.store(true, SeqCst);
+    th.join().unwrap();
+}
+
+#[test]
+#[should_panic(
+    expected = "iteration exceeded `max_iteration_ops` with 21 loom operations; \
+                           schedule: thread 0 x3, thread 1 x18"
+)]
+fn slow_iteration_fails() {
+    let mut builder = loom::model::Builder::new();
+    builder.max_iteration_ops(20);
+    builder.check(slow_path_in_some_schedules);
+}
+
+#[test]
+fn iterations_below_max_ops_pass() {
+    let mut builder = loom::model::Builder::new();
+    builder.max_iteration_ops(100);
+    builder.check(slow_path_in_some_schedules);
+}
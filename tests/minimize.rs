@@ -0,0 +1,122 @@
+#![deny(warnings, rust_2018_idioms)]
+
+mod support;
+
+use loom::sync::atomic::AtomicUsize;
+use loom::sync::Arc;
+use loom::thread;
+
+use std::cell::Cell;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::Ordering::{Relaxed, SeqCst};
+use std::sync::{Mutex, PoisonError};
+
+/// Checks the failing model with `minimize_failures` enabled, returning the
+/// panic message and the emitted warnings.
+fn check_minimized<F>(f: F) -> (String, String)
+where
+    F: Fn() + Sync + Send + 'static,
+{
+    let (res, warnings) = support::capture(tracing::Level::WARN, || {
+        panic::catch_unwind(AssertUnwindSafe(|| {
+            let mut builder = loom::model::Builder::new();
+            builder.minimize_failures(true);
+            builder.check(f);
+        }))
+    });
+
+    let payload = res.expect_err("model did not fail");
+    let message = match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => payload.downcast::<&str>().unwrap().to_string(),
+    };
+
+    (message, warnings)
+}
+
+/// Returns the number of forced choices before and after minimization.
+fn minimized(warnings: &str) -> (usize, usize) {
+    let counts: Vec<usize> = warnings
+        .split("from ")
+        .nth(1)
+        .expect("no minimization warning")
+        .split_whitespace()
+        .filter_map(|word| word.parse().ok())
+        .collect();
+
+    (counts[0], counts[1])
+}
+
+/// Serializes the tests, as the panic hook is shared by the whole process.
+static HOOK: Mutex<()> = Mutex::new(());
+
+thread_local! {
+    static PANICS: Cell<usize> = const { Cell::new(0) };
+}
+
+fn lost_update() {
+    let noise = Arc::new(AtomicUsize::new(0));
+    let num = Arc::new(AtomicUsize::new(0));
+
+    let ths: Vec<_> = (0..2)
+        .map(|_| {
+            let noise = noise.clone();
+            let num = num.clone();
+
+            thread::spawn(move || {
+                for _ in 0..2 {
+                    noise.fetch_add(1, Relaxed);
+                    noise.load(Relaxed);
+                }
+
+                let curr = num.load(SeqCst);
+                num.store(curr + 1, SeqCst);
+            })
+        })
+        .collect();
+
+    for th in ths {
+        th.join().unwrap();
+    }
+
+    assert_eq!(2, num.load(SeqCst), "lost update");
+}
+
+#[test]
+fn minimizes_lost_update() {
+    let _lock = HOOK.lock().unwrap_or_else(PoisonError::into_inner);
+    let (message, warnings) = check_minimized(lost_update);
+
+    let (before, after) = minimized(&warnings);
+    assert!(after < before, "{}", warnings);
+    assert!(message.contains("lost update"), "{}", message);
+}
+
+/// Installs a panic hook counting the failures of the model on the current
+/// thread, returning the number of them printed while checking it.
+fn printed_panics() -> usize {
+    panic::set_hook(Box::new(|info| {
+        if info.location().unwrap().file() == file!() {
+            PANICS.with(|n| n.set(n.get() + 1));
+        }
+    }));
+
+    let before = PANICS.with(Cell::get);
+    check_minimized(lost_update);
+    PANICS.with(Cell::get) - before
+}
+
+#[test]
+fn replays_are_quiet_with_hook_set_later() {
+    let _lock = HOOK.lock().unwrap_or_else(PoisonError::into_inner);
+    let hook = panic::take_hook();
+
+    let first = printed_panics();
+    let second = printed_panics();
+
+    panic::set_hook(hook);
+
+    // The failure is printed when found, then once minimized, but not while
+    // replaying it.
+    assert_eq!((2, 2), (first, second));
+}
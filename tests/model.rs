@@ -0,0 +1,563 @@
+#![deny(warnings, rust_2018_idioms)]
+
+use loom::model::Builder;
+use loom::sync::atomic::AtomicUsize;
+use loom::sync::Arc;
+use loom::thread;
+
+use std::ops::ControlFlow;
+use std::sync::atomic::AtomicUsize as StdAtomicUsize;
+use std::sync::atomic::Ordering::{Relaxed, SeqCst};
+
+/// Increments both counters, keeping their sum even unless a loom operation
+/// is performed in between the two increments.
+fn even_sum_model(
+    a: &'static StdAtomicUsize,
+    b: &'static StdAtomicUsize,
+    split: bool,
+) -> impl Fn() + Sync + Send + 'static {
+    move || {
+        a.store(0, Relaxed);
+        b.store(0, Relaxed);
+
+        let flag = Arc::new(AtomicUsize::new(0));
+
+        let th = {
+            let flag = flag.clone();
+            thread::spawn(move || {
+                a.fetch_add(1, Relaxed);
+                if split {
+                    flag.store(1, SeqCst);
+                }
+                b.fetch_add(1, Relaxed);
+            })
+        };
+
+        flag.load(SeqCst);
+        th.join().unwrap();
+    }
+}
+
+#[test]
+fn invariant_holds() {
+    static A: StdAtomicUsize = StdAtomicUsize::new(0);
+    static B: StdAtomicUsize = StdAtomicUsize::new(0);
+
+    let mut builder = Builder::new();
+    builder.invariant(|| (A.load(Relaxed) + B.load(Relaxed)) % 2 == 0);
+    builder.check(even_sum_model(&A, &B, false));
+}
+
+#[test]
+#[should_panic(expected = "model invariant violated")]
+fn invariant_violated_at_branch_point() {
+    static A: StdAtomicUsize = StdAtomicUsize::new(0);
+    static B: StdAtomicUsize = StdAtomicUsize::new(0);
+
+    let mut builder = Builder::new();
+    builder.invariant(|| (A.load(Relaxed) + B.load(Relaxed)) % 2 == 0);
+    builder.check(even_sum_model(&A, &B, true));
+}
+
+#[test]
+#[should_panic(expected = "loom operations are not permitted in model invariants")]
+fn invariant_performing_loom_operation() {
+    loom::lazy_static! {
+        static ref FLAG: AtomicUsize = AtomicUsize::new(0);
+    }
+
+    let mut builder = Builder::new();
+    builder.invariant(|| FLAG.load(Relaxed) == 0);
+    builder.check(|| {
+        FLAG.store(0, Relaxed);
+    });
+}
+
+#[test]
+fn progress_reported_every_checkpoint_interval() {
+    let reports = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let iterations = std::sync::Arc::new(StdAtomicUsize::new(0));
+
+    let mut builder = Builder::new();
+    builder.checkpoint_interval = 10;
+    builder.progress({
+        let reports = reports.clone();
+        move |i| reports.lock().unwrap().push(i)
+    });
+
+    builder.check({
+        let iterations = iterations.clone();
+        move || {
+            iterations.fetch_add(1, Relaxed);
+
+            let num = Arc::new(AtomicUsize::new(0));
+
+            let ths: Vec<_> = (0..2)
+                .map(|_| {
+                    let num = num.clone();
+                    thread::spawn(move || {
+                        num.fetch_add(1, SeqCst);
+                    })
+                })
+                .collect();
+
+            num.load(SeqCst);
+
+            for th in ths {
+                th.join().unwrap();
+            }
+        }
+    });
+
+    let iterations = iterations.load(Relaxed);
+    let reports = reports.lock().unwrap();
+
+    assert!(iterations >= 10, "iterations = {iterations}");
+    assert_eq!(
+        *reports,
+        (1..=iterations / 10).map(|n| n * 10).collect::<Vec<_>>()
+    );
+}
+
+/// Runs a model with a lost update between two threads, each then doing some
+/// unrelated work, and returns the iteration at which the bug was found.
+fn lost_update_found_at(warmup: Option<(usize, u64)>) -> usize {
+    let iterations = std::sync::Arc::new(StdAtomicUsize::new(0));
+
+    let mut builder = Builder::new();
+    builder.preemption_bound = Some(3);
+    if let Some((n, seed)) = warmup {
+        builder.random_warmup(n, seed);
+    }
+
+    let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe({
+        let iterations = iterations.clone();
+        move || {
+            builder.check(move || {
+                iterations.fetch_add(1, Relaxed);
+
+                let num = Arc::new(AtomicUsize::new(0));
+                let work = Arc::new(AtomicUsize::new(0));
+
+                let ths: Vec<_> = (0..2)
+                    .map(|_| {
+                        let num = num.clone();
+                        let work = work.clone();
+                        thread::spawn(move || {
+                            let n = num.load(SeqCst);
+                            num.store(n + 1, SeqCst);
+
+                            for _ in 0..3 {
+                                work.fetch_add(1, SeqCst);
+                            }
+                        })
+                    })
+                    .collect();
+
+                for th in ths {
+                    th.join().unwrap();
+                }
+
+                assert_eq!(2, num.load(SeqCst));
+            })
+        }
+    }));
+
+    assert!(res.is_err(), "lost update not found");
+    iterations.load(Relaxed)
+}
+
+#[test]
+fn random_warmup_finds_shallow_bug() {
+    let exhaustive = lost_update_found_at(None);
+    let warmup = lost_update_found_at(Some((100, 42)));
+
+    assert!(
+        warmup <= 100 && warmup < exhaustive,
+        "warmup = {warmup}; exhaustive = {exhaustive}"
+    );
+}
+
+/// Spawns two threads incrementing a counter, counting the iterations.
+fn counter_model(iterations: std::sync::Arc<StdAtomicUsize>) -> impl Fn() + Sync + Send {
+    move || {
+        iterations.fetch_add(1, Relaxed);
+
+        let num = Arc::new(AtomicUsize::new(0));
+
+        let th = {
+            let num = num.clone();
+            thread::spawn(move || num.fetch_add(1, SeqCst))
+        };
+
+        num.fetch_add(1, SeqCst);
+        th.join().unwrap();
+    }
+}
+
+#[test]
+fn on_complete_invoked_once_with_stats() {
+    let stats = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let iterations = std::sync::Arc::new(StdAtomicUsize::new(0));
+
+    let mut builder = Builder::new();
+    builder.on_complete({
+        let stats = stats.clone();
+        move |s| stats.lock().unwrap().push(s)
+    });
+
+    builder.check(counter_model(iterations.clone()));
+
+    let stats = stats.lock().unwrap();
+    assert_eq!(1, stats.len());
+    assert_eq!(iterations.load(Relaxed), stats[0].permutations);
+    assert!(stats[0].complete);
+}
+
+#[test]
+fn on_complete_invoked_when_truncated() {
+    let stats = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let iterations = std::sync::Arc::new(StdAtomicUsize::new(0));
+
+    let mut builder = Builder::new();
+    builder.checkpoint_interval = 1;
+    builder.max_permutations = Some(2);
+    builder.on_complete({
+        let stats = stats.clone();
+        move |s| stats.lock().unwrap().push(s)
+    });
+
+    builder.check(counter_model(iterations.clone()));
+
+    let stats = stats.lock().unwrap();
+    assert_eq!(1, stats.len());
+    assert_eq!(iterations.load(Relaxed), stats[0].permutations);
+    assert!(!stats[0].complete);
+}
+
+#[test]
+fn check_returns_stats() {
+    let iterations = std::sync::Arc::new(StdAtomicUsize::new(0));
+
+    let stats = Builder::new().check(counter_model(iterations.clone()));
+    assert_eq!(iterations.load(Relaxed), stats.permutations);
+    assert!(stats.complete);
+
+    let mut builder = Builder::new();
+    builder.checkpoint_interval = 1;
+    builder.max_permutations = Some(2);
+
+    iterations.store(0, Relaxed);
+    let stats = builder.check(counter_model(iterations.clone()));
+    assert_eq!(iterations.load(Relaxed), stats.permutations);
+    assert!(!stats.complete);
+}
+
+#[test]
+fn on_iteration_invoked_for_each_iteration() {
+    let seen = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let iterations = std::sync::Arc::new(StdAtomicUsize::new(0));
+
+    let mut builder = Builder::new();
+    builder.on_iteration({
+        let seen = seen.clone();
+        move |i| {
+            seen.lock().unwrap().push(i);
+            ControlFlow::Continue(())
+        }
+    });
+
+    let stats = builder.check(counter_model(iterations.clone()));
+
+    let expect: Vec<_> = (1..=iterations.load(Relaxed)).collect();
+    assert_eq!(expect, *seen.lock().unwrap());
+    assert!(stats.complete);
+}
+
+#[test]
+fn on_iteration_stops_check() {
+    let iterations = std::sync::Arc::new(StdAtomicUsize::new(0));
+
+    let mut builder = Builder::new();
+    builder.on_iteration(|i| {
+        if i > 2 {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    });
+
+    let stats = builder.check(counter_model(iterations.clone()));
+
+    assert_eq!(2, iterations.load(Relaxed));
+    assert_eq!(2, stats.permutations);
+    assert!(!stats.complete);
+}
+
+#[test]
+fn export_schedules_one_line_per_iteration() {
+    let path = std::env::temp_dir().join(format!("loom-schedules-{}.txt", std::process::id()));
+    let iterations = std::sync::Arc::new(StdAtomicUsize::new(0));
+
+    let mut builder = Builder::new();
+    builder.export_schedules(&path);
+    builder.check(counter_model(iterations.clone()));
+
+    let schedules = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let schedules: Vec<Vec<(usize, &str, usize)>> = schedules
+        .lines()
+        .map(|line| {
+            line.split(' ')
+                .map(|op| {
+                    let mut parts = op.split(':');
+                    let op = (
+                        parts.next().unwrap().parse().unwrap(),
+                        parts.next().unwrap(),
+                        parts.next().unwrap().parse().unwrap(),
+                    );
+                    assert_eq!(None, parts.next());
+                    op
+                })
+                .collect()
+        })
+        .collect();
+
+    assert_eq!(iterations.load(Relaxed), schedules.len());
+
+    // Both threads increment the counter, in either order
+    let rmws = |schedule: &[(usize, &str, usize)]| -> Vec<usize> {
+        schedule
+            .iter()
+            .filter(|&&(_, kind, _)| kind == "rmw")
+            .map(|&(thread, _, _)| thread)
+            .collect()
+    };
+
+    assert!(schedules.iter().all(|s| rmws(s).len() == 2));
+    assert!(schedules.iter().any(|s| rmws(s) == [0, 1]));
+    assert!(schedules.iter().any(|s| rmws(s) == [1, 0]));
+}
+
+/// Returns the schedules exported when checking `f` as the given shard.
+fn exported_schedules<F>(shard: Option<(usize, usize)>, f: F) -> Vec<String>
+where
+    F: Fn() + Sync + Send + 'static,
+{
+    let path =
+        std::env::temp_dir().join(format!("loom-shard-{}-{:?}.txt", std::process::id(), shard));
+
+    let mut builder = Builder::new();
+    builder.export_schedules(&path);
+
+    if let Some((index, total)) = shard {
+        builder.shard(index, total);
+    }
+
+    builder.check(f);
+
+    let schedules = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    schedules.lines().map(str::to_string).collect()
+}
+
+/// Threads incrementing a shared counter, so that DPOR explores all of their
+/// interleavings.
+fn contended_counter_model() {
+    let num = Arc::new(AtomicUsize::new(0));
+
+    let ths: Vec<_> = (0..2)
+        .map(|_| {
+            let num = num.clone();
+
+            thread::spawn(move || {
+                num.fetch_add(1, SeqCst);
+                num.fetch_add(1, SeqCst);
+            })
+        })
+        .collect();
+
+    num.fetch_add(1, SeqCst);
+
+    for th in ths {
+        th.join().unwrap();
+    }
+}
+
+#[test]
+fn shards_explore_same_permutations() {
+    let mut all = exported_schedules(None, contended_counter_model);
+    all.sort();
+
+    let mut sharded = exported_schedules(Some((0, 2)), contended_counter_model);
+    let shard_1 = exported_schedules(Some((1, 2)), contended_counter_model);
+
+    // Both shards explore part of the permutations
+    assert!(!sharded.is_empty() && sharded.len() < all.len());
+    assert!(!shard_1.is_empty() && shard_1.len() < all.len());
+
+    sharded.extend(shard_1);
+    sharded.sort();
+
+    assert_eq!(all, sharded);
+}
+
+#[test]
+fn shards_explore_at_least_all_permutations() {
+    // DPOR only explores some of the interleavings of independent accesses
+    fn model() {
+        let a = Arc::new(AtomicUsize::new(0));
+        let b = Arc::new(AtomicUsize::new(0));
+
+        let th = {
+            let (a, b) = (a.clone(), b.clone());
+
+            thread::spawn(move || {
+                a.store(1, SeqCst);
+                b.load(SeqCst);
+            })
+        };
+
+        b.store(1, SeqCst);
+        a.load(SeqCst);
+        th.join().unwrap();
+    }
+
+    let all = exported_schedules(None, model);
+
+    let mut sharded = exported_schedules(Some((0, 3)), model);
+    sharded.extend(exported_schedules(Some((1, 3)), model));
+    sharded.extend(exported_schedules(Some((2, 3)), model));
+
+    for schedule in &all {
+        assert!(sharded.contains(schedule), "{} not explored", schedule);
+    }
+}
+
+#[test]
+#[should_panic(expected = "shard index 2 out of range for 2 shards")]
+fn shard_index_out_of_range() {
+    Builder::new().shard(2, 2);
+}
+
+#[test]
+fn builder_setters() {
+    use std::time::Duration;
+
+    let mut builder = Builder::new();
+    builder
+        .max_threads(3)
+        .max_branches(500)
+        .max_permutations(Some(10_000))
+        .preemption_bound(Some(2))
+        .max_duration(Some(Duration::from_secs(60)))
+        .location(true);
+
+    assert_eq!(3, builder.max_threads);
+    assert_eq!(500, builder.max_branches);
+    assert_eq!(Some(10_000), builder.max_permutations);
+    assert_eq!(Some(2), builder.preemption_bound);
+    assert_eq!(Some(Duration::from_secs(60)), builder.max_duration);
+    assert!(builder.location);
+
+    builder.check(contended_counter_model);
+}
+
+/// Fails only if the spawned thread observes the first store of the main
+/// thread but not the second one, then the second one, which takes two
+/// preemptions.
+fn two_preemptions_model() {
+    let num = Arc::new(AtomicUsize::new(0));
+
+    let th = {
+        let num = num.clone();
+        thread::spawn(move || {
+            let first = num.load(SeqCst);
+            let second = num.load(SeqCst);
+            assert!((first, second) != (1, 2), "observed both stores");
+        })
+    };
+
+    num.store(1, SeqCst);
+    num.store(2, SeqCst);
+    th.join().unwrap();
+}
+
+#[test]
+fn two_preemptions_model_passes_with_one() {
+    let mut builder = Builder::new();
+    builder.preemption_bound(Some(1));
+    builder.check(two_preemptions_model);
+}
+
+#[test]
+#[should_panic(expected = "failure found at preemption bound 2: observed both stores")]
+fn iterative_deepening_reports_bound() {
+    Builder::new().check_iterative_deepening(0, 3, two_preemptions_model);
+}
+
+#[test]
+fn iterative_deepening_checks_each_bound() {
+    let iterations = std::sync::Arc::new(StdAtomicUsize::new(0));
+
+    let mut builder = Builder::new();
+    builder.preemption_bound(Some(5));
+    let stats = builder.check_iterative_deepening(1, 3, counter_model(iterations.clone()));
+
+    assert!(stats.complete);
+    assert!(stats.permutations < iterations.load(Relaxed));
+    assert_eq!(Some(5), builder.preemption_bound);
+}
+
+#[test]
+fn trace_thread_reports_operations() {
+    let mut builder = Builder::new();
+    builder.location = true;
+    builder.trace_thread(|th| th.name() == Some("suspect"));
+
+    let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        builder.check(|| {
+            let num = Arc::new(AtomicUsize::new(0));
+
+            let th = {
+                let num = num.clone();
+                thread::Builder::new()
+                    .name("suspect".to_string())
+                    .spawn(move || {
+                        let curr = num.load(SeqCst);
+                        num.store(curr + 1, SeqCst);
+                    })
+                    .unwrap()
+            };
+
+            let curr = num.load(SeqCst);
+            num.store(curr + 1, SeqCst);
+            th.join().unwrap();
+
+            assert_eq!(2, num.load(SeqCst), "lost an increment");
+        })
+    }));
+
+    let message = match res.unwrap_err().downcast::<String>() {
+        Ok(message) => *message,
+        Err(_) => panic!("expected a panic message"),
+    };
+
+    let (failure, operations) = message.split_once("\n\n").unwrap();
+    assert!(failure.contains("lost an increment"), "{}", failure);
+
+    let operations: Vec<_> = operations.lines().collect();
+    assert_eq!("operations of the traced threads:", operations[0]);
+    assert!(operations[1..]
+        .iter()
+        .all(|op| op.starts_with("  thread 1: ")));
+    assert!(operations[1].starts_with("  thread 1: load object "));
+    assert!(operations[2].starts_with("  thread 1: store object "));
+    assert!(
+        operations[1].contains("tests/model.rs:"),
+        "{}",
+        operations[1]
+    );
+}
@@ -0,0 +1,54 @@
+#![deny(warnings, rust_2018_idioms)]
+
+use loom::model::Builder;
+use loom::sync::atomic::AtomicUsize;
+use loom::sync::Arc;
+use loom::thread;
+
+use std::sync::atomic::AtomicUsize as StdAtomicUsize;
+use std::sync::atomic::Ordering::{Relaxed, SeqCst};
+
+fn count_iterations(builder: &Builder) -> usize {
+    let iterations = std::sync::Arc::new(StdAtomicUsize::new(0));
+
+    builder.check({
+        let iterations = iterations.clone();
+        move || {
+            iterations.fetch_add(1, Relaxed);
+
+            let num = Arc::new(AtomicUsize::new(0));
+
+            let ths: Vec<_> = (0..2)
+                .map(|_| {
+                    let num = num.clone();
+                    thread::spawn(move || {
+                        num.fetch_add(1, SeqCst);
+                        num.fetch_add(1, SeqCst);
+                    })
+                })
+                .collect();
+
+            for th in ths {
+                th.join().unwrap();
+            }
+        }
+    });
+
+    iterations.load(Relaxed)
+}
+
+// The environment is process wide, so this must be the only test in this file.
+#[test]
+fn without_env_ignores_environment() {
+    std::env::remove_var("LOOM_MAX_PREEMPTIONS");
+    let unset = count_iterations(&Builder::without_env());
+
+    std::env::set_var("LOOM_MAX_PREEMPTIONS", "0");
+
+    assert_eq!(Some(0), Builder::from_env().preemption_bound);
+    assert_eq!(Some(0), Builder::new().preemption_bound);
+    assert_eq!(None, Builder::without_env().preemption_bound);
+
+    assert_eq!(unset, count_iterations(&Builder::without_env()));
+    assert!(count_iterations(&Builder::from_env()) < unset);
+}
@@ -1,6 +1,9 @@
-use loom::sync::mpsc::channel;
+use loom::sync::mpsc::{channel, sync_channel, Sender};
 use loom::thread;
 
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Mutex;
+
 #[test]
 fn basic_sequential_usage() {
     loom::model(|| {
@@ -87,3 +90,335 @@ fn drop_receiver() {
         assert_eq!(r.recv().unwrap(), 1);
     });
 }
+
+#[test]
+fn send_racing_receiver_drop() {
+    use std::sync::atomic::AtomicBool;
+    use std::sync::atomic::Ordering::Relaxed;
+
+    static SENT: AtomicBool = AtomicBool::new(false);
+    static DISCONNECTED: AtomicBool = AtomicBool::new(false);
+
+    loom::model(|| {
+        let (s, r) = channel();
+
+        let th = thread::spawn(move || match s.send(1) {
+            Ok(()) => SENT.store(true, Relaxed),
+            Err(e) => {
+                assert_eq!(e.0, 1);
+                DISCONNECTED.store(true, Relaxed);
+            }
+        });
+
+        drop(r);
+
+        th.join().unwrap();
+    });
+
+    assert!(SENT.load(Relaxed));
+    assert!(DISCONNECTED.load(Relaxed));
+}
+
+#[test]
+fn sync_channel_send_blocks_when_full() {
+    use std::sync::atomic::AtomicBool;
+    use std::sync::atomic::Ordering::SeqCst;
+
+    loom::model(|| {
+        let (s, r) = sync_channel(1);
+
+        // Set by the sender without adding branch points.
+        let sent = std::sync::Arc::new(AtomicBool::new(false));
+
+        let th = {
+            let sent = sent.clone();
+
+            thread::spawn(move || {
+                s.send(1).unwrap();
+                s.send(2).unwrap();
+                sent.store(true, SeqCst);
+            })
+        };
+
+        // The second message does not fit until the first one is received.
+        assert!(!sent.load(SeqCst));
+        assert_eq!(r.recv().unwrap(), 1);
+        assert_eq!(r.recv().unwrap(), 2);
+
+        th.join().unwrap();
+    });
+}
+
+#[test]
+fn sync_channel_concurrent_senders() {
+    loom::model(|| {
+        let (s, r) = sync_channel(1);
+
+        let ths: Vec<_> = (0..2)
+            .map(|i| {
+                let s = s.clone();
+                thread::spawn(move || s.send(i).unwrap())
+            })
+            .collect();
+
+        let val = r.recv().unwrap() + r.recv().unwrap();
+        assert_eq!(val, 1);
+
+        for th in ths {
+            th.join().unwrap();
+        }
+    });
+}
+
+#[test]
+fn sync_channel_try_send_full() {
+    use std::sync::mpsc::TrySendError;
+
+    loom::model(|| {
+        let (s, r) = sync_channel(1);
+
+        s.try_send(1).unwrap();
+        assert_eq!(s.try_send(2), Err(TrySendError::Full(2)));
+
+        assert_eq!(r.recv().unwrap(), 1);
+        s.try_send(3).unwrap();
+        assert_eq!(r.recv().unwrap(), 3);
+
+        drop(r);
+        assert_eq!(s.try_send(4), Err(TrySendError::Disconnected(4)));
+    });
+}
+
+#[test]
+fn rendezvous_send_waits_for_receiver() {
+    use loom::cell::UnsafeCell;
+    use std::sync::atomic::AtomicBool;
+    use std::sync::atomic::Ordering::SeqCst;
+
+    struct Data(UnsafeCell<usize>);
+
+    unsafe impl Sync for Data {}
+
+    loom::model(|| {
+        let (s, r) = sync_channel(0);
+        let data = std::sync::Arc::new(Data(UnsafeCell::new(0)));
+
+        // Set by the sender without adding branch points.
+        let sent = std::sync::Arc::new(AtomicBool::new(false));
+
+        let th = {
+            let data = data.clone();
+            let sent = sent.clone();
+
+            thread::spawn(move || {
+                s.send(1).unwrap();
+                sent.store(true, SeqCst);
+
+                // The receiver was waiting before the send completed
+                data.0.with(|v| assert_eq!(1, unsafe { *v }));
+            })
+        };
+
+        data.0.with_mut(|v| unsafe { *v = 1 });
+
+        assert!(!sent.load(SeqCst));
+        assert_eq!(r.recv().unwrap(), 1);
+
+        th.join().unwrap();
+    });
+}
+
+#[test]
+fn rendezvous_try_send_without_receiver() {
+    use std::sync::mpsc::TrySendError;
+
+    loom::model(|| {
+        let (s, _r) = sync_channel(0);
+        assert_eq!(s.try_send(1), Err(TrySendError::Full(1)));
+    });
+}
+
+#[test]
+fn rendezvous_send_fails_when_receiver_dropped() {
+    loom::model(|| {
+        let (s, r) = sync_channel(0);
+
+        let th = thread::spawn(move || s.send(1));
+
+        drop(r);
+
+        assert_eq!(th.join().unwrap().unwrap_err().0, 1);
+    });
+}
+
+#[test]
+#[should_panic(expected = "deadlock")]
+fn rendezvous_send_without_receiver_deadlocks() {
+    loom::model(|| {
+        let (s, _r) = sync_channel(0);
+        s.send(1).unwrap();
+    });
+}
+
+#[test]
+fn try_recv_empty_and_disconnected() {
+    use std::sync::mpsc::TryRecvError;
+
+    loom::model(|| {
+        let (s, r) = channel();
+
+        assert_eq!(r.try_recv(), Err(TryRecvError::Empty));
+        s.send(1).unwrap();
+        assert_eq!(r.try_recv(), Ok(1));
+
+        drop(s);
+        assert_eq!(r.try_recv(), Err(TryRecvError::Disconnected));
+    });
+}
+
+#[test]
+fn try_recv_racing_send() {
+    use std::sync::atomic::AtomicBool;
+    use std::sync::atomic::Ordering::Relaxed;
+    use std::sync::mpsc::TryRecvError;
+
+    static EMPTY: AtomicBool = AtomicBool::new(false);
+    static RECEIVED: AtomicBool = AtomicBool::new(false);
+
+    loom::model(|| {
+        let (s, r) = channel();
+
+        let th = thread::spawn(move || s.send(1).unwrap());
+
+        match r.try_recv() {
+            Ok(v) => {
+                assert_eq!(v, 1);
+                RECEIVED.store(true, Relaxed);
+            }
+            Err(TryRecvError::Empty) => {
+                EMPTY.store(true, Relaxed);
+                assert_eq!(r.recv(), Ok(1));
+            }
+            Err(TryRecvError::Disconnected) => panic!("sender dropped before sending"),
+        }
+
+        th.join().unwrap();
+    });
+
+    assert!(EMPTY.load(Relaxed));
+    assert!(RECEIVED.load(Relaxed));
+}
+
+#[test]
+fn recv_fails_once_senders_dropped() {
+    loom::model(|| {
+        let (s, r) = channel();
+        let s2 = s.clone();
+
+        let th = thread::spawn(move || s2.send(1).unwrap());
+        drop(s);
+
+        assert_eq!(r.recv(), Ok(1));
+        assert!(r.recv().is_err());
+
+        th.join().unwrap();
+    });
+}
+
+#[test]
+fn recv_timeout_explores_timeout_and_delivery() {
+    use std::sync::atomic::AtomicBool;
+    use std::sync::atomic::Ordering::Relaxed;
+    use std::sync::mpsc::RecvTimeoutError;
+    use std::time::Duration;
+
+    static TIMED_OUT: AtomicBool = AtomicBool::new(false);
+    static RECEIVED: AtomicBool = AtomicBool::new(false);
+
+    loom::model(|| {
+        let (s, r) = channel();
+
+        let th = thread::spawn(move || s.send(1).unwrap());
+
+        match r.recv_timeout(Duration::from_millis(10)) {
+            Ok(v) => {
+                assert_eq!(v, 1);
+                RECEIVED.store(true, Relaxed);
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                TIMED_OUT.store(true, Relaxed);
+
+                // Only one timeout is explored, the retry blocks.
+                assert_eq!(r.recv_timeout(Duration::from_millis(10)), Ok(1));
+            }
+            Err(RecvTimeoutError::Disconnected) => panic!("sender dropped before sending"),
+        }
+
+        th.join().unwrap();
+    });
+
+    assert!(TIMED_OUT.load(Relaxed));
+    assert!(RECEIVED.load(Relaxed));
+}
+
+#[test]
+fn recv_timeout_disconnected() {
+    use std::sync::mpsc::RecvTimeoutError;
+    use std::time::Duration;
+
+    loom::model(|| {
+        let (s, r) = channel::<i32>();
+        drop(s);
+
+        assert_eq!(
+            r.recv_timeout(Duration::from_millis(10)),
+            Err(RecvTimeoutError::Disconnected)
+        );
+    });
+}
+
+#[test]
+fn sender_dropped_while_unwinding_disconnects() {
+    loom::model(|| {
+        let (s, r) = channel::<i32>();
+
+        let th = thread::spawn(move || {
+            let res = panic::catch_unwind(AssertUnwindSafe(move || {
+                let _s = s;
+                panic!("dropping the sender");
+            }));
+
+            assert!(res.is_err());
+        });
+
+        assert!(r.recv().is_err());
+        th.join().unwrap();
+    });
+}
+
+#[test]
+fn receiver_dropped_while_unwinding_disconnects() {
+    loom::model(|| {
+        let (s, r) = sync_channel::<i32>(1);
+
+        let res = panic::catch_unwind(AssertUnwindSafe(move || {
+            let _r = r;
+            panic!("dropping the receiver");
+        }));
+
+        assert!(res.is_err());
+        assert!(s.send(1).is_err());
+    });
+}
+
+#[test]
+fn sender_dropped_after_model() {
+    static SENDER: Mutex<Option<Sender<i32>>> = Mutex::new(None);
+
+    loom::model(|| {
+        let (s, _r) = channel();
+        *SENDER.lock().unwrap() = Some(s);
+    });
+
+    drop(SENDER.lock().unwrap().take());
+}
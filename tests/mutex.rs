@@ -7,6 +7,7 @@ use loom::thread;
 
 use std::rc::Rc;
 use std::sync::atomic::Ordering::SeqCst;
+use std::sync::TryLockError;
 
 #[test]
 fn mutex_enforces_mutal_exclusion() {
@@ -91,3 +92,160 @@ fn mutex_into_inner() {
         assert_eq!(lock, 2);
     })
 }
+
+#[test]
+fn mutex_get_mut() {
+    loom::model(|| {
+        let lock = Rc::new(Mutex::new(0));
+
+        let th = {
+            let lock = lock.clone();
+            thread::spawn(move || *lock.lock().unwrap() += 1)
+        };
+
+        th.join().unwrap();
+
+        let mut lock = Rc::try_unwrap(lock).unwrap();
+        *lock.get_mut().unwrap() += 1;
+        assert_eq!(lock.into_inner().unwrap(), 2);
+    })
+}
+
+#[test]
+#[should_panic(expected = "Causality violation: Concurrent lock and exclusive accesses.")]
+fn mutex_get_mut_racing_lock() {
+    struct Ptr(*mut Mutex<usize>);
+
+    unsafe impl Send for Ptr {}
+
+    loom::model(|| {
+        let lock = Box::into_raw(Box::new(Mutex::new(0)));
+
+        let th = {
+            let ptr = Ptr(lock);
+
+            thread::spawn(move || {
+                let ptr = ptr;
+                *unsafe { &*ptr.0 }.lock().unwrap() += 1;
+            })
+        };
+
+        // The thread is not joined, so the mutex is not exclusively owned.
+        thread::yield_now();
+        *unsafe { &mut *lock }.get_mut().unwrap() += 1;
+
+        th.join().unwrap();
+        drop(unsafe { Box::from_raw(lock) });
+    })
+}
+
+#[test]
+fn mutex_poisoned_by_panic() {
+    use std::panic::{self, AssertUnwindSafe};
+
+    loom::model(|| {
+        let lock = Rc::new(Mutex::new(0));
+
+        let th = {
+            let lock = lock.clone();
+
+            thread::spawn(move || {
+                let res = panic::catch_unwind(AssertUnwindSafe(|| {
+                    let mut guard = lock.lock().unwrap();
+                    *guard += 1;
+                    panic!("panicked while locked");
+                }));
+
+                assert!(res.is_err());
+            })
+        };
+
+        th.join().unwrap();
+        assert!(lock.is_poisoned());
+
+        // The guard is still usable through the error
+        let mut guard = lock.lock().unwrap_err().into_inner();
+        assert_eq!(1, *guard);
+        *guard += 1;
+        drop(guard);
+
+        assert!(matches!(lock.try_lock(), Err(TryLockError::Poisoned(_))));
+
+        lock.clear_poison();
+        assert!(!lock.is_poisoned());
+        assert_eq!(2, *lock.lock().unwrap());
+    });
+}
+
+#[test]
+fn mutex_poisoned_concurrently() {
+    use std::panic::{self, AssertUnwindSafe};
+
+    loom::model(|| {
+        let lock = Rc::new(Mutex::new(0));
+
+        let th = {
+            let lock = lock.clone();
+
+            thread::spawn(move || {
+                let _ = panic::catch_unwind(AssertUnwindSafe(|| {
+                    let _guard = lock.lock().unwrap();
+                    panic!("panicked while locked");
+                }));
+            })
+        };
+
+        // The mutex is already poisoned if the thread locked it first
+        let locked_first = lock.lock().map(|mut guard| *guard += 1).is_ok();
+
+        th.join().unwrap();
+
+        let lock = Rc::try_unwrap(lock).unwrap();
+        let value = lock.into_inner().unwrap_err().into_inner();
+        assert_eq!(locked_first, value == 1);
+    });
+}
+
+#[test]
+fn mutex_not_poisoned_by_panic_after_unlock() {
+    use std::panic::{self, AssertUnwindSafe};
+
+    loom::model(|| {
+        let mut lock = Mutex::new(0);
+
+        let res = panic::catch_unwind(AssertUnwindSafe(|| {
+            *lock.lock().unwrap() += 1;
+            panic!("panicked after unlocking");
+        }));
+
+        assert!(res.is_err());
+        assert!(!lock.is_poisoned());
+        assert_eq!(1, *lock.get_mut().unwrap());
+    });
+}
+
+#[test]
+fn mutex_not_poisoned_when_locked_while_unwinding() {
+    use std::panic::{self, AssertUnwindSafe};
+
+    struct LockOnDrop(Rc<Mutex<usize>>);
+
+    impl Drop for LockOnDrop {
+        fn drop(&mut self) {
+            *self.0.lock().unwrap() += 1;
+        }
+    }
+
+    loom::model(|| {
+        let lock = Rc::new(Mutex::new(0));
+
+        let res = panic::catch_unwind(AssertUnwindSafe(|| {
+            let _on_drop = LockOnDrop(lock.clone());
+            panic!("panicked before locking");
+        }));
+
+        assert!(res.is_err());
+        assert!(!lock.is_poisoned());
+        assert_eq!(1, *lock.lock().unwrap());
+    });
+}
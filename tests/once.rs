@@ -0,0 +1,110 @@
+#![deny(warnings, rust_2018_idioms)]
+
+use loom::cell::UnsafeCell;
+use loom::sync::{Arc, Once};
+use loom::thread;
+
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::SeqCst;
+
+#[test]
+fn call_once_runs_closure_once() {
+    loom::model(|| {
+        let once = Once::new();
+        let calls = AtomicUsize::new(0);
+
+        thread::scope(|s| {
+            for _ in 0..3 {
+                s.spawn(|| {
+                    once.call_once(|| {
+                        calls.fetch_add(1, SeqCst);
+                    });
+                });
+            }
+        });
+
+        assert!(once.is_completed());
+        assert_eq!(1, calls.load(SeqCst));
+    });
+}
+
+#[test]
+fn call_once_synchronizes_with_initialization() {
+    struct Init {
+        once: Once,
+        value: UnsafeCell<usize>,
+    }
+
+    unsafe impl Sync for Init {}
+
+    loom::model(|| {
+        let init = Arc::new(Init {
+            once: Once::new(),
+            value: UnsafeCell::new(0),
+        });
+
+        let th = {
+            let init = init.clone();
+
+            thread::spawn(move || {
+                init.once
+                    .call_once(|| init.value.with_mut(|v| unsafe { *v = 1 }));
+                init.value.with(|v| assert_eq!(1, unsafe { *v }));
+            })
+        };
+
+        if init.once.is_completed() {
+            init.value.with(|v| assert_eq!(1, unsafe { *v }));
+        }
+
+        init.once
+            .call_once(|| init.value.with_mut(|v| unsafe { *v = 1 }));
+        init.value.with(|v| assert_eq!(1, unsafe { *v }));
+
+        th.join().unwrap();
+    });
+}
+
+#[test]
+#[should_panic(expected = "Once instance has previously been poisoned")]
+fn call_once_after_panic_is_poisoned() {
+    loom::model(|| {
+        let once = Once::new();
+
+        let res = panic::catch_unwind(AssertUnwindSafe(|| {
+            once.call_once(|| panic!("initialization failed"));
+        }));
+        assert!(res.is_err());
+        assert!(!once.is_completed());
+
+        once.call_once(|| {});
+    });
+}
+
+#[test]
+fn blocked_call_once_observes_poisoning() {
+    loom::model(|| {
+        let once = Arc::new(Once::new());
+
+        let th = {
+            let once = once.clone();
+
+            thread::spawn(move || {
+                panic::catch_unwind(AssertUnwindSafe(|| {
+                    once.call_once(|| panic!("initialization failed"));
+                }))
+                .is_err()
+            })
+        };
+
+        let res = panic::catch_unwind(AssertUnwindSafe(|| once.call_once(|| {})));
+
+        // Either the thread's initialization panics first and poisons the
+        // `Once`, or the main thread's initialization completes first and the
+        // thread's closure never runs
+        let th_panicked = th.join().unwrap();
+        assert_eq!(th_panicked, res.is_err());
+        assert_eq!(!th_panicked, once.is_completed());
+    });
+}
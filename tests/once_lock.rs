@@ -0,0 +1,227 @@
+#![deny(warnings, rust_2018_idioms)]
+
+use loom::cell::UnsafeCell;
+use loom::sync::{Arc, OnceLock};
+use loom::thread;
+
+use std::sync::atomic::Ordering::SeqCst;
+use std::sync::atomic::{AtomicBool, AtomicUsize};
+
+#[test]
+fn wait_observes_initialized_value() {
+    static WAITED_BEFORE_INIT: AtomicBool = AtomicBool::new(false);
+    static WAITED_AFTER_INIT: AtomicBool = AtomicBool::new(false);
+
+    loom::model(|| {
+        let lock = Arc::new(OnceLock::new());
+        let data = Arc::new(UnsafeCell::new(0));
+
+        // Set by the main thread once the lock is initialized, without
+        // adding branch points.
+        let initialized = std::sync::Arc::new(AtomicBool::new(false));
+
+        let th = {
+            let lock = lock.clone();
+            let data = data.clone();
+            let initialized = initialized.clone();
+
+            thread::spawn(move || {
+                if initialized.load(SeqCst) {
+                    WAITED_AFTER_INIT.store(true, SeqCst);
+                } else {
+                    WAITED_BEFORE_INIT.store(true, SeqCst);
+                }
+
+                assert_eq!(42, *lock.wait());
+
+                // The initialization happens-before `wait` returns
+                data.with(|v| assert_eq!(1, unsafe { *v }));
+            })
+        };
+
+        data.with_mut(|v| unsafe { *v = 1 });
+        lock.set(42).unwrap();
+        initialized.store(true, SeqCst);
+
+        th.join().unwrap();
+    });
+
+    assert!(WAITED_BEFORE_INIT.load(SeqCst));
+    assert!(WAITED_AFTER_INIT.load(SeqCst));
+}
+
+#[test]
+fn get_synchronizes_with_set() {
+    loom::model(|| {
+        let lock = Arc::new(OnceLock::new());
+        let data = Arc::new(UnsafeCell::new(0));
+
+        let th = {
+            let lock = lock.clone();
+            let data = data.clone();
+
+            thread::spawn(move || {
+                data.with_mut(|v| unsafe { *v = 1 });
+                lock.set("init").unwrap();
+            })
+        };
+
+        if let Some(value) = lock.get() {
+            assert_eq!("init", *value);
+            data.with(|v| assert_eq!(1, unsafe { *v }));
+        }
+
+        th.join().unwrap();
+        assert_eq!(Some(&"init"), lock.get());
+    });
+}
+
+#[test]
+fn set_fails_when_initialized() {
+    loom::model(|| {
+        let lock = Arc::new(OnceLock::new());
+
+        let th = {
+            let lock = lock.clone();
+            thread::spawn(move || lock.set(1))
+        };
+
+        let res = lock.set(2);
+        let th_res = th.join().unwrap();
+
+        // Exactly one `set` wins, and the other gets its value back
+        match *lock.wait() {
+            1 => assert_eq!((Ok(()), Err(2)), (th_res, res)),
+            2 => assert_eq!((Err(1), Ok(())), (th_res, res)),
+            v => panic!("unexpected value {}", v),
+        }
+    });
+}
+
+#[test]
+#[should_panic(expected = "deadlock")]
+fn wait_without_set_deadlocks() {
+    loom::model(|| {
+        let lock = OnceLock::<usize>::new();
+        lock.wait();
+    });
+}
+
+#[test]
+fn debug_shows_value() {
+    loom::model(|| {
+        let lock = OnceLock::new();
+        assert_eq!("OnceLock(<uninit>)", format!("{:?}", lock));
+
+        lock.set(7).unwrap();
+        assert_eq!("OnceLock(7)", format!("{:?}", lock));
+    });
+}
+
+#[test]
+fn get_or_init_runs_one_closure() {
+    loom::model(|| {
+        let lock = Arc::new(OnceLock::new());
+        let calls = std::sync::Arc::new(AtomicUsize::new(0));
+
+        let th = {
+            let lock = lock.clone();
+            let calls = calls.clone();
+
+            thread::spawn(move || {
+                *lock.get_or_init(|| {
+                    calls.fetch_add(1, SeqCst);
+                    1
+                })
+            })
+        };
+
+        let value = *lock.get_or_init(|| {
+            calls.fetch_add(1, SeqCst);
+            2
+        });
+
+        // Both threads observe the value of the closure that ran
+        assert_eq!(value, th.join().unwrap());
+        assert_eq!(1, calls.load(SeqCst));
+    });
+}
+
+#[test]
+fn get_or_init_synchronizes_with_initialization() {
+    loom::model(|| {
+        let lock = Arc::new(OnceLock::new());
+        let data = Arc::new(UnsafeCell::new(0));
+
+        let init = |data: &UnsafeCell<usize>| {
+            data.with_mut(|v| unsafe { *v += 1 });
+        };
+
+        let th = {
+            let lock = lock.clone();
+            let data = data.clone();
+
+            thread::spawn(move || {
+                lock.get_or_init(|| init(&data));
+                data.with(|v| assert_eq!(1, unsafe { *v }));
+            })
+        };
+
+        lock.get_or_init(|| init(&data));
+        data.with(|v| assert_eq!(1, unsafe { *v }));
+
+        th.join().unwrap();
+    });
+}
+
+#[test]
+fn set_racing_get_or_init() {
+    loom::model(|| {
+        let lock = Arc::new(OnceLock::new());
+
+        let th = {
+            let lock = lock.clone();
+            thread::spawn(move || lock.set(1))
+        };
+
+        let value = *lock.get_or_init(|| 2);
+
+        // `set` fails if and only if the closure ran
+        match th.join().unwrap() {
+            Ok(()) => assert_eq!(1, value),
+            Err(1) => assert_eq!(2, value),
+            res => panic!("unexpected result {:?}", res),
+        }
+    });
+}
+
+#[test]
+fn get_or_init_after_panic() {
+    use std::panic::{self, AssertUnwindSafe};
+
+    loom::model(|| {
+        let lock = Arc::new(OnceLock::new());
+
+        let th = {
+            let lock = lock.clone();
+
+            thread::spawn(move || {
+                let res = panic::catch_unwind(AssertUnwindSafe(|| {
+                    lock.get_or_init(|| panic!("initialization failed"));
+                }));
+
+                // The closure only runs if the main thread did not
+                // initialize the cell first
+                if res.is_ok() {
+                    assert_eq!(Some(&2), lock.get());
+                }
+            })
+        };
+
+        // Blocks while the thread's initialization runs, then initializes the
+        // cell if it panicked
+        assert_eq!(2, *lock.get_or_init(|| 2));
+
+        th.join().unwrap();
+    });
+}
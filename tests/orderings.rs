@@ -0,0 +1,220 @@
+#![deny(warnings, rust_2018_idioms)]
+
+mod support;
+
+use loom::cell::UnsafeCell;
+use loom::model::{Mutation, OrderingCoverage};
+use loom::sync::atomic::{AtomicBool, AtomicUsize};
+use loom::sync::Arc;
+use loom::thread;
+
+use std::sync::atomic::AtomicU32 as StdAtomicU32;
+use std::sync::atomic::AtomicUsize as StdAtomicUsize;
+use std::sync::atomic::Ordering::{Acquire, Relaxed, Release, SeqCst};
+
+struct Message {
+    data: UnsafeCell<usize>,
+    ready: AtomicBool,
+    count: AtomicUsize,
+}
+
+#[test]
+fn suggests_relaxing_needless_seq_cst() {
+    static STORED_AT: StdAtomicU32 = StdAtomicU32::new(0);
+
+    let warnings = support::check_warnings(
+        |builder| {
+            builder.suggest_orderings(true);
+        },
+        || {
+            let msg = Arc::new(Message {
+                data: UnsafeCell::new(0),
+                ready: AtomicBool::new(false),
+                count: AtomicUsize::new(0),
+            });
+
+            let th = {
+                let msg = msg.clone();
+                thread::spawn(move || {
+                    msg.data.with_mut(|v| unsafe { *v = 1 });
+                    msg.ready.store(true, Release);
+                    // Only read after `join`, which synchronizes already.
+                    STORED_AT.store(line!() + 1, Relaxed);
+                    msg.count.store(1, SeqCst);
+                })
+            };
+
+            if msg.ready.load(Acquire) {
+                assert_eq!(1, msg.data.with(|v| unsafe { *v }));
+            }
+
+            th.join().unwrap();
+            assert_eq!(1, msg.count.load(Relaxed));
+        },
+    );
+
+    let suggestions: Vec<_> = warnings.lines().collect();
+
+    assert_eq!(1, suggestions.len(), "{}", warnings);
+    assert!(
+        suggestions[0].contains(&format!(
+            "SeqCst ordering at {}:{}:",
+            file!(),
+            STORED_AT.load(Relaxed)
+        )),
+        "{}",
+        warnings
+    );
+}
+
+struct SpinLock<T> {
+    locked: AtomicBool,
+    data: T,
+}
+
+unsafe impl<T: Send> Sync for SpinLock<T> {}
+
+impl<T> SpinLock<T> {
+    fn new(data: T) -> SpinLock<T> {
+        SpinLock {
+            locked: AtomicBool::new(false),
+            data,
+        }
+    }
+
+    fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        while self
+            .locked
+            .compare_exchange(false, true, Acquire, Relaxed)
+            .is_err()
+        {
+            thread::yield_now();
+        }
+
+        let ret = f(&self.data);
+        self.locked.store(false, Release);
+        ret
+    }
+}
+
+fn increment_twice<T: Send + 'static>(
+    data: fn() -> T,
+    increment: fn(&T),
+    get: fn(&T) -> usize,
+) -> Vec<Mutation> {
+    loom::model::Builder::new().mutation_test_orderings(move || {
+        let lock = Arc::new(SpinLock::new(data()));
+
+        let th = {
+            let lock = lock.clone();
+            thread::spawn(move || lock.with(increment))
+        };
+
+        lock.with(increment);
+        th.join().unwrap();
+
+        assert_eq!(2, lock.with(get));
+    })
+}
+
+#[test]
+fn mutation_survives_unchecked_lock() {
+    // Loom does not track accesses to `std` atomics, so weakening the lock
+    // goes unnoticed.
+    let mutations = increment_twice(
+        || StdAtomicUsize::new(0),
+        |n| {
+            let v = n.load(Relaxed);
+            n.store(v + 1, Relaxed);
+        },
+        |n| n.load(Relaxed),
+    );
+
+    let weakened: Vec<_> = mutations.iter().map(|m| (m.ordering, m.weakened)).collect();
+
+    assert_eq!(vec![(Acquire, Relaxed), (Release, Relaxed)], weakened);
+}
+
+#[test]
+fn mutation_killed_by_checked_lock() {
+    let mutations = increment_twice(
+        || UnsafeCell::new(0),
+        |n| n.with_mut(|v| unsafe { *v += 1 }),
+        |n| n.with(|v| unsafe { *v }),
+    );
+
+    assert!(mutations.is_empty(), "{:?}", mutations);
+}
+
+struct Published<T> {
+    data: T,
+    ready: AtomicBool,
+}
+
+unsafe impl<T: Send> Sync for Published<T> {}
+
+/// Publishes `data` with a `Release` store, reading it after an `Acquire`
+/// load observes the flag.
+fn publish<T: Send + 'static>(
+    data: fn() -> T,
+    write: fn(&T),
+    read: fn(&T) -> usize,
+) -> Vec<OrderingCoverage> {
+    loom::model::Builder::new().ordering_coverage_report(move || {
+        let msg = Arc::new(Published {
+            data: data(),
+            ready: AtomicBool::new(false),
+        });
+
+        let th = {
+            let msg = msg.clone();
+            thread::spawn(move || {
+                write(&msg.data);
+                msg.ready.store(true, Release);
+            })
+        };
+
+        if msg.ready.load(Acquire) {
+            assert_eq!(1, read(&msg.data));
+        }
+
+        th.join().unwrap();
+    })
+}
+
+#[test]
+fn coverage_reports_exercised_orderings() {
+    let report = publish(
+        || UnsafeCell::new(0),
+        |n| n.with_mut(|v| unsafe { *v = 1 }),
+        |n| n.with(|v| unsafe { *v }),
+    );
+
+    let orderings: Vec<_> = report.iter().map(|c| (c.ordering, c.exercised)).collect();
+    assert_eq!(vec![(Release, true), (Acquire, true)], orderings);
+}
+
+#[test]
+fn coverage_reports_ordering_not_exercised() {
+    // Loom does not track accesses to `std` atomics, so the test does not
+    // depend on the synchronization of the flag.
+    let report = publish(
+        || StdAtomicUsize::new(0),
+        |n| n.store(1, Relaxed),
+        |n| n.load(Relaxed),
+    );
+
+    let lines: Vec<_> = report.iter().map(ToString::to_string).collect();
+    assert_eq!(2, lines.len(), "{:?}", lines);
+    assert!(
+        lines[0].starts_with("Release ordering at tests/orderings.rs:")
+            && lines[0].ends_with(": ordering not exercised"),
+        "{:?}",
+        lines
+    );
+    assert!(
+        lines[1].ends_with(": ordering not exercised"),
+        "{:?}",
+        lines
+    );
+}
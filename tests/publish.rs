@@ -0,0 +1,99 @@
+#![deny(warnings, rust_2018_idioms)]
+
+use loom::cell::UnsafeCell;
+use loom::sync::atomic::AtomicBool;
+use loom::sync::{Arc, Publisher};
+use loom::thread;
+
+use std::sync::atomic::Ordering::Relaxed;
+
+#[derive(Debug, PartialEq)]
+struct Config {
+    name: &'static str,
+    retries: usize,
+    verbose: bool,
+}
+
+fn config() -> Config {
+    Config {
+        name: "loom",
+        retries: 3,
+        verbose: true,
+    }
+}
+
+#[test]
+fn subscriber_never_sees_partial_value() {
+    loom::model(|| {
+        let publisher = Publisher::new();
+
+        let ths: Vec<_> = (0..2)
+            .map(|_| {
+                let subscriber = publisher.subscribe();
+
+                thread::spawn(move || {
+                    if let Some(ok) = subscriber.with(|value| *value == config()) {
+                        assert!(ok);
+                    }
+                })
+            })
+            .collect();
+
+        publisher.publish(config());
+
+        for th in ths {
+            th.join().unwrap();
+        }
+    });
+}
+
+#[test]
+fn subscriber_sees_value_after_join() {
+    loom::model(|| {
+        let publisher = Publisher::new();
+        let subscriber = publisher.subscribe();
+
+        let th = thread::spawn(move || publisher.publish(config()));
+        th.join().unwrap();
+
+        assert!(subscriber.is_published());
+        assert_eq!(Some(true), subscriber.with(|value| *value == config()));
+    });
+}
+
+#[test]
+#[should_panic(expected = "Causality violation")]
+fn relaxed_publish_is_detected() {
+    struct Shared {
+        published: AtomicBool,
+        value: UnsafeCell<Option<Config>>,
+    }
+
+    unsafe impl Sync for Shared {}
+
+    loom::model(|| {
+        let shared = Arc::new(Shared {
+            published: AtomicBool::new(false),
+            value: UnsafeCell::new(None),
+        });
+
+        let th = {
+            let shared = shared.clone();
+
+            thread::spawn(move || {
+                if shared.published.load(Relaxed) {
+                    shared.value.with(|ptr| unsafe {
+                        assert_eq!(Some(config()), *ptr);
+                    });
+                }
+            })
+        };
+
+        shared
+            .value
+            .with_mut(|ptr| unsafe { *ptr = Some(config()) });
+        shared.published.store(true, Relaxed);
+
+        th.join().unwrap();
+    });
+}
@@ -0,0 +1,127 @@
+#![deny(warnings, rust_2018_idioms)]
+
+use loom::cell::UnsafeCell;
+use loom::sync::atomic::AtomicUsize;
+use loom::sync::RefCount;
+use loom::thread;
+
+use std::sync::atomic::Ordering::{Relaxed, Release};
+
+/// Intrusive reference count, either `RefCount` or a hand-rolled one.
+trait Count: Send + Sync + 'static {
+    fn new() -> Self;
+    fn increment(&self);
+    fn decrement(&self) -> bool;
+}
+
+impl Count for RefCount {
+    fn new() -> Self {
+        RefCount::new()
+    }
+
+    fn increment(&self) {
+        RefCount::increment(self)
+    }
+
+    fn decrement(&self) -> bool {
+        RefCount::decrement(self)
+    }
+}
+
+/// Reference count missing the `Acquire` fence on the last release.
+struct Unfenced(AtomicUsize);
+
+impl Count for Unfenced {
+    fn new() -> Self {
+        Unfenced(AtomicUsize::new(1))
+    }
+
+    fn increment(&self) {
+        self.0.fetch_add(1, Relaxed);
+    }
+
+    fn decrement(&self) -> bool {
+        self.0.fetch_sub(1, Release) == 1
+    }
+}
+
+struct Node<C> {
+    refs: C,
+    value: UnsafeCell<usize>,
+}
+
+/// Releases a reference to the node, dropping it if it was the last one.
+unsafe fn release<C: Count>(node: *mut Node<C>) {
+    if (*node).refs.decrement() {
+        // The destructor accesses the data
+        (*node).value.with_mut(|v| *v = 0);
+        drop(Box::from_raw(node));
+    }
+}
+
+fn shared_node<C: Count>() {
+    loom::model(|| {
+        let node = Box::into_raw(Box::new(Node {
+            refs: C::new(),
+            value: UnsafeCell::new(1),
+        }));
+
+        let ths: Vec<_> = (0..2)
+            .map(|_| {
+                unsafe { (*node).refs.increment() };
+                let ptr = node as usize;
+
+                thread::spawn(move || {
+                    let node = ptr as *mut Node<C>;
+
+                    unsafe {
+                        (*node).value.with(|v| assert_eq!(1, *v));
+                        release(node);
+                    }
+                })
+            })
+            .collect();
+
+        unsafe { release(node) };
+
+        for th in ths {
+            th.join().unwrap();
+        }
+    });
+}
+
+#[test]
+fn ref_count_drops_after_last_release() {
+    shared_node::<RefCount>();
+}
+
+#[test]
+#[should_panic(expected = "Causality violation")]
+fn missing_acquire_fence_is_detected() {
+    shared_node::<Unfenced>();
+}
+
+#[test]
+fn ref_count_counts_references() {
+    loom::model(|| {
+        let refs = RefCount::new();
+        assert_eq!(1, refs.count());
+
+        refs.increment();
+        assert_eq!(2, refs.count());
+
+        assert!(!refs.decrement());
+        assert!(refs.decrement());
+        assert_eq!(0, refs.count());
+    });
+}
+
+#[test]
+#[should_panic(expected = "released a reference that was not held")]
+fn ref_count_release_without_reference() {
+    loom::model(|| {
+        let refs = RefCount::new();
+        refs.decrement();
+        refs.decrement();
+    });
+}
@@ -0,0 +1,71 @@
+#![deny(warnings, rust_2018_idioms)]
+
+mod support;
+
+use loom::sync::atomic::{fence, AtomicUsize};
+use loom::sync::Arc;
+use loom::thread;
+
+use std::sync::atomic::AtomicU32;
+use std::sync::atomic::Ordering::{self, Acquire, Relaxed, Release};
+
+static DECREMENTED_AT: AtomicU32 = AtomicU32::new(0);
+
+/// Checks the model with `refcount_lint` enabled, returning the emitted
+/// warnings.
+fn check_warnings<F>(f: F) -> String
+where
+    F: Fn() + Sync + Send + 'static,
+{
+    support::check_warnings(
+        |builder| {
+            builder.refcount_lint(true);
+        },
+        f,
+    )
+}
+
+/// Reference counted data, decrementing the count with `order`. The data is
+/// not tracked by loom, so freeing it too early goes unnoticed.
+fn refcount(order: Ordering) {
+    let refs = Arc::new(AtomicUsize::new(2));
+    let freed = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+
+    let release = move |refs: &AtomicUsize| {
+        DECREMENTED_AT.store(line!() + 1, Relaxed);
+        if refs.fetch_sub(1, order) == 1 {
+            fence(Acquire);
+            assert!(!freed.swap(true, Relaxed));
+        }
+    };
+
+    let th = {
+        let (refs, release) = (refs.clone(), release.clone());
+        thread::spawn(move || release(&refs))
+    };
+
+    release(&refs);
+    th.join().unwrap();
+}
+
+#[test]
+fn relaxed_decrement_is_flagged() {
+    let output = check_warnings(|| refcount(Relaxed));
+
+    assert!(
+        output.contains(&format!(
+            "`fetch_sub` at {}:{}:{} decremented an atomic to zero without `Release` ordering",
+            file!(),
+            DECREMENTED_AT.load(Relaxed),
+            17
+        )),
+        "{}",
+        output
+    );
+}
+
+#[test]
+fn release_decrement_is_not_flagged() {
+    let output = check_warnings(|| refcount(Release));
+    assert_eq!("", output);
+}
@@ -2,6 +2,8 @@ use loom::sync::{Arc, RwLock, TryLockResult};
 use loom::thread;
 
 use std::rc::Rc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::SeqCst;
 use std::sync::TryLockError;
 
 #[test]
@@ -94,7 +96,7 @@ fn rwlock_read_then_try_write() {
     loom::model(|| {
         let lock = Arc::new(RwLock::new(1));
 
-        let _l1 = lock.write().unwrap();
+        let _l1 = lock.read().unwrap();
 
         assert!(matches!(
             lock.try_write(),
@@ -162,3 +164,128 @@ fn rwlock_into_inner() {
         assert_eq!(lock, 2);
     })
 }
+
+#[test]
+fn rwlock_reader_count() {
+    loom::model(|| {
+        let lock = RwLock::new(1);
+        assert_eq!(0, lock.reader_count());
+
+        let a = lock.read().unwrap();
+        let b = lock.read().unwrap();
+        assert_eq!(2, lock.reader_count());
+        assert!(!lock.is_write_locked());
+
+        drop(a);
+        assert_eq!(1, lock.reader_count());
+        assert!(lock.try_write().is_err());
+
+        drop(b);
+        assert_eq!(0, lock.reader_count());
+
+        let _w = lock.write().unwrap();
+        assert_eq!(0, lock.reader_count());
+        assert!(lock.is_write_locked());
+    });
+}
+
+#[test]
+fn rwlock_reader_count_across_threads() {
+    loom::model(|| {
+        let lock = Arc::new(RwLock::new(1));
+        let c_lock = lock.clone();
+
+        let guard = lock.read().unwrap();
+
+        thread::spawn(move || {
+            let _guard = c_lock.read().unwrap();
+            assert_eq!(2, c_lock.reader_count());
+        })
+        .join()
+        .unwrap();
+
+        assert_eq!(1, lock.reader_count());
+        drop(guard);
+    });
+}
+
+#[test]
+fn rwlock_try_write_racing_read() {
+    static ACQUIRED: AtomicBool = AtomicBool::new(false);
+    static WOULD_BLOCK: AtomicBool = AtomicBool::new(false);
+
+    loom::model(|| {
+        let lock = Arc::new(RwLock::new(0));
+
+        let th = {
+            let lock = lock.clone();
+            thread::spawn(move || {
+                let n = lock.read().unwrap();
+                thread::yield_now();
+                *n
+            })
+        };
+
+        match lock.try_write() {
+            Ok(mut n) => {
+                ACQUIRED.store(true, SeqCst);
+                *n = 1;
+            }
+            Err(TryLockError::WouldBlock) => {
+                WOULD_BLOCK.store(true, SeqCst);
+            }
+            Err(TryLockError::Poisoned(_)) => unreachable!(),
+        }
+
+        th.join().unwrap();
+    });
+
+    assert!(ACQUIRED.load(SeqCst));
+    assert!(WOULD_BLOCK.load(SeqCst));
+}
+
+#[test]
+fn rwlock_get_mut() {
+    loom::model(|| {
+        let lock = Rc::new(RwLock::new(0));
+
+        let th = {
+            let lock = lock.clone();
+            thread::spawn(move || *lock.write().unwrap() += 1)
+        };
+
+        th.join().unwrap();
+
+        let mut lock = Rc::try_unwrap(lock).unwrap();
+        *lock.get_mut().unwrap() += 1;
+        assert_eq!(lock.into_inner().unwrap(), 2);
+    })
+}
+
+#[test]
+#[should_panic(expected = "Causality violation: Concurrent lock and exclusive accesses.")]
+fn rwlock_get_mut_racing_read() {
+    struct Ptr(*mut RwLock<usize>);
+
+    unsafe impl Send for Ptr {}
+
+    loom::model(|| {
+        let lock = Box::into_raw(Box::new(RwLock::new(0)));
+
+        let th = {
+            let ptr = Ptr(lock);
+
+            thread::spawn(move || {
+                let ptr = ptr;
+                let _ = *unsafe { &*ptr.0 }.read().unwrap();
+            })
+        };
+
+        // The thread is not joined, so the rwlock is not exclusively owned.
+        thread::yield_now();
+        *unsafe { &mut *lock }.get_mut().unwrap() += 1;
+
+        th.join().unwrap();
+        drop(unsafe { Box::from_raw(lock) });
+    })
+}
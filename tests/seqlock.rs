@@ -0,0 +1,153 @@
+#![deny(warnings, rust_2018_idioms)]
+
+use loom::cell::UnsafeCell;
+use loom::sync::atomic::AtomicUsize;
+use loom::sync::Arc;
+use loom::thread;
+
+use std::sync::atomic::Ordering::SeqCst;
+
+struct SeqLock {
+    seq: AtomicUsize,
+    data: UnsafeCell<(usize, usize)>,
+}
+
+unsafe impl Sync for SeqLock {}
+
+impl SeqLock {
+    fn new() -> SeqLock {
+        SeqLock {
+            seq: AtomicUsize::new(0),
+            data: UnsafeCell::new((0, 0)),
+        }
+    }
+
+    fn write(&self, value: usize) {
+        self.seq.fetch_add(1, SeqCst);
+        self.data.with_mut(|ptr| unsafe { *ptr = (value, value) });
+        self.seq.fetch_add(1, SeqCst);
+    }
+}
+
+fn check<F>(read: F)
+where
+    F: Fn(&SeqLock) -> Option<(usize, usize)> + Sync + Send + 'static,
+{
+    loom::model(move || {
+        let lock = Arc::new(SeqLock::new());
+
+        let th = {
+            let lock = lock.clone();
+            thread::spawn(move || lock.write(1))
+        };
+
+        if let Some((a, b)) = read(&lock) {
+            assert_eq!(a, b);
+        }
+
+        th.join().unwrap();
+    });
+}
+
+#[test]
+fn optimistic_read_validated_by_version() {
+    check(|lock| {
+        let version = lock.seq.load(SeqCst);
+        let read = lock.data.with_optimistic(|ptr| unsafe { *ptr });
+
+        if version % 2 == 0 && lock.seq.load(SeqCst) == version {
+            Some(read.validate())
+        } else {
+            None
+        }
+    });
+}
+
+#[test]
+#[should_panic(expected = "Causality violation")]
+fn optimistic_read_without_revalidation() {
+    check(|lock| {
+        let version = lock.seq.load(SeqCst);
+        let read = lock.data.with_optimistic(|ptr| unsafe { *ptr });
+        let _ = lock.seq.load(SeqCst);
+
+        // The read is used without checking that the version did not change
+        if version % 2 == 0 {
+            Some(read.validate())
+        } else {
+            None
+        }
+    });
+}
+
+#[test]
+#[should_panic(expected = "Causality violation")]
+fn checked_read_races_with_write() {
+    check(|lock| {
+        let version = lock.seq.load(SeqCst);
+        let value = lock.data.with(|ptr| unsafe { *ptr });
+
+        if version % 2 == 0 && lock.seq.load(SeqCst) == version {
+            Some(value)
+        } else {
+            None
+        }
+    });
+}
+
+/// A seqlock protecting two cells, written one after the other.
+struct SeqLock2 {
+    seq: AtomicUsize,
+    a: UnsafeCell<usize>,
+    b: UnsafeCell<usize>,
+}
+
+unsafe impl Sync for SeqLock2 {}
+
+fn check_joined(revalidate: bool) {
+    loom::model(move || {
+        let lock = Arc::new(SeqLock2 {
+            seq: AtomicUsize::new(0),
+            a: UnsafeCell::new(0),
+            b: UnsafeCell::new(0),
+        });
+
+        let th = {
+            let lock = lock.clone();
+
+            thread::spawn(move || {
+                lock.seq.fetch_add(1, SeqCst);
+                lock.a.with_mut(|ptr| unsafe { *ptr = 1 });
+                lock.b.with_mut(|ptr| unsafe { *ptr = 1 });
+                lock.seq.fetch_add(1, SeqCst);
+            })
+        };
+
+        let version = lock.seq.load(SeqCst);
+        let read = lock
+            .a
+            .with_optimistic(|ptr| unsafe { *ptr })
+            .join(lock.b.with_optimistic(|ptr| unsafe { *ptr }));
+
+        let unchanged = lock.seq.load(SeqCst) == version;
+        let valid = unchanged || !revalidate;
+
+        if version % 2 == 0 && valid {
+            let (a, b) = read.validate();
+            assert_eq!(a, b);
+        }
+
+        th.join().unwrap();
+    });
+}
+
+#[test]
+fn joined_optimistic_reads_validated_by_version() {
+    check_joined(true);
+}
+
+#[test]
+#[should_panic(expected = "Causality violation")]
+fn joined_optimistic_reads_without_revalidation() {
+    check_joined(false);
+}
@@ -0,0 +1,127 @@
+#![deny(warnings, rust_2018_idioms)]
+
+mod support;
+
+use loom::sync::atomic::AtomicUsize;
+use loom::sync::{Arc, Mutex};
+use loom::thread;
+
+use std::sync::atomic::AtomicU32;
+use std::sync::atomic::Ordering::{Relaxed, SeqCst};
+
+/// Checks the model with `report_single_thread_objects` enabled, returning
+/// the emitted warnings.
+fn check_warnings<F>(f: F) -> String
+where
+    F: Fn() + Sync + Send + 'static,
+{
+    support::check_warnings(
+        |builder| {
+            builder.report_single_thread_objects(true);
+        },
+        f,
+    )
+}
+
+#[test]
+fn reports_object_accessed_by_main_thread_only() {
+    static CREATED_AT: AtomicU32 = AtomicU32::new(0);
+
+    let warnings = check_warnings(|| {
+        let shared = Arc::new(AtomicUsize::new(0));
+        let lock = Arc::new(Mutex::new(0));
+        CREATED_AT.store(line!() + 1, Relaxed);
+        let local = AtomicUsize::new(0);
+
+        let th = {
+            let shared = shared.clone();
+            let lock = lock.clone();
+
+            thread::spawn(move || {
+                shared.fetch_add(1, SeqCst);
+                *lock.lock().unwrap() += 1;
+            })
+        };
+
+        shared.fetch_add(1, SeqCst);
+        *lock.lock().unwrap() += 1;
+        local.store(1, SeqCst);
+
+        th.join().unwrap();
+    });
+
+    let reported: Vec<_> = warnings.lines().collect();
+
+    assert_eq!(1, reported.len(), "{}", warnings);
+    assert!(
+        reported[0].contains(&format!(
+            "atomic created at {}:{}:",
+            file!(),
+            CREATED_AT.load(Relaxed)
+        )),
+        "{}",
+        warnings
+    );
+}
+
+#[test]
+fn per_thread_objects_are_reported() {
+    static CREATED_AT: AtomicU32 = AtomicU32::new(0);
+
+    let warnings = check_warnings(|| {
+        let ths: Vec<_> = (0..2)
+            .map(|_| {
+                thread::spawn(|| {
+                    CREATED_AT.store(line!() + 1, Relaxed);
+                    let lock = Mutex::new(0);
+                    *lock.lock().unwrap() += 1;
+                })
+            })
+            .collect();
+
+        for th in ths {
+            th.join().unwrap();
+        }
+    });
+
+    assert!(
+        warnings.contains(&format!(
+            "mutex created at {}:{}:",
+            file!(),
+            CREATED_AT.load(Relaxed)
+        )),
+        "{}",
+        warnings
+    );
+}
+
+#[test]
+#[cfg(feature = "large_models")]
+fn object_accessed_by_more_than_eight_threads() {
+    let warnings = support::check_warnings(
+        |builder| {
+            builder
+                .report_single_thread_objects(true)
+                .max_threads(10)
+                .preemption_bound(Some(0));
+        },
+        || {
+            let num = Arc::new(AtomicUsize::new(0));
+
+            let ths: Vec<_> = (0..9)
+                .map(|_| {
+                    let num = num.clone();
+                    thread::spawn(move || num.fetch_add(1, SeqCst))
+                })
+                .collect();
+
+            for th in ths {
+                th.join().unwrap();
+            }
+
+            assert_eq!(9, num.load(SeqCst));
+        },
+    );
+
+    assert_eq!("", warnings);
+}
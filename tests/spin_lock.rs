@@ -0,0 +1,81 @@
+#![deny(warnings, rust_2018_idioms)]
+
+use loom::model::Builder;
+use loom::sync::{Arc, SpinLock};
+use loom::thread;
+
+#[test]
+fn spin_lock_mutual_exclusion() {
+    let mut builder = Builder::new();
+    builder.max_branches(100);
+    builder.check(|| {
+        let lock = Arc::new(SpinLock::new(0));
+
+        let ths: Vec<_> = (0..2)
+            .map(|_| {
+                let lock = lock.clone();
+                thread::spawn(move || {
+                    let mut guard = lock.lock();
+                    let curr = *guard;
+                    thread::yield_now();
+                    *guard = curr + 1;
+                })
+            })
+            .collect();
+
+        for th in ths {
+            th.join().unwrap();
+        }
+
+        assert_eq!(2, *lock.lock());
+    });
+}
+
+#[test]
+fn spin_lock_counter_bounded_branches() {
+    let mut builder = Builder::new();
+    builder.max_branches(50);
+    let stats = builder.check(|| {
+        let lock = Arc::new(SpinLock::new(0));
+
+        let ths: Vec<_> = (0..2)
+            .map(|_| {
+                let lock = lock.clone();
+                thread::spawn(move || *lock.lock() += 1)
+            })
+            .collect();
+
+        for th in ths {
+            th.join().unwrap();
+        }
+
+        assert_eq!(2, Arc::try_unwrap(lock).unwrap().into_inner());
+    });
+
+    assert!(stats.complete);
+}
+
+#[test]
+fn spin_lock_try_lock() {
+    loom::model(|| {
+        let lock = Arc::new(SpinLock::new(0));
+
+        let th = {
+            let lock = lock.clone();
+            thread::spawn(move || {
+                if let Some(mut guard) = lock.try_lock() {
+                    *guard += 1;
+                }
+            })
+        };
+
+        {
+            let guard = lock.lock();
+            assert!(lock.try_lock().is_none());
+            drop(guard);
+        }
+
+        th.join().unwrap();
+        assert!(*lock.lock() <= 1);
+    });
+}
@@ -0,0 +1,62 @@
+//! Helpers shared by the tests, included with `mod support;`.
+
+// Each test only uses some of the helpers
+#![allow(dead_code)]
+
+use loom::model::Builder;
+
+use std::io;
+use std::sync::{Arc, Mutex};
+
+/// Output of a `tracing` subscriber, collected in memory.
+#[derive(Clone, Default)]
+pub struct Output(Arc<Mutex<Vec<u8>>>);
+
+impl Output {
+    /// Returns the output collected so far.
+    pub fn contents(&self) -> String {
+        String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+    }
+}
+
+impl io::Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Runs `f` with a `tracing` subscriber collecting the events up to `level`,
+/// returning the value returned by `f` and the collected output.
+pub fn capture<R>(level: tracing::Level, f: impl FnOnce() -> R) -> (R, String) {
+    let output = Output::default();
+    let writer = output.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_writer(move || writer.clone())
+        .without_time()
+        .with_ansi(false)
+        .finish();
+
+    let ret = tracing::subscriber::with_default(subscriber, f);
+    (ret, output.contents())
+}
+
+/// Checks the model with the `Builder` set up by `configure`, returning the
+/// emitted warnings.
+pub fn check_warnings<F>(configure: impl FnOnce(&mut Builder), f: F) -> String
+where
+    F: Fn() + Sync + Send + 'static,
+{
+    let (_, warnings) = capture(tracing::Level::WARN, || {
+        let mut builder = Builder::new();
+        configure(&mut builder);
+        builder.check(f);
+    });
+
+    warnings
+}
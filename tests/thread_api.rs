@@ -75,6 +75,51 @@ fn threads_have_unique_ids() {
     })
 }
 
+#[test]
+fn thread_ids_as_map_keys() {
+    use loom::sync::{Arc, Mutex};
+    use std::collections::HashMap;
+
+    loom::model(|| {
+        let counts = Arc::new(Mutex::new(HashMap::new()));
+
+        let ths: Vec<_> = (1..=2)
+            .map(|n| {
+                let counts = counts.clone();
+
+                thread::spawn(move || {
+                    for _ in 0..n {
+                        *counts
+                            .lock()
+                            .unwrap()
+                            .entry(thread::current().id())
+                            .or_insert(0) += 1;
+                    }
+                })
+            })
+            .collect();
+
+        *counts
+            .lock()
+            .unwrap()
+            .entry(thread::current().id())
+            .or_insert(0) += 3;
+
+        let ids: Vec<_> = ths.iter().map(|th| th.thread().id()).collect();
+
+        for th in ths {
+            th.join().unwrap();
+        }
+
+        let counts = counts.lock().unwrap();
+
+        assert_eq!(3, counts.len());
+        assert_eq!(Some(&1), counts.get(&ids[0]));
+        assert_eq!(Some(&2), counts.get(&ids[1]));
+        assert_eq!(Some(&3), counts.get(&thread::current().id()));
+    })
+}
+
 #[test]
 fn thread_names() {
     loom::model(|| {
@@ -115,7 +160,7 @@ fn thread_stack_size() {
         thread::Builder::new()
             .stack_size(
                 // Include space for function calls in addition to the array.
-                2 * STACK_SIZE,
+                2 * STACK_SIZE * std::mem::size_of::<usize>(),
             )
             .spawn(body)
             .unwrap()
@@ -124,6 +169,42 @@ fn thread_stack_size() {
     })
 }
 
+#[test]
+fn thread_stack_size_deep_recursion() {
+    fn recurse(depth: usize) -> usize {
+        // Use some stack in each frame.
+        let frame = std::hint::black_box([depth; 64]);
+
+        if depth == 0 {
+            0
+        } else {
+            frame[0] + recurse(depth - 1) - depth + 1
+        }
+    }
+
+    loom::model(|| {
+        let th = thread::Builder::new()
+            .stack_size(16 << 20)
+            .spawn(|| recurse(10_000))
+            .unwrap();
+
+        assert_eq!(10_000, th.join().unwrap());
+    })
+}
+
+#[test]
+fn thread_stack_size_below_minimum() {
+    loom::model(|| {
+        // Rounded up to the default stack size of loom threads.
+        let th = thread::Builder::new()
+            .stack_size(1)
+            .spawn(|| std::hint::black_box([1usize; 512]).iter().sum::<usize>())
+            .unwrap();
+
+        assert_eq!(512, th.join().unwrap());
+    })
+}
+
 #[test]
 fn park_unpark_loom() {
     loom::model(|| {
@@ -143,3 +224,87 @@ fn park_unpark_std() {
     std::thread::park();
     println!("it did not deadlock");
 }
+
+#[test]
+fn join_returns_thread_result() {
+    loom::model(|| {
+        let ok = thread::Builder::new()
+            .spawn(|| -> Result<usize, String> { Ok(1) })
+            .unwrap();
+        let err = thread::Builder::new()
+            .spawn(|| -> Result<usize, String> { Err("failed".to_string()) })
+            .unwrap();
+
+        assert_eq!(Ok(1), ok.join().unwrap());
+        assert_eq!(Err("failed".to_string()), err.join().unwrap());
+    });
+}
+
+#[test]
+#[should_panic(expected = "thread panicked")]
+fn panicking_thread_fails_model() {
+    loom::model(|| {
+        let th = thread::spawn(|| -> Result<usize, String> { panic!("thread panicked") });
+
+        let _ = th.join();
+    });
+}
+
+#[test]
+fn available_parallelism_fits_max_threads() {
+    use loom::model::Builder;
+
+    for (max_threads, expect) in [(5, 4), (2, 1), (1, 1)] {
+        let mut builder = Builder::new();
+        builder.max_threads(max_threads);
+        builder.check(move || {
+            let parallelism = thread::available_parallelism().unwrap().get();
+            assert_eq!(expect, parallelism);
+
+            // A pool sized with it fits in the model
+            if max_threads > 1 {
+                let workers: Vec<_> = (0..parallelism).map(|_| thread::spawn(|| {})).collect();
+
+                for worker in workers {
+                    worker.join().unwrap();
+                }
+            }
+        });
+    }
+}
+
+#[test]
+#[should_panic(expected = "loom supports at most")]
+fn max_threads_above_limit() {
+    let mut builder = loom::model::Builder::new();
+    builder.max_threads(loom::MAX_THREADS + 1);
+    builder.check(|| {});
+}
+
+#[test]
+#[cfg(feature = "large_models")]
+fn large_models_main_and_five_workers() {
+    use loom::sync::atomic::AtomicUsize;
+    use loom::sync::Arc;
+    use std::sync::atomic::Ordering::Relaxed;
+
+    let mut builder = loom::model::Builder::new();
+    builder.max_threads(6);
+    builder.preemption_bound(Some(1));
+    builder.check(|| {
+        let num = Arc::new(AtomicUsize::new(0));
+
+        let workers: Vec<_> = (0..5)
+            .map(|_| {
+                let num = num.clone();
+                thread::spawn(move || num.fetch_add(1, Relaxed))
+            })
+            .collect();
+
+        for worker in workers {
+            worker.join().unwrap();
+        }
+
+        assert_eq!(5, num.load(Relaxed));
+    });
+}
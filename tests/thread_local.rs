@@ -107,3 +107,43 @@ fn drop() {
     // should also be dropped.
     assert_eq!(DROPS.load(Ordering::Acquire), 3);
 }
+
+#[test]
+fn drop_observed_after_join() {
+    use loom::sync::atomic::AtomicUsize;
+
+    loom::lazy_static! {
+        static ref LIVE: AtomicUsize = AtomicUsize::new(0);
+    }
+
+    struct Guard;
+
+    impl Guard {
+        fn new() -> Self {
+            LIVE.fetch_add(1, Ordering::Relaxed);
+            Guard
+        }
+    }
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            LIVE.fetch_sub(1, Ordering::Release);
+        }
+    }
+
+    loom::thread_local! {
+        static GUARD: Guard = Guard::new();
+    }
+
+    loom::model(|| {
+        let th = thread::spawn(|| {
+            GUARD.with(|_| {});
+            assert_eq!(LIVE.load(Ordering::Relaxed), 1);
+        });
+
+        th.join().unwrap();
+
+        // The thread-local destructor ran before `join` returned.
+        assert_eq!(LIVE.load(Ordering::Acquire), 0);
+    });
+}
@@ -0,0 +1,125 @@
+#![deny(warnings, rust_2018_idioms)]
+
+use loom::sync::atomic::{AtomicBool, AtomicUsize};
+use loom::thread;
+
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::Ordering::Relaxed;
+
+#[test]
+fn scoped_thread_returns_borrowed_data() {
+    loom::model(|| {
+        let words = [String::from("loom"), String::from("scope")];
+
+        let longest: &String = thread::scope(|s| {
+            let th = s.spawn(|| words.iter().max_by_key(|w| w.len()).unwrap());
+            th.join().unwrap()
+        });
+
+        // The reference borrows `words`, and outlives the scope
+        assert!(std::ptr::eq(&words[1], longest));
+        assert_eq!("scope", longest);
+    });
+}
+
+#[test]
+fn scope_joins_remaining_threads() {
+    loom::model(|| {
+        let num = AtomicUsize::new(0);
+
+        thread::scope(|s| {
+            for _ in 0..2 {
+                s.spawn(|| num.fetch_add(1, Relaxed));
+            }
+        });
+
+        assert_eq!(2, num.load(Relaxed));
+    });
+}
+
+#[test]
+fn scoped_threads_spawn_in_scope() {
+    loom::model(|| {
+        let num = AtomicUsize::new(0);
+
+        thread::scope(|s| {
+            s.spawn(|| {
+                s.spawn(|| num.fetch_add(1, Relaxed));
+                num.fetch_add(1, Relaxed);
+            });
+        });
+
+        assert_eq!(2, num.load(Relaxed));
+    });
+}
+
+#[test]
+fn builder_spawn_scoped() {
+    loom::model(|| {
+        let num = AtomicUsize::new(0);
+
+        let name = thread::scope(|s| {
+            let th = thread::Builder::new()
+                .name("scoped".into())
+                .spawn_scoped(s, || {
+                    num.store(1, Relaxed);
+                    thread::current().name().map(String::from)
+                })
+                .unwrap();
+
+            th.join().unwrap()
+        });
+
+        assert_eq!(Some("scoped"), name.as_deref());
+        assert_eq!(1, num.load(Relaxed));
+    });
+}
+
+#[test]
+fn scope_waits_for_threads_before_propagating_panic() {
+    loom::model(|| {
+        let done = AtomicBool::new(false);
+
+        let res = panic::catch_unwind(AssertUnwindSafe(|| {
+            thread::scope(|s| {
+                s.spawn(|| panic!("scoped thread panicked"));
+                s.spawn(|| {
+                    thread::yield_now();
+                    done.store(true, Relaxed);
+                });
+            })
+        }));
+
+        let payload = res.unwrap_err();
+        assert_eq!(
+            Some(&"scoped thread panicked"),
+            payload.downcast_ref::<&str>()
+        );
+        assert!(done.load(Relaxed));
+    });
+}
+
+#[test]
+#[should_panic(expected = "scoped thread panicked")]
+fn scoped_thread_panic_fails_model() {
+    loom::model(|| {
+        thread::scope(|s| {
+            s.spawn(|| panic!("scoped thread panicked"));
+        });
+    });
+}
+
+#[test]
+#[should_panic(expected = "scoped thread panicked")]
+fn joining_panicked_scoped_thread_fails_model() {
+    loom::model(|| {
+        let num = AtomicUsize::new(0);
+
+        thread::scope(|s| {
+            let th = s.spawn(|| -> usize { panic!("scoped thread panicked") });
+            s.spawn(|| num.fetch_add(1, Relaxed));
+
+            let _ = th.join();
+        });
+    });
+}
@@ -0,0 +1,59 @@
+#![deny(warnings, rust_2018_idioms)]
+
+mod support;
+
+use loom::sync::atomic::AtomicUsize;
+use loom::sync::Arc;
+use loom::thread;
+
+use std::collections::HashSet;
+use std::sync::atomic::AtomicU32;
+use std::sync::atomic::Ordering::{Relaxed, SeqCst};
+
+#[test]
+fn atomic_label_is_stable_across_iterations() {
+    static CREATED_AT: AtomicU32 = AtomicU32::new(0);
+
+    let (_, output) = support::capture(tracing::Level::TRACE, || {
+        loom::model::Builder::new().check(|| {
+            let num = Arc::new(AtomicUsize::new(0));
+
+            let th = {
+                let num = num.clone();
+                thread::spawn(move || num.store(1, SeqCst))
+            };
+
+            // Creating an extra atomic in some iterations only changes the
+            // index of the next one in the object store.
+            let _extra = (num.load(SeqCst) == 1).then(|| AtomicUsize::new(0));
+            CREATED_AT.store(line!() + 1, Relaxed);
+            let tracked = AtomicUsize::new(0);
+            tracked.store(1, SeqCst);
+
+            th.join().unwrap();
+        });
+    });
+
+    let label = format!("atomic=Atomic@{}:{}", file!(), CREATED_AT.load(Relaxed));
+
+    // The state of `tracked` is not at the same index in every iteration
+    let states: HashSet<_> = output
+        .lines()
+        .filter(|line| line.contains("Atomic::new") && line.contains(&label))
+        .map(|line| line.split("state=").nth(1).unwrap().to_string())
+        .collect();
+
+    assert_eq!(2, states.len(), "{}", output);
+
+    let iterations = output
+        .lines()
+        .filter(|line| line.contains("Atomic::new") && line.contains(&label))
+        .count();
+
+    let stores = output
+        .lines()
+        .filter(|line| line.contains("Atomic::store") && line.contains(&label))
+        .count();
+
+    assert_eq!(iterations, stores);
+}
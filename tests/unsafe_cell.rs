@@ -1,4 +1,5 @@
 #![deny(warnings, rust_2018_idioms)]
+#![allow(clippy::arc_with_non_send_sync)]
 
 use loom::cell::UnsafeCell;
 use loom::sync::atomic::AtomicUsize;
@@ -333,3 +334,37 @@ fn unsafe_cell_access_after_sync() {
         }
     });
 }
+
+#[test]
+fn unsafe_cell_borrow_guards() {
+    loom::model(|| {
+        let cell = UnsafeCell::new(1);
+
+        {
+            let guard = cell.borrow_mut();
+            unsafe { *guard.deref() += 1 };
+        }
+
+        let a = cell.borrow();
+        let b = cell.borrow();
+        assert_eq!(2, unsafe { *a.deref() });
+        assert_eq!(2, b.with(|ptr| unsafe { *ptr }));
+    });
+}
+
+#[test]
+#[should_panic]
+fn unsafe_cell_borrow_race_mut_immut() {
+    loom::model(|| {
+        let x = Arc::new(UnsafeCell::new(1));
+        let y = x.clone();
+
+        let th = thread::spawn(move || unsafe { *x.borrow_mut().deref() += 1 });
+
+        let guard = y.borrow();
+        assert!(unsafe { *guard.deref() } >= 1);
+        drop(guard);
+
+        th.join().unwrap();
+    });
+}
@@ -0,0 +1,78 @@
+#![deny(warnings, rust_2018_idioms)]
+
+mod support;
+
+use loom::sync::atomic::AtomicUsize;
+use loom::sync::Arc;
+use loom::thread;
+
+use std::sync::atomic::Ordering::{Relaxed, SeqCst};
+use std::sync::atomic::{AtomicU32, AtomicUsize as StdAtomicUsize};
+
+/// Checks the model with `check_update_purity` enabled, returning the emitted
+/// warnings.
+fn check_warnings<F>(f: F) -> String
+where
+    F: Fn() + Sync + Send + 'static,
+{
+    support::check_warnings(
+        |builder| {
+            builder.check_update_purity(true);
+        },
+        f,
+    )
+}
+
+static UPDATED_AT: AtomicU32 = AtomicU32::new(0);
+
+/// Two threads updating the atomic with `f`.
+fn update(f: fn(usize) -> Option<usize>) {
+    let num = Arc::new(AtomicUsize::new(0));
+
+    let th = {
+        let num = num.clone();
+        thread::spawn(move || num.fetch_update(SeqCst, SeqCst, f))
+    };
+
+    UPDATED_AT.store(line!() + 1, Relaxed);
+    let _ = num.fetch_update(SeqCst, SeqCst, f);
+    let _ = th.join().unwrap();
+}
+
+#[test]
+fn side_effect_is_flagged() {
+    static CALLS: StdAtomicUsize = StdAtomicUsize::new(0);
+
+    let output = check_warnings(|| {
+        update(|v| Some(v + CALLS.fetch_add(1, Relaxed)));
+    });
+
+    assert!(
+        output.contains(&format!(
+            "the function passed to `fetch_update` at {}:{}:{} returned different values for \
+             the same input",
+            file!(),
+            UPDATED_AT.load(Relaxed),
+            17
+        )),
+        "{}",
+        output
+    );
+}
+
+#[test]
+fn pure_function_is_not_flagged() {
+    let output = check_warnings(|| update(|v| Some(v + 1)));
+    assert_eq!("", output);
+}
+
+#[test]
+fn pure_function_updates_once() {
+    loom::model::Builder::new()
+        .check_update_purity(true)
+        .check(|| {
+            let num = AtomicUsize::new(1);
+            assert_eq!(Ok(1), num.fetch_update(SeqCst, SeqCst, |v| Some(v * 2)));
+            assert_eq!(2, num.load(SeqCst));
+        });
+}
@@ -1,10 +1,12 @@
 #![deny(warnings, rust_2018_idioms)]
 
+use loom::model::Builder;
 use loom::sync::atomic::AtomicUsize;
 use loom::thread;
 
-use std::sync::atomic::Ordering::Relaxed;
+use std::sync::atomic::Ordering::{Acquire, Relaxed, Release, SeqCst};
 use std::sync::Arc;
+use std::time::Duration;
 
 #[test]
 fn yield_completes() {
@@ -27,3 +29,222 @@ fn yield_completes() {
         }
     });
 }
+
+#[test]
+fn yield_now_checked_reports_switches() {
+    loom::model(|| {
+        let done = Arc::new(AtomicUsize::new(0));
+
+        let th = {
+            let done = done.clone();
+            thread::spawn(move || done.store(1, SeqCst))
+        };
+
+        // The spawned thread is runnable until it stores `1`
+        while 1 != done.load(SeqCst) {
+            assert!(thread::yield_now_checked());
+        }
+
+        th.join().unwrap();
+
+        // Only the main thread is left
+        assert!(!thread::yield_now_checked());
+    });
+}
+
+/// Two threads increment a counter with a CAS loop, spinning with exponential
+/// backoff before each attempt, checked with few branches per permutation.
+fn backoff_increments(spin: fn()) {
+    let mut builder = loom::model::Builder::new();
+    builder.max_branches = 50;
+
+    builder.check(move || {
+        let num = Arc::new(AtomicUsize::new(0));
+
+        let ths: Vec<_> = (0..2)
+            .map(|_| {
+                let num = num.clone();
+
+                thread::spawn(move || {
+                    let mut backoff = 16;
+
+                    loop {
+                        for _ in 0..backoff {
+                            spin();
+                        }
+
+                        let cur = num.load(Relaxed);
+
+                        if num.compare_exchange(cur, cur + 1, Relaxed, Relaxed).is_ok() {
+                            return;
+                        }
+
+                        backoff *= 2;
+                    }
+                })
+            })
+            .collect();
+
+        for th in ths {
+            th.join().unwrap();
+        }
+
+        assert_eq!(2, num.load(Relaxed));
+    });
+}
+
+#[test]
+fn spin_loop_collapses_backoff() {
+    // The spins between two accesses only yield once
+    backoff_increments(loom::hint::spin_loop);
+}
+
+#[test]
+#[should_panic(expected = "Model exceeded maximum number of branches")]
+fn yield_now_backoff_exceeds_max_branches() {
+    backoff_increments(thread::yield_now);
+}
+
+#[test]
+#[should_panic(expected = "lost an increment")]
+fn spin_loop_backoff_finds_lost_update() {
+    loom::model(|| {
+        let num = Arc::new(AtomicUsize::new(0));
+
+        let ths: Vec<_> = (0..2)
+            .map(|_| {
+                let num = num.clone();
+
+                thread::spawn(move || {
+                    let cur = num.load(Relaxed);
+
+                    for _ in 0..4 {
+                        loom::hint::spin_loop();
+                    }
+
+                    // Not atomic with the load
+                    num.store(cur + 1, Relaxed);
+                })
+            })
+            .collect();
+
+        for th in ths {
+            th.join().unwrap();
+        }
+
+        assert_eq!(2, num.load(Relaxed), "lost an increment");
+    });
+}
+
+#[test]
+#[should_panic(expected = "Lock-freedom violation")]
+fn check_lock_freedom_finds_livelock() {
+    let mut builder = Builder::new();
+    builder.check_lock_freedom(true);
+    builder.check(|| {
+        // Each thread waits for the other one to hand the value over first.
+        let x = Arc::new(AtomicUsize::new(2));
+
+        let ths: Vec<_> = (0..2)
+            .map(|me| {
+                let x = x.clone();
+                thread::spawn(move || {
+                    while x.compare_exchange(me, 1 - me, SeqCst, SeqCst).is_err() {
+                        thread::yield_now();
+                    }
+                })
+            })
+            .collect();
+
+        for th in ths {
+            th.join().unwrap();
+        }
+    });
+}
+
+#[test]
+fn check_lock_freedom_passes_cas_loop() {
+    let mut builder = Builder::new();
+    builder.check_lock_freedom(true);
+    builder.check(|| {
+        let x = Arc::new(AtomicUsize::new(0));
+
+        let ths: Vec<_> = (0..2)
+            .map(|_| {
+                let x = x.clone();
+                thread::spawn(move || {
+                    let mut curr = x.load(Relaxed);
+
+                    while let Err(actual) = x.compare_exchange(curr, curr + 1, SeqCst, Relaxed) {
+                        curr = actual;
+                        thread::yield_now();
+                    }
+                })
+            })
+            .collect();
+
+        for th in ths {
+            th.join().unwrap();
+        }
+
+        assert_eq!(2, x.load(Relaxed));
+    });
+}
+
+#[test]
+fn check_lock_freedom_passes_spin_wait() {
+    let mut builder = Builder::new();
+    builder.check_lock_freedom(true);
+    builder.check(|| {
+        let ready = Arc::new(AtomicUsize::new(0));
+
+        {
+            let ready = ready.clone();
+            thread::spawn(move || {
+                ready.store(1, Release);
+            });
+        }
+
+        while ready.load(Acquire) == 0 {
+            thread::yield_now();
+        }
+    });
+}
+
+#[test]
+fn sleep_yields() {
+    loom::model(|| {
+        let ready = Arc::new(AtomicUsize::new(0));
+
+        {
+            let ready = ready.clone();
+            thread::spawn(move || {
+                ready.store(1, Release);
+            });
+        }
+
+        // The duration is ignored, the thread only yields
+        while ready.load(Acquire) == 0 {
+            thread::sleep(Duration::from_secs(3600));
+        }
+    });
+}
+
+#[test]
+#[allow(deprecated)]
+fn sleep_ms_yields() {
+    loom::model(|| {
+        let ready = Arc::new(AtomicUsize::new(0));
+
+        {
+            let ready = ready.clone();
+            thread::spawn(move || {
+                ready.store(1, Release);
+            });
+        }
+
+        while ready.load(Acquire) == 0 {
+            thread::sleep_ms(1000);
+        }
+    });
+}